@@ -0,0 +1,246 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::ContractError;
+
+/// Number of accounts returned per page, if not specified
+pub const DEFAULT_ACCOUNT_ACTIVITY_PAGE_LIMIT: u32 = 30;
+
+/// Maximum number of accounts returned per page
+pub const MAX_ACCOUNT_ACTIVITY_PAGE_LIMIT: u32 = 100;
+
+/// Kind of activity recorded for an account
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivityKind {
+    Join,
+    Exit,
+    Swap,
+}
+
+/// Lifetime aggregates of an account's activity. Values are of tokens in, in alloyed asset amount,
+/// so that values of different denoms are comparable.
+#[cw_serde]
+pub struct AccountActivity {
+    pub join_count: u64,
+    pub exit_count: u64,
+    pub swap_count: u64,
+    /// Value of tokens joined with
+    pub join_value: Uint128,
+    /// Value of alloyed asset exited with
+    pub exit_value: Uint128,
+    /// Value of tokens swapped in
+    pub swap_value: Uint128,
+    pub first_active_at: Timestamp,
+    pub last_active_at: Timestamp,
+}
+
+/// Per-account lifetime activity. Recording is opt-in via `account_activity` parameter since it
+/// adds an entry per account, records are kept but no longer updated while disabled.
+pub struct AccountActivities<'a> {
+    enabled: Item<'a, bool>,
+    activities: Map<'a, &'a Addr, AccountActivity>,
+}
+
+impl<'a> AccountActivities<'a> {
+    pub const fn new(enabled_namespace: &'a str, activities_namespace: &'a str) -> Self {
+        Self {
+            enabled: Item::new(enabled_namespace),
+            activities: Map::new(activities_namespace),
+        }
+    }
+
+    pub fn is_enabled(&self, storage: &dyn Storage) -> StdResult<bool> {
+        self.enabled
+            .may_load(storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    pub fn set_enabled(&self, storage: &mut dyn Storage, enabled: bool) -> StdResult<()> {
+        self.enabled.save(storage, &enabled)
+    }
+
+    /// Add activity of `kind` with `value` to `account` at `block_time`, if recording is enabled
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        account: &Addr,
+        kind: ActivityKind,
+        value: Uint128,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        if !self.is_enabled(storage)? {
+            return Ok(());
+        }
+
+        let mut activity = self
+            .activities
+            .may_load(storage, account)?
+            .unwrap_or_else(|| AccountActivity {
+                join_count: 0,
+                exit_count: 0,
+                swap_count: 0,
+                join_value: Uint128::zero(),
+                exit_value: Uint128::zero(),
+                swap_value: Uint128::zero(),
+                first_active_at: block_time,
+                last_active_at: block_time,
+            });
+
+        let (count, total_value) = match kind {
+            ActivityKind::Join => (&mut activity.join_count, &mut activity.join_value),
+            ActivityKind::Exit => (&mut activity.exit_count, &mut activity.exit_value),
+            ActivityKind::Swap => (&mut activity.swap_count, &mut activity.swap_value),
+        };
+        *count = count.saturating_add(1);
+        *total_value = total_value.checked_add(value)?;
+        activity.last_active_at = block_time;
+
+        self.activities.save(storage, account, &activity)?;
+
+        Ok(())
+    }
+
+    /// Activity of `account`, `None` if it has not been recorded
+    pub fn get(&self, storage: &dyn Storage, account: &Addr) -> StdResult<Option<AccountActivity>> {
+        self.activities.may_load(storage, account)
+    }
+
+    /// Up to `limit` accounts with their activity after `start_after`, in address order
+    pub fn list(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<&Addr>,
+        limit: u32,
+    ) -> StdResult<Vec<(Addr, AccountActivity)>> {
+        let limit = limit.min(MAX_ACCOUNT_ACTIVITY_PAGE_LIMIT) as usize;
+
+        self.activities
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_record() {
+        let mut deps = mock_dependencies();
+        let activities = AccountActivities::new("enabled", "activities");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let start = Timestamp::from_seconds(1000);
+
+        // not recorded while disabled
+        activities
+            .record(
+                &mut deps.storage,
+                &alice,
+                ActivityKind::Join,
+                100u128.into(),
+                start,
+            )
+            .unwrap();
+        assert_eq!(activities.get(&deps.storage, &alice).unwrap(), None);
+
+        activities.set_enabled(&mut deps.storage, true).unwrap();
+        activities
+            .record(
+                &mut deps.storage,
+                &alice,
+                ActivityKind::Join,
+                100u128.into(),
+                start,
+            )
+            .unwrap();
+        activities
+            .record(
+                &mut deps.storage,
+                &alice,
+                ActivityKind::Swap,
+                30u128.into(),
+                start.plus_seconds(10),
+            )
+            .unwrap();
+        activities
+            .record(
+                &mut deps.storage,
+                &alice,
+                ActivityKind::Swap,
+                20u128.into(),
+                start.plus_seconds(20),
+            )
+            .unwrap();
+        activities
+            .record(
+                &mut deps.storage,
+                &bob,
+                ActivityKind::Exit,
+                7u128.into(),
+                start.plus_seconds(30),
+            )
+            .unwrap();
+
+        let alice_activity = AccountActivity {
+            join_count: 1,
+            exit_count: 0,
+            swap_count: 2,
+            join_value: 100u128.into(),
+            exit_value: Uint128::zero(),
+            swap_value: 50u128.into(),
+            first_active_at: start,
+            last_active_at: start.plus_seconds(20),
+        };
+        let bob_activity = AccountActivity {
+            join_count: 0,
+            exit_count: 1,
+            swap_count: 0,
+            join_value: Uint128::zero(),
+            exit_value: 7u128.into(),
+            swap_value: Uint128::zero(),
+            first_active_at: start.plus_seconds(30),
+            last_active_at: start.plus_seconds(30),
+        };
+
+        assert_eq!(
+            activities.get(&deps.storage, &alice).unwrap(),
+            Some(alice_activity.clone())
+        );
+        assert_eq!(
+            activities.list(&deps.storage, None, 10).unwrap(),
+            vec![
+                (alice.clone(), alice_activity.clone()),
+                (bob.clone(), bob_activity.clone())
+            ]
+        );
+        assert_eq!(
+            activities.list(&deps.storage, Some(&alice), 10).unwrap(),
+            vec![(bob.clone(), bob_activity)]
+        );
+
+        // kept but no longer updated once disabled
+        activities.set_enabled(&mut deps.storage, false).unwrap();
+        activities
+            .record(
+                &mut deps.storage,
+                &alice,
+                ActivityKind::Exit,
+                1u128.into(),
+                start.plus_seconds(40),
+            )
+            .unwrap();
+        assert_eq!(
+            activities.get(&deps.storage, &alice).unwrap(),
+            Some(alice_activity)
+        );
+    }
+}
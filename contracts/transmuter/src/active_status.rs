@@ -0,0 +1,290 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, ensure_ne, StdResult, Storage, Timestamp};
+use cw_storage_plus::Item;
+
+use crate::ContractError;
+
+/// Machine-readable reason of pool deactivation, for downstream UIs to explain the outage
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum InactiveReason {
+    #[default]
+    Unspecified,
+    Maintenance,
+    Incident,
+    Upgrade,
+    /// Outflow of an asset exceeded the circuit breaker threshold
+    CircuitBreaker,
+    /// Tokenfactory admin of the alloyed denom has been claimed or handed over
+    AlloyedDenomAdminTransferred,
+}
+
+impl InactiveReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InactiveReason::Unspecified => "unspecified",
+            InactiveReason::Maintenance => "maintenance",
+            InactiveReason::Incident => "incident",
+            InactiveReason::Upgrade => "upgrade",
+            InactiveReason::CircuitBreaker => "circuit_breaker",
+            InactiveReason::AlloyedDenomAdminTransferred => "alloyed_denom_admin_transferred",
+        }
+    }
+}
+
+/// Why the pool is inactive, and when it reactivates by itself if at all
+#[cw_serde]
+pub struct Deactivation {
+    pub reason: InactiveReason,
+    /// Pool is active again from this time on, `None` if it stays inactive until reactivated
+    pub reactivate_at: Option<Timestamp>,
+}
+
+/// Active status of the pool. Deactivation can carry a reactivation time, past which the pool
+/// is active without having to be reactivated, so the effective status depends on block time.
+pub struct ActiveStatus<'a> {
+    active: Item<'a, bool>,
+    deactivation: Item<'a, Deactivation>,
+}
+
+impl<'a> ActiveStatus<'a> {
+    pub const fn new(active_namespace: &'a str, deactivation_namespace: &'a str) -> Self {
+        Self {
+            active: Item::new(active_namespace),
+            deactivation: Item::new(deactivation_namespace),
+        }
+    }
+
+    pub fn init(&self, storage: &mut dyn Storage) -> StdResult<()> {
+        self.active.save(storage, &true)
+    }
+
+    /// Whether the pool is active at `now`
+    pub fn is_active(&self, storage: &dyn Storage, now: Timestamp) -> StdResult<bool> {
+        Ok(self.deactivation(storage, now)?.is_none())
+    }
+
+    /// Deactivation in effect at `now`, `None` if the pool is active
+    pub fn deactivation(
+        &self,
+        storage: &dyn Storage,
+        now: Timestamp,
+    ) -> StdResult<Option<Deactivation>> {
+        if self.active.load(storage)? {
+            return Ok(None);
+        }
+
+        // deactivated before reasons were recorded
+        let deactivation = self
+            .deactivation
+            .may_load(storage)?
+            .unwrap_or(Deactivation {
+                reason: InactiveReason::Unspecified,
+                reactivate_at: None,
+            });
+
+        match deactivation.reactivate_at {
+            Some(reactivate_at) if reactivate_at <= now => Ok(None),
+            _ => Ok(Some(deactivation)),
+        }
+    }
+
+    /// Set active status at `now`, erroring if it is unchanged. `reason` and `reactivate_at`
+    /// only apply to deactivation, and `reactivate_at` must be in the future.
+    pub fn checked_set(
+        &self,
+        storage: &mut dyn Storage,
+        now: Timestamp,
+        active: bool,
+        reason: Option<InactiveReason>,
+        reactivate_at: Option<Timestamp>,
+    ) -> Result<(), ContractError> {
+        ensure_ne!(
+            self.is_active(storage, now)?,
+            active,
+            ContractError::UnchangedActiveStatus { status: active }
+        );
+
+        if active {
+            ensure!(
+                reason.is_none() && reactivate_at.is_none(),
+                ContractError::UnexpectedDeactivationOnActivation {}
+            );
+
+            self.active.save(storage, &true)?;
+            self.deactivation.remove(storage);
+
+            return Ok(());
+        }
+
+        if let Some(reactivate_at) = reactivate_at {
+            ensure!(
+                reactivate_at > now,
+                ContractError::InvalidReactivationTime { reactivate_at }
+            );
+        }
+
+        self.save_deactivation(
+            storage,
+            &Deactivation {
+                reason: reason.unwrap_or_default(),
+                reactivate_at,
+            },
+        )
+    }
+
+    /// Deactivate the pool for `reason` until reactivated, regardless of its current status
+    pub fn deactivate(
+        &self,
+        storage: &mut dyn Storage,
+        reason: InactiveReason,
+    ) -> Result<(), ContractError> {
+        self.save_deactivation(
+            storage,
+            &Deactivation {
+                reason,
+                reactivate_at: None,
+            },
+        )
+    }
+
+    fn save_deactivation(
+        &self,
+        storage: &mut dyn Storage,
+        deactivation: &Deactivation,
+    ) -> Result<(), ContractError> {
+        self.active.save(storage, &false)?;
+        self.deactivation.save(storage, deactivation)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_deactivate_with_reactivation_time() {
+        let mut deps = mock_dependencies();
+        let active_status = ActiveStatus::new("active", "deactivation");
+        let now = Timestamp::from_seconds(1000);
+
+        active_status.init(&mut deps.storage).unwrap();
+        assert!(active_status.is_active(&deps.storage, now).unwrap());
+
+        // reactivation time must be in the future
+        let err = active_status
+            .checked_set(
+                &mut deps.storage,
+                now,
+                false,
+                Some(InactiveReason::Maintenance),
+                Some(now),
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidReactivationTime { reactivate_at: now }
+        );
+
+        let reactivate_at = now.plus_seconds(600);
+        active_status
+            .checked_set(
+                &mut deps.storage,
+                now,
+                false,
+                Some(InactiveReason::Maintenance),
+                Some(reactivate_at),
+            )
+            .unwrap();
+
+        let deactivation = Deactivation {
+            reason: InactiveReason::Maintenance,
+            reactivate_at: Some(reactivate_at),
+        };
+        assert!(!active_status.is_active(&deps.storage, now).unwrap());
+        assert_eq!(
+            active_status
+                .deactivation(&deps.storage, reactivate_at.minus_seconds(1))
+                .unwrap(),
+            Some(deactivation)
+        );
+
+        // active again once reactivation time is reached
+        assert!(active_status
+            .is_active(&deps.storage, reactivate_at)
+            .unwrap());
+        assert_eq!(
+            active_status
+                .deactivation(&deps.storage, reactivate_at)
+                .unwrap(),
+            None
+        );
+
+        // so it can't be activated again, but can be deactivated again
+        let err = active_status
+            .checked_set(&mut deps.storage, reactivate_at, true, None, None)
+            .unwrap_err();
+        assert_eq!(err, ContractError::UnchangedActiveStatus { status: true });
+
+        active_status
+            .checked_set(&mut deps.storage, reactivate_at, false, None, None)
+            .unwrap();
+        assert_eq!(
+            active_status
+                .deactivation(&deps.storage, reactivate_at.plus_seconds(1_000_000))
+                .unwrap(),
+            Some(Deactivation {
+                reason: InactiveReason::Unspecified,
+                reactivate_at: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_activate() {
+        let mut deps = mock_dependencies();
+        let active_status = ActiveStatus::new("active", "deactivation");
+        let now = Timestamp::from_seconds(1000);
+
+        active_status.init(&mut deps.storage).unwrap();
+        active_status
+            .deactivate(&mut deps.storage, InactiveReason::CircuitBreaker)
+            .unwrap();
+        assert_eq!(
+            active_status.deactivation(&deps.storage, now).unwrap(),
+            Some(Deactivation {
+                reason: InactiveReason::CircuitBreaker,
+                reactivate_at: None,
+            })
+        );
+
+        // deactivation details are not accepted when activating
+        let err = active_status
+            .checked_set(
+                &mut deps.storage,
+                now,
+                true,
+                Some(InactiveReason::Incident),
+                None,
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::UnexpectedDeactivationOnActivation {});
+
+        active_status
+            .checked_set(&mut deps.storage, now, true, None, None)
+            .unwrap();
+        assert!(active_status.is_active(&deps.storage, now).unwrap());
+        assert_eq!(
+            active_status.deactivation.may_load(&deps.storage).unwrap(),
+            None
+        );
+
+        let err = active_status
+            .checked_set(&mut deps.storage, now, true, None, None)
+            .unwrap_err();
+        assert_eq!(err, ContractError::UnchangedActiveStatus { status: true });
+    }
+}
@@ -1,11 +1,30 @@
-use cosmwasm_std::{ensure, Addr, Coin, Deps, StdResult, Storage, Uint128};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Coin, CosmosMsg, Deps, StdResult, Storage, Timestamp, Uint128};
 use cw_storage_plus::Item;
 
 use crate::{
+    alloyed_backend::{AlloyedBackend, AlloyedBackendKind, ShareLedger, TokenFactory},
     asset::{convert_amount, Rounding},
     ContractError,
 };
 
+/// Change of alloyed asset normalization factor, e.g. a split to adjust decimals.
+/// Alloyed amount before the change converts to `amount * to / from` after the change.
+#[cw_serde]
+pub struct AlloyedNormalizationFactorChange {
+    pub height: u64,
+    pub time: Timestamp,
+    pub from: Uint128,
+    pub to: Uint128,
+}
+
+/// Block at which the alloyed denom was created
+#[cw_serde]
+pub struct AlloyedDenomCreation {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
 /// Alloyed asset represents the shares of the pool
 /// and since the pool is a 1:1 multi-asset pool, it act
 /// as a composite of the underlying assets and assume 1:1
@@ -13,24 +32,108 @@ use crate::{
 pub struct AlloyedAsset<'a> {
     alloyed_denom: Item<'a, String>,
     normalization_factor: Item<'a, Uint128>,
+    creation: Item<'a, AlloyedDenomCreation>,
+    backend_kind: Item<'a, AlloyedBackendKind>,
+    share_ledger: ShareLedger<'a>,
 }
 
 impl<'a> AlloyedAsset<'a> {
     pub const fn new(
         alloyed_denom_namespace: &'a str,
         normalization_factor_namespace: &'a str,
+        creation_namespace: &'a str,
+        backend_kind_namespace: &'a str,
+        share_balances_namespace: &'a str,
+        share_total_supply_namespace: &'a str,
     ) -> Self {
         Self {
             alloyed_denom: Item::new(alloyed_denom_namespace),
             normalization_factor: Item::new(normalization_factor_namespace),
+            creation: Item::new(creation_namespace),
+            backend_kind: Item::new(backend_kind_namespace),
+            share_ledger: ShareLedger::new(share_balances_namespace, share_total_supply_namespace),
         }
     }
 
+    /// get the backend of alloyed asset, tokenfactory for pools instantiated before backends
+    pub fn get_backend_kind(&self, store: &dyn Storage) -> StdResult<AlloyedBackendKind> {
+        self.backend_kind
+            .may_load(store)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// set the backend of alloyed asset, only at instantiate or migration
+    pub fn set_backend_kind(
+        &self,
+        store: &mut dyn Storage,
+        kind: AlloyedBackendKind,
+    ) -> StdResult<()> {
+        self.backend_kind.save(store, &kind)
+    }
+
+    fn backend(&self, store: &dyn Storage) -> StdResult<&dyn AlloyedBackend> {
+        Ok(match self.get_backend_kind(store)? {
+            AlloyedBackendKind::TokenFactory => &TokenFactory,
+            AlloyedBackendKind::ShareLedger => &self.share_ledger,
+        })
+    }
+
+    /// get the share ledger, erroring if alloyed asset is not held in it
+    pub fn share_ledger(&self, store: &dyn Storage) -> Result<&ShareLedger<'a>, ContractError> {
+        ensure!(
+            self.get_backend_kind(store)? == AlloyedBackendKind::ShareLedger,
+            ContractError::ShareLedgerNotEnabled {}
+        );
+
+        Ok(&self.share_ledger)
+    }
+
+    /// ensure that alloyed asset is a tokenfactory denom, for features that depend on it
+    pub fn ensure_token_factory(&self, store: &dyn Storage) -> Result<(), ContractError> {
+        ensure!(
+            self.get_backend_kind(store)? == AlloyedBackendKind::TokenFactory,
+            ContractError::UnsupportedByShareLedger {}
+        );
+
+        Ok(())
+    }
+
+    /// mint `amount` of alloyed asset to `recipient`,
+    /// returning the message that does it if the backend mints by message
+    pub fn mint(
+        &self,
+        store: &mut dyn Storage,
+        contract: &Addr,
+        amount: Uint128,
+        recipient: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        let coin = Coin::new(amount.u128(), self.get_alloyed_denom(store)?);
+        self.backend(store)?.mint(store, contract, coin, recipient)
+    }
+
+    /// burn `amount` of alloyed asset from `from`,
+    /// returning the message that does it if the backend burns by message
+    pub fn burn(
+        &self,
+        store: &mut dyn Storage,
+        contract: &Addr,
+        amount: Uint128,
+        from: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        let coin = Coin::new(amount.u128(), self.get_alloyed_denom(store)?);
+        self.backend(store)?.burn(store, contract, coin, from)
+    }
+
     /// get the alloyed denom
     pub fn get_alloyed_denom(&self, store: &dyn Storage) -> StdResult<String> {
         self.alloyed_denom.load(store)
     }
 
+    /// get the alloyed denom, `None` if it is not created yet
+    pub fn may_get_alloyed_denom(&self, store: &dyn Storage) -> StdResult<Option<String>> {
+        self.alloyed_denom.may_load(store)
+    }
+
     /// set the alloyed denom
     pub fn set_alloyed_denom(
         &self,
@@ -40,23 +143,36 @@ impl<'a> AlloyedAsset<'a> {
         self.alloyed_denom.save(store, alloyed_denom)
     }
 
+    /// get the block at which the alloyed denom was created,
+    /// `None` if it was created before creation got recorded
+    pub fn get_creation(&self, store: &dyn Storage) -> StdResult<Option<AlloyedDenomCreation>> {
+        self.creation.may_load(store)
+    }
+
+    /// set the block at which the alloyed denom was created
+    pub fn set_creation(
+        &self,
+        store: &mut dyn Storage,
+        creation: &AlloyedDenomCreation,
+    ) -> StdResult<()> {
+        self.creation.save(store, creation)
+    }
+
     /// get the total supply of alloyed asset
     /// which is the total shares of the pool
     pub fn get_total_supply(&self, deps: Deps) -> StdResult<Uint128> {
         let alloyed_denom = self.get_alloyed_denom(deps.storage)?;
 
-        deps.querier
-            .query_supply(alloyed_denom)
-            .map(|coin| coin.amount)
+        self.backend(deps.storage)?
+            .total_supply(deps, &alloyed_denom)
     }
 
     /// get the balance of alloyed asset for a given address
     pub fn get_balance(&self, deps: Deps, address: &Addr) -> StdResult<Uint128> {
         let alloyed_denom = self.get_alloyed_denom(deps.storage)?;
 
-        deps.querier
-            .query_balance(address, alloyed_denom)
-            .map(|coin| coin.amount)
+        self.backend(deps.storage)?
+            .balance(deps, &alloyed_denom, address)
     }
 
     /// get alloyed denom normalization factor
@@ -152,6 +268,75 @@ pub mod swap_to_alloyed {
 
         Ok(token_in_amount)
     }
+
+    /// With exact out and multiple tokens in, token out is split between tokens in
+    /// pro-rata to the value of their max amounts, and each share is converted back
+    /// to token in rounding up, so it never exceeds its max amount.
+    /// returns tokens in, excluding the ones with zero amount
+    pub fn tokens_in_via_exact_out(
+        tokens_in_max_with_norm_factor: Vec<(Coin, Uint128)>,
+        token_out_amount: Uint128,
+        alloyed_denom_normalization_factor: Uint128,
+    ) -> Result<Vec<Coin>, ContractError> {
+        let max_values = tokens_in_max_with_norm_factor
+            .iter()
+            .map(|(coin, norm_factor)| {
+                convert_amount(
+                    coin.amount,
+                    *norm_factor,
+                    alloyed_denom_normalization_factor,
+                    &Rounding::Down,
+                )
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        let total_max_value = max_values
+            .iter()
+            .try_fold(Uint128::zero(), |acc, value| acc.checked_add(*value))?;
+
+        ensure!(
+            total_max_value >= token_out_amount,
+            ContractError::InsufficientTokenOut {
+                min_required: token_out_amount,
+                amount_out: total_max_value
+            }
+        );
+
+        if token_out_amount.is_zero() {
+            return Ok(vec![]);
+        }
+
+        let mut values = max_values
+            .iter()
+            .map(|max_value| max_value.multiply_ratio(token_out_amount, total_max_value))
+            .collect::<Vec<_>>();
+
+        // distribute what's left from rounding down to tokens in that still have room
+        let mut remainder = values
+            .iter()
+            .try_fold(token_out_amount, |acc, value| acc.checked_sub(*value))?;
+        for (value, max_value) in values.iter_mut().zip(max_values) {
+            let added = remainder.min(max_value.checked_sub(*value)?);
+            *value = value.checked_add(added)?;
+            remainder = remainder.checked_sub(added)?;
+        }
+
+        tokens_in_max_with_norm_factor
+            .into_iter()
+            .zip(values)
+            .filter(|(_, value)| !value.is_zero())
+            .map(|((coin, norm_factor), value)| {
+                let amount = convert_amount(
+                    value,
+                    alloyed_denom_normalization_factor,
+                    norm_factor,
+                    &Rounding::Up,
+                )?;
+
+                Ok(Coin::new(amount.u128(), coin.denom))
+            })
+            .collect()
+    }
 }
 
 pub mod swap_from_alloyed {
@@ -217,8 +402,14 @@ mod tests {
 
     #[test]
     fn test_alloyed_assets_balance_and_supply() {
-        let alloyed_assets =
-            AlloyedAsset::new("alloyed_assets", "alloyed_assets_normalization_factor");
+        let alloyed_assets = AlloyedAsset::new(
+            "alloyed_assets",
+            "alloyed_assets_normalization_factor",
+            "alloyed_assets_creation",
+            "alloyed_assets_backend_kind",
+            "alloyed_assets_share_balances",
+            "alloyed_assets_share_total_supply",
+        );
         let mut deps = mock_dependencies();
 
         let alloyed_denom = "alloyed_denom".to_string();
@@ -264,8 +455,14 @@ mod tests {
 
     #[test]
     fn test_amount_from() {
-        let alloyed_assets =
-            AlloyedAsset::new("alloyed_denom", "alloyed_denom_normalization_factor");
+        let alloyed_assets = AlloyedAsset::new(
+            "alloyed_denom",
+            "alloyed_denom_normalization_factor",
+            "alloyed_denom_creation",
+            "alloyed_backend_kind",
+            "share_balances",
+            "share_total_supply",
+        );
         let mut deps = mock_dependencies();
 
         let alloyed_denom = "alloyed_denom".to_string();
@@ -307,10 +504,103 @@ mod tests {
         assert_eq!(amount.unwrap(), Uint128::from(83u128));
     }
 
+    #[test]
+    fn test_tokens_in_via_exact_out() {
+        // pro-rata to the value of max amounts
+        let tokens_in = swap_to_alloyed::tokens_in_via_exact_out(
+            vec![
+                (Coin::new(3000, "ua"), Uint128::one()),
+                (Coin::new(100000, "ub"), Uint128::from(100u128)),
+            ],
+            Uint128::from(2000u128),
+            Uint128::one(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            tokens_in,
+            vec![Coin::new(1500, "ua"), Coin::new(50000, "ub")]
+        );
+
+        // remainder from rounding down goes to the first token in with room,
+        // and tokens in are rounded up
+        let tokens_in = swap_to_alloyed::tokens_in_via_exact_out(
+            vec![
+                (Coin::new(100, "ua"), Uint128::one()),
+                (Coin::new(100, "ub"), Uint128::one()),
+                (Coin::new(100, "uc"), Uint128::from(3u128)),
+            ],
+            Uint128::from(100u128),
+            Uint128::one(),
+        )
+        .unwrap();
+
+        // values: 100, 100, 33 => shares: 42 + 2, 42, 14
+        assert_eq!(
+            tokens_in,
+            vec![
+                Coin::new(44, "ua"),
+                Coin::new(42, "ub"),
+                Coin::new(42, "uc")
+            ]
+        );
+        assert!(
+            AlloyedAsset::amount_from(
+                &[
+                    (tokens_in[0].clone(), Uint128::one()),
+                    (tokens_in[1].clone(), Uint128::one()),
+                    (tokens_in[2].clone(), Uint128::from(3u128)),
+                ],
+                Uint128::one(),
+                Rounding::Down
+            )
+            .unwrap()
+                >= Uint128::from(100u128)
+        );
+
+        // exact max amounts
+        let tokens_in = swap_to_alloyed::tokens_in_via_exact_out(
+            vec![
+                (Coin::new(100, "ua"), Uint128::one()),
+                (Coin::new(0, "ub"), Uint128::one()),
+            ],
+            Uint128::from(100u128),
+            Uint128::one(),
+        )
+        .unwrap();
+
+        assert_eq!(tokens_in, vec![Coin::new(100, "ua")]);
+
+        // max amounts not enough
+        let err = swap_to_alloyed::tokens_in_via_exact_out(
+            vec![
+                (Coin::new(100, "ua"), Uint128::one()),
+                (Coin::new(100, "ub"), Uint128::from(3u128)),
+            ],
+            Uint128::from(134u128),
+            Uint128::one(),
+        )
+        .unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InsufficientTokenOut {
+                min_required: Uint128::from(134u128),
+                amount_out: Uint128::from(133u128)
+            }
+        );
+    }
+
     #[test]
     fn test_set_normalization_factor() {
-        let alloyed_assets =
-            AlloyedAsset::new("alloyed_denom", "alloyed_denom_normalization_factor");
+        let alloyed_assets = AlloyedAsset::new(
+            "alloyed_denom",
+            "alloyed_denom_normalization_factor",
+            "alloyed_denom_creation",
+            "alloyed_backend_kind",
+            "share_balances",
+            "share_total_supply",
+        );
         let mut deps = mock_dependencies();
 
         let alloyed_denom = "alloyed_denom".to_string();
@@ -0,0 +1,406 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Coin, CosmosMsg, Deps, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
+
+use crate::ContractError;
+
+/// How alloyed asset is minted, burned and held, selected at instantiate
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum AlloyedBackendKind {
+    /// Bank denom created via the Osmosis tokenfactory module
+    #[default]
+    TokenFactory,
+    /// Balances kept by the contract itself, for chains without the tokenfactory module.
+    /// Shares can't be sent as funds, they are moved with `transfer_shares` instead.
+    ShareLedger,
+}
+
+impl AlloyedBackendKind {
+    /// Alloyed denom of the transmuter at `contract_address` with `subdenom` and `prefix`
+    pub fn alloyed_denom(&self, contract_address: &Addr, prefix: &str, subdenom: &str) -> String {
+        match self {
+            AlloyedBackendKind::TokenFactory => {
+                format!("factory/{contract_address}/{prefix}/{subdenom}")
+            }
+            AlloyedBackendKind::ShareLedger => {
+                format!("ledger/{contract_address}/{prefix}/{subdenom}")
+            }
+        }
+    }
+}
+
+/// Minting, burning and accounting of alloyed asset
+pub trait AlloyedBackend {
+    /// Mint `amount` to `recipient`, returning the message that does it if any
+    fn mint(
+        &self,
+        storage: &mut dyn Storage,
+        contract: &Addr,
+        amount: Coin,
+        recipient: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError>;
+
+    /// Burn `amount` from `from`, returning the message that does it if any
+    fn burn(
+        &self,
+        storage: &mut dyn Storage,
+        contract: &Addr,
+        amount: Coin,
+        from: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError>;
+
+    fn total_supply(&self, deps: Deps, denom: &str) -> StdResult<Uint128>;
+
+    fn balance(&self, deps: Deps, denom: &str, address: &Addr) -> StdResult<Uint128>;
+}
+
+/// Alloyed asset as tokenfactory denom, with the contract as its admin
+pub struct TokenFactory;
+
+impl AlloyedBackend for TokenFactory {
+    fn mint(
+        &self,
+        _storage: &mut dyn Storage,
+        contract: &Addr,
+        amount: Coin,
+        recipient: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        Ok(Some(
+            MsgMint {
+                sender: contract.to_string(),
+                amount: Some(amount.into()),
+                mint_to_address: recipient.to_string(),
+            }
+            .into(),
+        ))
+    }
+
+    fn burn(
+        &self,
+        _storage: &mut dyn Storage,
+        contract: &Addr,
+        amount: Coin,
+        from: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        Ok(Some(
+            MsgBurn {
+                sender: contract.to_string(),
+                amount: Some(amount.into()),
+                burn_from_address: from.to_string(),
+            }
+            .into(),
+        ))
+    }
+
+    fn total_supply(&self, deps: Deps, denom: &str) -> StdResult<Uint128> {
+        deps.querier.query_supply(denom).map(|coin| coin.amount)
+    }
+
+    fn balance(&self, deps: Deps, denom: &str, address: &Addr) -> StdResult<Uint128> {
+        deps.querier
+            .query_balance(address, denom)
+            .map(|coin| coin.amount)
+    }
+}
+
+/// Alloyed asset as balances in contract storage. Minting and burning take effect
+/// immediately, without any message.
+pub struct ShareLedger<'a> {
+    balances: Map<'a, &'a Addr, Uint128>,
+    total_supply: Item<'a, Uint128>,
+}
+
+impl<'a> ShareLedger<'a> {
+    pub const fn new(balances_namespace: &'a str, total_supply_namespace: &'a str) -> Self {
+        Self {
+            balances: Map::new(balances_namespace),
+            total_supply: Item::new(total_supply_namespace),
+        }
+    }
+
+    /// Move `amount` shares from `from` to `to`
+    pub fn transfer(
+        &self,
+        storage: &mut dyn Storage,
+        from: &Addr,
+        to: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        self.debit(storage, from, amount)?;
+        self.credit(storage, to, amount)
+    }
+
+    /// Rescale all balances by `numerator / denominator`, rounding down. Total supply becomes
+    /// the sum of the rescaled balances, so that it stays fully backed by them.
+    pub fn rescale(
+        &self,
+        storage: &mut dyn Storage,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> Result<(), ContractError> {
+        let balances = self
+            .balances
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut total_supply = Uint128::zero();
+        for (address, balance) in balances {
+            let balance = balance.checked_multiply_ratio(numerator, denominator)?;
+            if balance.is_zero() {
+                self.balances.remove(storage, &address);
+            } else {
+                self.balances.save(storage, &address, &balance)?;
+            }
+            total_supply = total_supply.checked_add(balance)?;
+        }
+
+        self.total_supply.save(storage, &total_supply)?;
+
+        Ok(())
+    }
+
+    fn credit(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        let balance = self
+            .balances
+            .may_load(storage, address)?
+            .unwrap_or_default();
+        self.balances
+            .save(storage, address, &balance.checked_add(amount)?)?;
+
+        Ok(())
+    }
+
+    fn debit(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        let balance = self
+            .balances
+            .may_load(storage, address)?
+            .unwrap_or_default();
+        ensure!(
+            balance >= amount,
+            ContractError::InsufficientShares {
+                required: amount,
+                available: balance
+            }
+        );
+
+        let remaining = balance - amount;
+        if remaining.is_zero() {
+            self.balances.remove(storage, address);
+        } else {
+            self.balances.save(storage, address, &remaining)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl AlloyedBackend for ShareLedger<'_> {
+    fn mint(
+        &self,
+        storage: &mut dyn Storage,
+        _contract: &Addr,
+        amount: Coin,
+        recipient: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        self.credit(storage, recipient, amount.amount)?;
+
+        let total_supply = self.total_supply.may_load(storage)?.unwrap_or_default();
+        self.total_supply
+            .save(storage, &total_supply.checked_add(amount.amount)?)?;
+
+        Ok(None)
+    }
+
+    fn burn(
+        &self,
+        storage: &mut dyn Storage,
+        _contract: &Addr,
+        amount: Coin,
+        from: &Addr,
+    ) -> Result<Option<CosmosMsg>, ContractError> {
+        self.debit(storage, from, amount.amount)?;
+
+        let total_supply = self.total_supply.may_load(storage)?.unwrap_or_default();
+        self.total_supply
+            .save(storage, &total_supply.checked_sub(amount.amount)?)?;
+
+        Ok(None)
+    }
+
+    fn total_supply(&self, deps: Deps, _denom: &str) -> StdResult<Uint128> {
+        self.total_supply
+            .may_load(deps.storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    fn balance(&self, deps: Deps, _denom: &str, address: &Addr) -> StdResult<Uint128> {
+        self.balances
+            .may_load(deps.storage, address)
+            .map(Option::unwrap_or_default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_share_ledger() {
+        let mut deps = mock_dependencies();
+        let ledger = ShareLedger::new("balances", "total_supply");
+        let contract = Addr::unchecked("contract");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let denom = "ledger/contract/alloyed/usdc";
+
+        assert_eq!(
+            ledger
+                .mint(&mut deps.storage, &contract, Coin::new(1000, denom), &alice)
+                .unwrap(),
+            None
+        );
+        ledger
+            .transfer(&mut deps.storage, &alice, &bob, Uint128::new(400))
+            .unwrap();
+
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &alice).unwrap(),
+            Uint128::new(600)
+        );
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &bob).unwrap(),
+            Uint128::new(400)
+        );
+        assert_eq!(
+            ledger.total_supply(deps.as_ref(), denom).unwrap(),
+            Uint128::new(1000)
+        );
+
+        // can't burn or transfer more than the balance
+        assert_eq!(
+            ledger
+                .burn(&mut deps.storage, &contract, Coin::new(401, denom), &bob)
+                .unwrap_err(),
+            ContractError::InsufficientShares {
+                required: Uint128::new(401),
+                available: Uint128::new(400)
+            }
+        );
+        assert_eq!(
+            ledger
+                .transfer(&mut deps.storage, &bob, &alice, Uint128::new(401))
+                .unwrap_err(),
+            ContractError::InsufficientShares {
+                required: Uint128::new(401),
+                available: Uint128::new(400)
+            }
+        );
+
+        ledger
+            .burn(&mut deps.storage, &contract, Coin::new(400, denom), &bob)
+            .unwrap();
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &bob).unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            ledger.total_supply(deps.as_ref(), denom).unwrap(),
+            Uint128::new(600)
+        );
+    }
+
+    #[test]
+    fn test_share_ledger_rescale() {
+        let mut deps = mock_dependencies();
+        let ledger = ShareLedger::new("balances", "total_supply");
+        let contract = Addr::unchecked("contract");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+        let denom = "ledger/contract/alloyed/usdc";
+
+        ledger
+            .mint(&mut deps.storage, &contract, Coin::new(1005, denom), &alice)
+            .unwrap();
+        ledger
+            .mint(&mut deps.storage, &contract, Coin::new(9, denom), &bob)
+            .unwrap();
+
+        // 10x split
+        ledger
+            .rescale(&mut deps.storage, Uint128::new(10), Uint128::one())
+            .unwrap();
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &alice).unwrap(),
+            Uint128::new(10050)
+        );
+        assert_eq!(
+            ledger.total_supply(deps.as_ref(), denom).unwrap(),
+            Uint128::new(10140)
+        );
+
+        // 100x merge rounds down, dropping balances that round to zero from the total
+        ledger
+            .rescale(&mut deps.storage, Uint128::one(), Uint128::new(100))
+            .unwrap();
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &alice).unwrap(),
+            Uint128::new(100)
+        );
+        assert_eq!(
+            ledger.balance(deps.as_ref(), denom, &bob).unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(
+            ledger.total_supply(deps.as_ref(), denom).unwrap(),
+            Uint128::new(100)
+        );
+    }
+
+    #[test]
+    fn test_token_factory() {
+        let mut deps = mock_dependencies();
+        let contract = Addr::unchecked("contract");
+        let user = Addr::unchecked("user");
+        let denom = "factory/contract/alloyed/usdc";
+
+        assert_eq!(
+            TokenFactory
+                .mint(&mut deps.storage, &contract, Coin::new(1000, denom), &user)
+                .unwrap(),
+            Some(
+                MsgMint {
+                    sender: "contract".to_string(),
+                    amount: Some(Coin::new(1000, denom).into()),
+                    mint_to_address: "user".to_string(),
+                }
+                .into()
+            )
+        );
+        assert_eq!(
+            TokenFactory
+                .burn(&mut deps.storage, &contract, Coin::new(1000, denom), &user)
+                .unwrap(),
+            Some(
+                MsgBurn {
+                    sender: "contract".to_string(),
+                    amount: Some(Coin::new(1000, denom).into()),
+                    burn_from_address: "user".to_string(),
+                }
+                .into()
+            )
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use std::collections::BTreeSet;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+/// Destructive action that requires approvals from approvers while approval is enabled
+#[cw_serde]
+pub enum DestructiveAction {
+    /// Mark assets as corrupted, which are removed from the pool once drained
+    MarkCorruptedAssets {
+        denoms: Vec<String>,
+    },
+    DeregisterLimiter {
+        denom: String,
+        label: String,
+    },
+    TransferAlloyedDenomAdmin {
+        candidate: String,
+    },
+    HandOverToSuccessor {
+        successor: String,
+    },
+    /// Change or disable, with empty `approvers`, the approver set
+    SetApprovers {
+        approvers: Vec<String>,
+        threshold: u32,
+    },
+}
+
+impl DestructiveAction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            DestructiveAction::MarkCorruptedAssets { .. } => "mark_corrupted_assets",
+            DestructiveAction::DeregisterLimiter { .. } => "deregister_limiter",
+            DestructiveAction::TransferAlloyedDenomAdmin { .. } => "transfer_alloyed_denom_admin",
+            DestructiveAction::HandOverToSuccessor { .. } => "hand_over_to_successor",
+            DestructiveAction::SetApprovers { .. } => "set_approvers",
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ApproverSet {
+    pub approvers: Vec<Addr>,
+    /// Number of approvals from `approvers` an action requires
+    pub threshold: u32,
+}
+
+impl ApproverSet {
+    /// Ensure there is no duplicated approver and `threshold` is within 1 and number of approvers
+    pub fn validate(&self) -> Result<(), ContractError> {
+        let mut seen = BTreeSet::new();
+        for approver in &self.approvers {
+            ensure!(
+                seen.insert(approver),
+                ContractError::DuplicateApprover {
+                    approver: approver.clone()
+                }
+            );
+        }
+
+        let approvers = self.approvers.len() as u32;
+        ensure!(
+            self.threshold > 0 && self.threshold <= approvers,
+            ContractError::InvalidApprovalThreshold {
+                threshold: self.threshold,
+                approvers
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[cw_serde]
+pub struct ActionProposal {
+    pub id: u64,
+    pub action: DestructiveAction,
+    pub proposer: Addr,
+    pub approvals: Vec<Addr>,
+}
+
+/// Optional set of approvers which destructive actions require approvals from before execution,
+/// so that a single compromised key can't perform them.
+pub struct Approvals<'a> {
+    approver_set: Item<'a, ApproverSet>,
+    proposals: Map<'a, u64, ActionProposal>,
+    next_id: Item<'a, u64>,
+}
+
+impl<'a> Approvals<'a> {
+    pub const fn new(
+        approver_set_namespace: &'a str,
+        proposals_namespace: &'a str,
+        next_id_namespace: &'a str,
+    ) -> Self {
+        Self {
+            approver_set: Item::new(approver_set_namespace),
+            proposals: Map::new(proposals_namespace),
+            next_id: Item::new(next_id_namespace),
+        }
+    }
+
+    /// Current approver set, `None` if approval is disabled
+    pub fn approver_set(&self, storage: &dyn Storage) -> StdResult<Option<ApproverSet>> {
+        self.approver_set.may_load(storage)
+    }
+
+    /// Set approver set, `None` to disable approval
+    pub fn set_approver_set(
+        &self,
+        storage: &mut dyn Storage,
+        approver_set: Option<ApproverSet>,
+    ) -> Result<(), ContractError> {
+        match approver_set {
+            Some(approver_set) => {
+                approver_set.validate()?;
+                self.approver_set.save(storage, &approver_set)?;
+            }
+            None => self.approver_set.remove(storage),
+        }
+
+        Ok(())
+    }
+
+    /// Ensure the action can be performed right away, i.e. approval is disabled
+    pub fn ensure_not_required(&self, storage: &dyn Storage) -> Result<(), ContractError> {
+        ensure!(
+            self.approver_set(storage)?.is_none(),
+            ContractError::ApprovalRequired {}
+        );
+
+        Ok(())
+    }
+
+    /// Propose `action` to be approved by approvers
+    pub fn propose(
+        &self,
+        storage: &mut dyn Storage,
+        action: DestructiveAction,
+        proposer: Addr,
+    ) -> Result<ActionProposal, ContractError> {
+        ensure!(
+            self.approver_set(storage)?.is_some(),
+            ContractError::ApprovalNotEnabled {}
+        );
+
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+
+        let proposal = ActionProposal {
+            id,
+            action,
+            proposer,
+            approvals: vec![],
+        };
+        self.proposals.save(storage, id, &proposal)?;
+
+        Ok(proposal)
+    }
+
+    /// Approve proposal `id`, `approver` must be in the current approver set
+    pub fn approve(
+        &self,
+        storage: &mut dyn Storage,
+        id: u64,
+        approver: Addr,
+    ) -> Result<ActionProposal, ContractError> {
+        let approver_set = self
+            .approver_set(storage)?
+            .ok_or(ContractError::ApprovalNotEnabled {})?;
+        ensure!(
+            approver_set.approvers.contains(&approver),
+            ContractError::Unauthorized {}
+        );
+
+        let mut proposal = self.get(storage, id)?;
+        ensure!(
+            !proposal.approvals.contains(&approver),
+            ContractError::AlreadyApproved { id, approver }
+        );
+
+        proposal.approvals.push(approver);
+        self.proposals.save(storage, id, &proposal)?;
+
+        Ok(proposal)
+    }
+
+    /// Remove proposal `id` so that its action can be executed.
+    /// Only approvals from the current approver set count towards its threshold.
+    pub fn take_approved(
+        &self,
+        storage: &mut dyn Storage,
+        id: u64,
+    ) -> Result<ActionProposal, ContractError> {
+        let proposal = self.get(storage, id)?;
+
+        // proposal stays executable if approval has been disabled since it was proposed
+        if let Some(approver_set) = self.approver_set(storage)? {
+            let approvals = proposal
+                .approvals
+                .iter()
+                .filter(|approver| approver_set.approvers.contains(approver))
+                .count() as u32;
+
+            ensure!(
+                approvals >= approver_set.threshold,
+                ContractError::InsufficientApprovals {
+                    id,
+                    approvals,
+                    threshold: approver_set.threshold
+                }
+            );
+        }
+
+        self.proposals.remove(storage, id);
+
+        Ok(proposal)
+    }
+
+    pub fn cancel(
+        &self,
+        storage: &mut dyn Storage,
+        id: u64,
+    ) -> Result<ActionProposal, ContractError> {
+        let proposal = self.get(storage, id)?;
+        self.proposals.remove(storage, id);
+
+        Ok(proposal)
+    }
+
+    /// Cancel every action proposal
+    pub fn cancel_all(&self, storage: &mut dyn Storage) {
+        self.proposals.clear(storage);
+    }
+
+    pub fn get(&self, storage: &dyn Storage, id: u64) -> Result<ActionProposal, ContractError> {
+        self.proposals
+            .may_load(storage, id)?
+            .ok_or(ContractError::ActionProposalNotFound { id })
+    }
+
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<ActionProposal>> {
+        self.proposals
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, proposal)| proposal))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_approve_and_take_approved() {
+        let mut deps = mock_dependencies();
+        let approvals = Approvals::new("approver_set", "proposals", "next_id");
+        let approver_1 = Addr::unchecked("approver_1");
+        let approver_2 = Addr::unchecked("approver_2");
+        let proposer = Addr::unchecked("proposer");
+
+        let action = DestructiveAction::TransferAlloyedDenomAdmin {
+            candidate: "successor".to_string(),
+        };
+
+        // disabled by default
+        approvals.ensure_not_required(&deps.storage).unwrap();
+        assert_eq!(
+            approvals
+                .propose(&mut deps.storage, action.clone(), proposer.clone())
+                .unwrap_err(),
+            ContractError::ApprovalNotEnabled {}
+        );
+
+        // threshold must be within 1 and number of approvers, without duplicates
+        for (approvers, threshold, err) in [
+            (
+                vec![approver_1.clone(), approver_2.clone()],
+                3,
+                ContractError::InvalidApprovalThreshold {
+                    threshold: 3,
+                    approvers: 2,
+                },
+            ),
+            (
+                vec![approver_1.clone()],
+                0,
+                ContractError::InvalidApprovalThreshold {
+                    threshold: 0,
+                    approvers: 1,
+                },
+            ),
+            (
+                vec![approver_1.clone(), approver_1.clone()],
+                1,
+                ContractError::DuplicateApprover {
+                    approver: approver_1.clone(),
+                },
+            ),
+        ] {
+            assert_eq!(
+                approvals
+                    .set_approver_set(
+                        &mut deps.storage,
+                        Some(ApproverSet {
+                            approvers,
+                            threshold
+                        })
+                    )
+                    .unwrap_err(),
+                err
+            );
+        }
+
+        approvals
+            .set_approver_set(
+                &mut deps.storage,
+                Some(ApproverSet {
+                    approvers: vec![approver_1.clone(), approver_2.clone()],
+                    threshold: 2,
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            approvals.ensure_not_required(&deps.storage).unwrap_err(),
+            ContractError::ApprovalRequired {}
+        );
+
+        let proposal = approvals
+            .propose(&mut deps.storage, action.clone(), proposer.clone())
+            .unwrap();
+        assert_eq!(proposal.id, 0);
+
+        // only approvers can approve, once
+        assert_eq!(
+            approvals
+                .approve(&mut deps.storage, 0, proposer.clone())
+                .unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        approvals
+            .approve(&mut deps.storage, 0, approver_1.clone())
+            .unwrap();
+        assert_eq!(
+            approvals
+                .approve(&mut deps.storage, 0, approver_1.clone())
+                .unwrap_err(),
+            ContractError::AlreadyApproved {
+                id: 0,
+                approver: approver_1.clone()
+            }
+        );
+
+        assert_eq!(
+            approvals.take_approved(&mut deps.storage, 0).unwrap_err(),
+            ContractError::InsufficientApprovals {
+                id: 0,
+                approvals: 1,
+                threshold: 2
+            }
+        );
+
+        let proposal = approvals
+            .approve(&mut deps.storage, 0, approver_2.clone())
+            .unwrap();
+        assert_eq!(
+            approvals.take_approved(&mut deps.storage, 0).unwrap(),
+            proposal
+        );
+
+        // can only be taken once
+        assert_eq!(
+            approvals.take_approved(&mut deps.storage, 0).unwrap_err(),
+            ContractError::ActionProposalNotFound { id: 0 }
+        );
+        assert_eq!(approvals.list(&deps.storage).unwrap(), vec![]);
+    }
+}
@@ -1,7 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Coin, Deps, StdError, Uint128, Uint256};
+use cosmwasm_std::{ensure, Coin, Deps, StdError, StdResult, Uint128, Uint256};
 
-use crate::ContractError;
+use crate::{
+    bank_supply::{query_supply, supply_violation},
+    ContractError,
+};
 
 #[derive(PartialEq)]
 pub enum Rounding {
@@ -24,20 +27,18 @@ impl AssetConfig {
     }
 
     pub fn checked_init_asset(self, deps: Deps) -> Result<Asset, ContractError> {
-        let supply = deps.querier.query_supply(self.denom.as_str())?;
-
-        // check for supply instead of metadata
-        // since some denom (eg. ibc denom) could have no metadata
-        ensure!(
-            supply.amount > Uint128::zero(),
-            ContractError::DenomHasNoSupply { denom: self.denom }
-        );
+        let supply = query_supply(&deps.querier, &self.denom);
+        self.checked_init_asset_with_supply(supply)
+    }
 
-        // check for zero normalization factor
-        ensure!(
-            self.normalization_factor > Uint128::zero(),
-            ContractError::NormalizationFactorMustBePositive {}
-        );
+    /// Same as `checked_init_asset`, with `supply` of the denom already queried
+    pub fn checked_init_asset_with_supply(
+        self,
+        supply: StdResult<Uint128>,
+    ) -> Result<Asset, ContractError> {
+        if let Some(violation) = self.violations_with_supply(supply).into_iter().next() {
+            return Err(violation);
+        }
 
         Ok(Asset {
             amount: Uint128::zero(),
@@ -46,6 +47,30 @@ impl AssetConfig {
             is_corrupted: false,
         })
     }
+
+    /// Every reason the asset can't be a pool asset, in the order `checked_init_asset` checks them
+    pub fn violations(&self, deps: Deps) -> Vec<ContractError> {
+        self.violations_with_supply(query_supply(&deps.querier, &self.denom))
+    }
+
+    /// Same as `violations`, with `supply` of the denom already queried
+    pub fn violations_with_supply(&self, supply: StdResult<Uint128>) -> Vec<ContractError> {
+        let mut violations = vec![];
+
+        // check for supply instead of metadata
+        // since some denom (eg. ibc denom) could have no metadata
+        match supply {
+            Ok(supply) => violations.extend(supply_violation(&self.denom, supply)),
+            Err(err) => violations.push(err.into()),
+        }
+
+        // check for zero normalization factor
+        if self.normalization_factor.is_zero() {
+            violations.push(ContractError::NormalizationFactorMustBePositive {});
+        }
+
+        violations
+    }
 }
 
 #[cw_serde]
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_vec, Addr, HexBinary, Order, StdError, StdResult, Storage, Timestamp,
+};
+use cw_storage_plus::{Bound, Item, Map};
+use serde::de::IgnoredAny;
+use sha2::{Digest, Sha256};
+
+use crate::contract::sv::ExecMsg;
+
+/// Number of most recent entries kept in the audit log, older entries are pruned
+pub const MAX_AUDIT_LOG_ENTRIES: u64 = 1000;
+
+/// Number of entries returned per page, if not specified
+pub const DEFAULT_AUDIT_LOG_PAGE_LIMIT: u32 = 50;
+
+/// Method name and JSON encoding of `msg` if it is an admin, moderator or other privileged action
+pub fn audited_msg(msg: &ExecMsg) -> StdResult<Option<(String, Vec<u8>)>> {
+    let audited = matches!(
+        msg,
+        ExecMsg::RescaleNormalizationFactor { .. }
+            | ExecMsg::AddNewAssets { .. }
+            | ExecMsg::SetBlockedPoolAssetDenom { .. }
+            | ExecMsg::SetFrozenMessage { .. }
+            | ExecMsg::MarkCorruptedAssets { .. }
+            | ExecMsg::UnmarkCorruptedAssets { .. }
+            | ExecMsg::RegisterLimiter { .. }
+            | ExecMsg::DeregisterLimiter { .. }
+            | ExecMsg::SetChangeLimiterBoundaryOffset { .. }
+            | ExecMsg::SetStaticLimiterUpperLimit { .. }
+            | ExecMsg::SetLimiterTuningBounds { .. }
+            | ExecMsg::SetAlloyedDenomMetadata { .. }
+            | ExecMsg::SetSwapFee { .. }
+            | ExecMsg::SetSwapFeeExemption { .. }
+            | ExecMsg::SetMaxAffiliateShare { .. }
+            | ExecMsg::SetDynamicSwapFee { .. }
+            | ExecMsg::SetPermissionedMode { .. }
+            | ExecMsg::SetAllowedTrader { .. }
+            | ExecMsg::SetAlloyedTransferRestrictionMode { .. }
+            | ExecMsg::SetAlloyedTransferRestrictedAddress { .. }
+            | ExecMsg::SetProtocolAddress { .. }
+            | ExecMsg::SetMinSwapAmount { .. }
+            | ExecMsg::SetCircuitBreaker { .. }
+            | ExecMsg::SetSwapHook { .. }
+            | ExecMsg::SetSupplyHook { .. }
+            | ExecMsg::SetFlowQuota { .. }
+            | ExecMsg::SetIcqContract { .. }
+            | ExecMsg::AddRemoteAsset { .. }
+            | ExecMsg::CancelRemoteAsset { .. }
+            | ExecMsg::RegisterSiblingPool { .. }
+            | ExecMsg::DeregisterSiblingPool { .. }
+            | ExecMsg::SetPoolRegistry { .. }
+            | ExecMsg::SetMonitoringContract { .. }
+            | ExecMsg::SetDefaultRedemptionStrategy { .. }
+            | ExecMsg::SetAlloyedSupplyCap { .. }
+            | ExecMsg::SetAlloyedSupplyCapSchedule { .. }
+            | ExecMsg::SetParam { .. }
+            | ExecMsg::SetTimelockDelay { .. }
+            | ExecMsg::QueueChange { .. }
+            | ExecMsg::ExecuteChange { .. }
+            | ExecMsg::CancelChange { .. }
+            | ExecMsg::RollbackConfig { .. }
+            | ExecMsg::SetApprovers { .. }
+            | ExecMsg::ProposeAction { .. }
+            | ExecMsg::ApproveAction { .. }
+            | ExecMsg::ExecuteAction { .. }
+            | ExecMsg::CancelAction { .. }
+            | ExecMsg::SetActiveStatus { .. }
+            | ExecMsg::TransferAdmin { .. }
+            | ExecMsg::CancelAdminTransfer { .. }
+            | ExecMsg::RejectAdminTransfer { .. }
+            | ExecMsg::ClaimAdmin { .. }
+            | ExecMsg::RenounceAdminship { .. }
+            | ExecMsg::ConfirmRenounceAdminship { .. }
+            | ExecMsg::TransferAlloyedDenomAdmin { .. }
+            | ExecMsg::HandOverToSuccessor { .. }
+            | ExecMsg::CancelAlloyedDenomAdminTransfer { .. }
+            | ExecMsg::ClaimAlloyedDenomAdmin { .. }
+            | ExecMsg::AssignModerator { .. }
+            | ExecMsg::AddModerator { .. }
+            | ExecMsg::RemoveModerator { .. }
+            | ExecMsg::GrantRole { .. }
+            | ExecMsg::RevokeRole { .. }
+            | ExecMsg::SetRoleRateLimit { .. }
+    );
+
+    if !audited {
+        return Ok(None);
+    }
+
+    let msg = to_json_vec(msg)?;
+    let method = method_of(&msg)?;
+
+    Ok(Some((method, msg)))
+}
+
+/// Method name of `msg`, e.g. `join_pool`
+pub fn exec_method(msg: &ExecMsg) -> StdResult<String> {
+    method_of(&to_json_vec(msg)?)
+}
+
+pub fn method_of(msg: &[u8]) -> StdResult<String> {
+    // execute message is encoded as `{"<method>": {...}}`
+    from_json::<BTreeMap<String, IgnoredAny>>(msg)?
+        .into_keys()
+        .next()
+        .ok_or_else(|| StdError::generic_err("empty execute message"))
+}
+
+/// Record of a privileged action
+#[cw_serde]
+pub struct AuditEntry {
+    /// Sequential id, starting from 0
+    pub id: u64,
+    /// Execute message name, e.g. `set_swap_fee`
+    pub method: String,
+    pub actor: Addr,
+    pub timestamp: Timestamp,
+    /// sha256 of the JSON encoded execute message
+    pub params_hash: HexBinary,
+}
+
+/// Append-only log of admin, moderator and other privileged actions,
+/// bounded to the most recent [MAX_AUDIT_LOG_ENTRIES] entries.
+pub struct AuditLog<'a> {
+    entries: Map<'a, u64, AuditEntry>,
+    next_id: Item<'a, u64>,
+}
+
+impl<'a> AuditLog<'a> {
+    pub const fn new(entries_namespace: &'a str, next_id_namespace: &'a str) -> Self {
+        Self {
+            entries: Map::new(entries_namespace),
+            next_id: Item::new(next_id_namespace),
+        }
+    }
+
+    /// Append entry of `method` executed by `actor` with JSON encoded `msg`,
+    /// pruning the oldest entry once the log is full
+    pub fn append(
+        &self,
+        storage: &mut dyn Storage,
+        method: &str,
+        actor: Addr,
+        timestamp: Timestamp,
+        msg: &[u8],
+    ) -> StdResult<AuditEntry> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+
+        let entry = AuditEntry {
+            id,
+            method: method.to_string(),
+            actor,
+            timestamp,
+            params_hash: HexBinary::from(Sha256::digest(msg).to_vec()),
+        };
+        self.entries.save(storage, id, &entry)?;
+
+        if let Some(pruned_id) = id.checked_sub(MAX_AUDIT_LOG_ENTRIES) {
+            self.entries.remove(storage, pruned_id);
+        }
+
+        Ok(entry)
+    }
+
+    /// Up to `limit` entries after `start_after`, oldest first
+    pub fn list(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<u64>,
+        limit: u32,
+    ) -> StdResult<Vec<AuditEntry>> {
+        self.entries
+            .range(
+                storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|item| item.map(|(_, entry)| entry))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_append_and_prune() {
+        let mut deps = mock_dependencies();
+        let audit_log = AuditLog::new("entries", "next_id");
+        let actor = Addr::unchecked("admin");
+        let timestamp = Timestamp::from_seconds(1000);
+
+        let entry = audit_log
+            .append(
+                &mut deps.storage,
+                "set_swap_fee",
+                actor.clone(),
+                timestamp,
+                b"{}",
+            )
+            .unwrap();
+        assert_eq!(
+            entry,
+            AuditEntry {
+                id: 0,
+                method: "set_swap_fee".to_string(),
+                actor: actor.clone(),
+                timestamp,
+                params_hash: HexBinary::from_hex(
+                    "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+                )
+                .unwrap(),
+            }
+        );
+
+        for _ in 1..MAX_AUDIT_LOG_ENTRIES + 5 {
+            audit_log
+                .append(
+                    &mut deps.storage,
+                    "set_swap_fee",
+                    actor.clone(),
+                    timestamp,
+                    b"{}",
+                )
+                .unwrap();
+        }
+
+        // only the most recent entries are kept
+        let entries = audit_log.list(&deps.storage, None, 2).unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![5, 6]
+        );
+
+        let entries = audit_log
+            .list(&deps.storage, Some(MAX_AUDIT_LOG_ENTRIES + 2), 10)
+            .unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+            vec![MAX_AUDIT_LOG_ENTRIES + 3, MAX_AUDIT_LOG_ENTRIES + 4]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, Binary, Coin, StdResult, Uint128};
+
+use crate::{audit_log::exec_method, contract::sv::ExecMsg};
+
+pub const MSG_EXECUTE_CONTRACT_TYPE_URL: &str = "/cosmwasm.wasm.v1.MsgExecuteContract";
+
+/// Set of execute messages that can be granted together via x/authz
+/// `ContractExecutionAuthorization`
+#[cw_serde]
+pub struct AuthzGrant {
+    pub name: String,
+    pub description: String,
+    /// Keys for the `AcceptedMessageKeysFilter` of the grant
+    pub accepted_message_keys: Vec<String>,
+    /// Messages the grantee can execute under the grant, wrapped in `MsgExec`
+    /// with the granter as sender
+    pub examples: Vec<AuthzExampleMsg>,
+}
+
+/// Example `MsgExecuteContract`, without sender
+#[cw_serde]
+pub struct AuthzExampleMsg {
+    pub type_url: String,
+    pub contract: String,
+    pub msg: Binary,
+    pub funds: Vec<Coin>,
+}
+
+/// Grants that only act on the granter's own funds, never on pool configuration.
+/// Accepted message keys are taken from the example messages themselves,
+/// so that they stay in sync with `ExecMsg`.
+pub fn grant_presets(
+    contract: &Addr,
+    token_in_denom: &str,
+    token_out_denom: &str,
+    alloyed_denom: &str,
+) -> StdResult<Vec<AuthzGrant>> {
+    let amount = Uint128::new(1_000_000);
+    let presets = [
+        (
+            "swap",
+            "Swap the granter's tokens. Message keys can't restrict `to_address` and `ibc_forward`, \
+             so pair the filter with a spend limit, e.g. `MaxFundsLimit`.",
+            vec![
+                (
+                    ExecMsg::SwapExactAmountIn {
+                        token_out_denom: token_out_denom.to_string(),
+                        token_out_min_amount: amount,
+                        max_execution_price: None,
+                        allow_partial_fill: None,
+                        affiliate: None,
+                        owner: None,
+                        alloyed_amount_in: None,
+                        to_address: None,
+                        ibc_forward: None,
+                        deadline: None,
+                    },
+                    vec![Coin::new(amount.u128(), token_in_denom)],
+                ),
+                (
+                    ExecMsg::SwapExactAmountOut {
+                        token_out: Coin::new(amount.u128(), token_out_denom),
+                        token_in_max_amount: amount,
+                        max_execution_price: None,
+                        affiliate: None,
+                        owner: None,
+                        to_address: None,
+                        ibc_forward: None,
+                        deadline: None,
+                    },
+                    vec![Coin::new(amount.u128(), token_in_denom)],
+                ),
+            ],
+        ),
+        (
+            "liquidity",
+            "Join and exit pool with the granter's tokens and alloyed asset. \
+             Message keys can't restrict `to_address` of exit pool, so pair the filter \
+             with a spend limit, e.g. `MaxFundsLimit`.",
+            vec![
+                (
+                    ExecMsg::JoinPool { deadline: None },
+                    vec![Coin::new(amount.u128(), token_in_denom)],
+                ),
+                (
+                    ExecMsg::ExitPool {
+                        tokens_out: vec![Coin::new(amount.u128(), token_out_denom)],
+                        owner: None,
+                        to_address: None,
+                        deadline: None,
+                    },
+                    vec![Coin::new(amount.u128(), alloyed_denom)],
+                ),
+            ],
+        ),
+        (
+            "drip_orders",
+            "Submit and cancel drip orders of the granter, whose token out always goes to the granter.",
+            vec![
+                (
+                    ExecMsg::SubmitDripOrder {
+                        token_out_denom: token_out_denom.to_string(),
+                        max_execution_price: None,
+                    },
+                    vec![Coin::new(amount.u128(), token_in_denom)],
+                ),
+                (ExecMsg::CancelDripOrder { order_id: 0 }, vec![]),
+            ],
+        ),
+    ];
+
+    presets
+        .into_iter()
+        .map(|(name, description, examples)| {
+            let accepted_message_keys = examples
+                .iter()
+                .map(|(msg, _)| exec_method(msg))
+                .collect::<StdResult<Vec<_>>>()?;
+            let examples = examples
+                .into_iter()
+                .map(|(msg, funds)| {
+                    Ok(AuthzExampleMsg {
+                        type_url: MSG_EXECUTE_CONTRACT_TYPE_URL.to_string(),
+                        contract: contract.to_string(),
+                        msg: to_json_binary(&msg)?,
+                        funds,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+
+            Ok(AuthzGrant {
+                name: name.to_string(),
+                description: description.to_string(),
+                accepted_message_keys,
+                examples,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::authorized_actions;
+    use cosmwasm_std::from_json;
+
+    #[test]
+    fn test_grant_presets() {
+        let grants =
+            grant_presets(&Addr::unchecked("transmuter"), "uaaa", "ubbb", "alloyed").unwrap();
+
+        assert_eq!(
+            grants
+                .iter()
+                .map(|grant| (grant.name.as_str(), grant.accepted_message_keys.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "swap",
+                    vec![
+                        "swap_exact_amount_in".to_string(),
+                        "swap_exact_amount_out".to_string()
+                    ]
+                ),
+                (
+                    "liquidity",
+                    vec!["join_pool".to_string(), "exit_pool".to_string()]
+                ),
+                (
+                    "drip_orders",
+                    vec![
+                        "submit_drip_order".to_string(),
+                        "cancel_drip_order".to_string()
+                    ]
+                ),
+            ]
+        );
+
+        // no preset grants a privileged action
+        for grant in &grants {
+            for key in &grant.accepted_message_keys {
+                assert!(authorized_actions::roles_for(key).is_empty(), "{key}");
+            }
+        }
+
+        let example = &grants[0].examples[0];
+        assert_eq!(example.type_url, MSG_EXECUTE_CONTRACT_TYPE_URL);
+        assert_eq!(example.contract, "transmuter");
+        assert_eq!(example.funds, vec![Coin::new(1_000_000, "uaaa")]);
+        assert_eq!(
+            from_json::<ExecMsg>(&example.msg).unwrap(),
+            ExecMsg::SwapExactAmountIn {
+                token_out_denom: "ubbb".to_string(),
+                token_out_min_amount: Uint128::new(1_000_000),
+                max_execution_price: None,
+                allow_partial_fill: None,
+                affiliate: None,
+                owner: None,
+                alloyed_amount_in: None,
+                to_address: None,
+                ibc_forward: None,
+                deadline: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{QuerierWrapper, StdResult, Uint128};
+use osmosis_std::types::cosmos::bank::v1beta1::BankQuerier;
+
+use crate::ContractError;
+
+/// Bank supply of `denom`. Some chains momentarily report zero supply for IBC vouchers
+/// right after the channel is opened, so a zero supply of an IBC voucher is double checked
+/// with the stargate `SupplyOf` query, which reads the supply store directly.
+pub fn query_supply(querier: &QuerierWrapper, denom: &str) -> StdResult<Uint128> {
+    let supply = querier.query_supply(denom)?.amount;
+    if !supply.is_zero() || !is_ibc_voucher(denom) {
+        return Ok(supply);
+    }
+
+    // stargate queries might not be enabled, keep the bank supply then
+    let fallback = BankQuerier::new(querier)
+        .supply_of(denom.to_string())
+        .ok()
+        .and_then(|res| res.amount)
+        .and_then(|coin| coin.amount.parse::<Uint128>().ok());
+
+    Ok(fallback.unwrap_or(supply))
+}
+
+/// Violation of a pool asset with `supply`, if it has none
+pub fn supply_violation(denom: &str, supply: Uint128) -> Option<ContractError> {
+    if !supply.is_zero() {
+        return None;
+    }
+
+    Some(if is_ibc_voucher(denom) {
+        ContractError::IbcVoucherSupplyUnavailable {
+            denom: denom.to_string(),
+        }
+    } else {
+        ContractError::DenomHasNoSupply {
+            denom: denom.to_string(),
+        }
+    })
+}
+
+fn is_ibc_voucher(denom: &str) -> bool {
+    denom.starts_with("ibc/")
+}
+
+/// Supplies of `denoms`, queried once per message since each pool asset supply is checked
+/// more than once. Failing queries are left out, so that they get reported where used.
+pub fn query_supplies(querier: &QuerierWrapper, denoms: &[&str]) -> BTreeMap<String, Uint128> {
+    denoms
+        .iter()
+        .filter_map(|denom| {
+            query_supply(querier, denom)
+                .ok()
+                .map(|supply| (denom.to_string(), supply))
+        })
+        .collect()
+}
+
+/// Supply of `denom` from `supplies`, or queried if it is not there
+pub fn supply_of(
+    supplies: &BTreeMap<String, Uint128>,
+    querier: &QuerierWrapper,
+    denom: &str,
+) -> StdResult<Uint128> {
+    match supplies.get(denom) {
+        Some(supply) => Ok(*supply),
+        None => query_supply(querier, denom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{testing::mock_dependencies, Coin};
+
+    #[test]
+    fn test_query_supplies() {
+        let mut deps = mock_dependencies();
+        deps.querier
+            .update_balance("someone", vec![Coin::new(1000, "uosmo")]);
+
+        let supplies = query_supplies(&deps.as_ref().querier, &["uosmo", "ibc/voucher"]);
+        assert_eq!(
+            supplies,
+            BTreeMap::from([
+                ("ibc/voucher".to_string(), Uint128::zero()),
+                ("uosmo".to_string(), Uint128::new(1000)),
+            ])
+        );
+
+        // supply is looked up from the queried supplies
+        deps.querier
+            .update_balance("someone", vec![Coin::new(2000, "uosmo")]);
+        assert_eq!(
+            supply_of(&supplies, &deps.as_ref().querier, "uosmo").unwrap(),
+            Uint128::new(1000)
+        );
+
+        // or queried if it is not there
+        assert_eq!(
+            supply_of(&BTreeMap::new(), &deps.as_ref().querier, "uosmo").unwrap(),
+            Uint128::new(2000)
+        );
+    }
+
+    #[test]
+    fn test_supply_violation() {
+        assert_eq!(supply_violation("uosmo", Uint128::one()), None);
+        assert_eq!(
+            supply_violation("uosmo", Uint128::zero()),
+            Some(ContractError::DenomHasNoSupply {
+                denom: "uosmo".to_string()
+            })
+        );
+        assert_eq!(
+            supply_violation("ibc/voucher", Uint128::zero()),
+            Some(ContractError::IbcVoucherSupplyUnavailable {
+                denom: "ibc/voucher".to_string()
+            })
+        );
+    }
+}
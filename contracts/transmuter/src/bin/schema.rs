@@ -1,13 +1,120 @@
 #![cfg(not(tarpaulin_include))]
 
-use cosmwasm_schema::write_api;
+use std::{env::current_dir, fs::create_dir_all};
+
+use cosmwasm_schema::{export_schema, schema_for, write_api};
 
 use transmuter::contract::sv::{ExecMsg, InstantiateMsg, QueryMsg};
+use transmuter::contract::*;
+use transmuter::{
+    ChangeLimiter, Division, Limiter, LimiterParams, MigrateMsg, StaticLimiter, SudoMsg,
+    VersionedResponse, WindowConfig,
+};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
+        migrate: MigrateMsg,
     }
+
+    // every query response, and nested types shared between them,
+    // as separate files for codegen that works per type
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    out_dir.push("types");
+    create_dir_all(&out_dir).unwrap();
+
+    export_schema(&schema_for!(AmountInUntilWeightResponse), &out_dir);
+    export_schema(&schema_for!(AuthzGrantsResponse), &out_dir);
+    export_schema(&schema_for!(CalcInAmtGivenOutResponse), &out_dir);
+    export_schema(&schema_for!(CalcOutAmtGivenInResponse), &out_dir);
+    export_schema(&schema_for!(CapabilitiesResponse), &out_dir);
+    export_schema(&schema_for!(ConvertSharesResponse), &out_dir);
+    export_schema(&schema_for!(ConvertToSharesResponse), &out_dir);
+    export_schema(&schema_for!(DashboardResponse), &out_dir);
+    export_schema(&schema_for!(DepthResponse), &out_dir);
+    export_schema(&schema_for!(ExportStateResponse), &out_dir);
+    export_schema(&schema_for!(GetAccountActivityResponse), &out_dir);
+    export_schema(&schema_for!(GetAdminCandidateResponse), &out_dir);
+    export_schema(&schema_for!(GetAdminResponse), &out_dir);
+    export_schema(&schema_for!(GetAdminTransferResponse), &out_dir);
+    export_schema(&schema_for!(GetAllowedTradersResponse), &out_dir);
+    export_schema(&schema_for!(GetAlloyedConversionFactorResponse), &out_dir);
+    export_schema(&schema_for!(GetAlloyedDenomResponse), &out_dir);
+    export_schema(&schema_for!(GetAlloyedSupplyCapResponse), &out_dir);
+    export_schema(
+        &schema_for!(GetAlloyedTransferRestrictionResponse),
+        &out_dir,
+    );
+    export_schema(&schema_for!(GetApproversResponse), &out_dir);
+    export_schema(&schema_for!(GetBlockedPoolAssetDenomsResponse), &out_dir);
+    export_schema(&schema_for!(GetCircuitBreakerResponse), &out_dir);
+    export_schema(&schema_for!(GetCirculatingSupplyResponse), &out_dir);
+    export_schema(&schema_for!(GetContractVersionResponse), &out_dir);
+    export_schema(&schema_for!(GetCorrruptedDenomsResponse), &out_dir);
+    export_schema(&schema_for!(GetDefaultRedemptionStrategyResponse), &out_dir);
+    export_schema(&schema_for!(GetDripOrderResponse), &out_dir);
+    export_schema(&schema_for!(GetExpectedAlloyedDenomResponse), &out_dir);
+    export_schema(&schema_for!(GetFlowQuotaResponse), &out_dir);
+    export_schema(&schema_for!(GetFrozenMessagesResponse), &out_dir);
+    export_schema(&schema_for!(GetIcqContractResponse), &out_dir);
+    export_schema(&schema_for!(GetLimiterTuningResponse), &out_dir);
+    export_schema(&schema_for!(GetMetaTxSignerResponse), &out_dir);
+    export_schema(&schema_for!(GetMetricsResponse), &out_dir);
+    export_schema(&schema_for!(GetMinSwapAmountsResponse), &out_dir);
+    export_schema(&schema_for!(GetModeratorResponse), &out_dir);
+    export_schema(&schema_for!(GetMonitoringContractResponse), &out_dir);
+    export_schema(&schema_for!(GetOperatorAllowanceResponse), &out_dir);
+    export_schema(&schema_for!(GetParamResponse), &out_dir);
+    export_schema(&schema_for!(GetPermissionedModeResponse), &out_dir);
+    export_schema(&schema_for!(GetPoolAssetMetadataResponse), &out_dir);
+    export_schema(&schema_for!(GetPoolRegistryResponse), &out_dir);
+    export_schema(&schema_for!(GetShareDenomResponse), &out_dir);
+    export_schema(&schema_for!(GetSharesResponse), &out_dir);
+    export_schema(&schema_for!(GetSuccessorResponse), &out_dir);
+    export_schema(&schema_for!(GetSupplyHookResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapFeeConfigResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapFeeExemptedAddressesResponse), &out_dir);
+    export_schema(&schema_for!(GetAccruedFeesResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapFeeResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapHookResponse), &out_dir);
+    export_schema(&schema_for!(GetSwapVolumesResponse), &out_dir);
+    export_schema(&schema_for!(GetTimelockDelayResponse), &out_dir);
+    export_schema(&schema_for!(GetTotalPoolLiquidityResponse), &out_dir);
+    export_schema(&schema_for!(GetTotalSharesResponse), &out_dir);
+    export_schema(&schema_for!(GetWeightCheckpointsResponse), &out_dir);
+    export_schema(&schema_for!(IsActiveResponse), &out_dir);
+    export_schema(&schema_for!(ListAccountActivitiesResponse), &out_dir);
+    export_schema(&schema_for!(ListActionProposalsResponse), &out_dir);
+    export_schema(&schema_for!(ListAssetConfigsResponse), &out_dir);
+    export_schema(&schema_for!(ListAuditLogResponse), &out_dir);
+    export_schema(&schema_for!(ListConfigSnapshotsResponse), &out_dir);
+    export_schema(&schema_for!(ListDripOrdersResponse), &out_dir);
+    export_schema(&schema_for!(ListGrantedRolesResponse), &out_dir);
+    export_schema(&schema_for!(ListLimitersResponse), &out_dir);
+    export_schema(&schema_for!(ListModeratorsResponse), &out_dir);
+    export_schema(&schema_for!(ListOperatorAllowancesResponse), &out_dir);
+    export_schema(&schema_for!(ListPendingChangesResponse), &out_dir);
+    export_schema(&schema_for!(ListPendingRemoteAssetsResponse), &out_dir);
+    export_schema(&schema_for!(ListRoleRateLimitsResponse), &out_dir);
+    export_schema(&schema_for!(ListSiblingPoolsResponse), &out_dir);
+    export_schema(&schema_for!(PermissionsResponse), &out_dir);
+    export_schema(&schema_for!(QuoteExitPoolExactInResponse), &out_dir);
+    export_schema(&schema_for!(QuoteJoinPoolResponse), &out_dir);
+    export_schema(&schema_for!(RouteViabilityResponse), &out_dir);
+    export_schema(&schema_for!(SimulateSwapExactAssetInResponse), &out_dir);
+    export_schema(&schema_for!(SimulateSwapExactAssetOutResponse), &out_dir);
+    export_schema(&schema_for!(SpotPriceResponse), &out_dir);
+    export_schema(&schema_for!(SwapCommitmentResponse), &out_dir);
+    export_schema(&schema_for!(VersionedResponse), &out_dir);
+
+    export_schema(&schema_for!(Limiter), &out_dir);
+    export_schema(&schema_for!(LimiterParams), &out_dir);
+    export_schema(&schema_for!(ChangeLimiter), &out_dir);
+    export_schema(&schema_for!(StaticLimiter), &out_dir);
+    export_schema(&schema_for!(Division), &out_dir);
+    export_schema(&schema_for!(WindowConfig), &out_dir);
 }
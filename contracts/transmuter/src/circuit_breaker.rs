@@ -0,0 +1,238 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Coin, Decimal, StdResult, Storage};
+use cw_storage_plus::Item;
+
+use crate::{transmuter_pool::TransmuterPool, ContractError};
+
+/// Pool liquidity at the start of the block, before any outflow in the block.
+#[cw_serde]
+pub struct BlockStart {
+    pub height: u64,
+    pub pool_assets: Vec<Coin>,
+}
+
+/// Watchdog that pauses the pool when net outflow of any denom within a single block
+/// exceeds `max_outflow` fraction of its liquidity at the start of the block.
+pub struct CircuitBreaker<'a> {
+    max_outflow: Item<'a, Decimal>,
+    block_start: Item<'a, BlockStart>,
+}
+
+impl<'a> CircuitBreaker<'a> {
+    pub const fn new(max_outflow_namespace: &'a str, block_start_namespace: &'a str) -> Self {
+        Self {
+            max_outflow: Item::new(max_outflow_namespace),
+            block_start: Item::new(block_start_namespace),
+        }
+    }
+
+    /// Max outflow, `None` if circuit breaker is disabled
+    pub fn max_outflow(&self, storage: &dyn Storage) -> StdResult<Option<Decimal>> {
+        self.max_outflow.may_load(storage)
+    }
+
+    /// Set max outflow, must be within (0, 1]. Unset to disable circuit breaker.
+    pub fn set_max_outflow(
+        &self,
+        storage: &mut dyn Storage,
+        max_outflow: Option<Decimal>,
+    ) -> Result<(), ContractError> {
+        match max_outflow {
+            Some(max_outflow) => {
+                ensure!(
+                    !max_outflow.is_zero() && max_outflow <= Decimal::one(),
+                    ContractError::InvalidMaxOutflow { max_outflow }
+                );
+                self.max_outflow.save(storage, &max_outflow)?;
+            }
+            None => {
+                self.max_outflow.remove(storage);
+                self.block_start.remove(storage);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record `pool` as the pool at the start of the block at `height`,
+    /// unless it has already been recorded for the block. No-op if disabled.
+    pub fn record_block_start(
+        &self,
+        storage: &mut dyn Storage,
+        height: u64,
+        pool: &TransmuterPool,
+    ) -> Result<(), ContractError> {
+        if self.max_outflow(storage)?.is_none() {
+            return Ok(());
+        }
+
+        let is_recorded = matches!(
+            self.block_start.may_load(storage)?,
+            Some(block_start) if block_start.height == height
+        );
+
+        if !is_recorded {
+            self.block_start.save(
+                storage,
+                &BlockStart {
+                    height,
+                    pool_assets: pool
+                        .pool_assets
+                        .iter()
+                        .map(|asset| asset.to_coin())
+                        .collect(),
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// First denom whose net outflow since the start of the block at `height` exceeds max outflow,
+    /// along with its outflow fraction. Denoms no longer in `pool` are ignored.
+    pub fn exceeded_outflow(
+        &self,
+        storage: &dyn Storage,
+        height: u64,
+        pool: &TransmuterPool,
+    ) -> Result<Option<(String, Decimal)>, ContractError> {
+        let max_outflow = match self.max_outflow(storage)? {
+            Some(max_outflow) => max_outflow,
+            None => return Ok(None),
+        };
+
+        let block_start = match self.block_start.may_load(storage)? {
+            Some(block_start) if block_start.height == height => block_start,
+            _ => return Ok(None),
+        };
+
+        for start in block_start.pool_assets {
+            if start.amount.is_zero() {
+                continue;
+            }
+
+            if let Ok(asset) = pool.get_pool_asset_by_denom(&start.denom) {
+                let outflow = start.amount.saturating_sub(asset.amount());
+                let outflow = Decimal::checked_from_ratio(outflow, start.amount)?;
+
+                if outflow > max_outflow {
+                    return Ok(Some((start.denom, outflow)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    fn pool(amount_a: u128, amount_b: u128) -> TransmuterPool {
+        TransmuterPool::new(vec![
+            Asset::unchecked(amount_a.into(), "denoma", 1u128.into()),
+            Asset::unchecked(amount_b.into(), "denomb", 1u128.into()),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_set_max_outflow() {
+        let mut deps = mock_dependencies();
+        let circuit_breaker = CircuitBreaker::new("max_outflow", "block_start");
+
+        assert_eq!(circuit_breaker.max_outflow(&deps.storage).unwrap(), None);
+
+        for max_outflow in [Decimal::zero(), Decimal::percent(101)] {
+            assert_eq!(
+                circuit_breaker
+                    .set_max_outflow(&mut deps.storage, Some(max_outflow))
+                    .unwrap_err(),
+                ContractError::InvalidMaxOutflow { max_outflow }
+            );
+        }
+
+        circuit_breaker
+            .set_max_outflow(&mut deps.storage, Some(Decimal::percent(30)))
+            .unwrap();
+        assert_eq!(
+            circuit_breaker.max_outflow(&deps.storage).unwrap(),
+            Some(Decimal::percent(30))
+        );
+
+        circuit_breaker
+            .set_max_outflow(&mut deps.storage, None)
+            .unwrap();
+        assert_eq!(circuit_breaker.max_outflow(&deps.storage).unwrap(), None);
+    }
+
+    #[test]
+    fn test_exceeded_outflow() {
+        let mut deps = mock_dependencies();
+        let circuit_breaker = CircuitBreaker::new("max_outflow", "block_start");
+
+        // disabled
+        circuit_breaker
+            .record_block_start(&mut deps.storage, 1, &pool(1000, 1000))
+            .unwrap();
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 1, &pool(0, 2000))
+                .unwrap(),
+            None
+        );
+
+        circuit_breaker
+            .set_max_outflow(&mut deps.storage, Some(Decimal::percent(30)))
+            .unwrap();
+
+        circuit_breaker
+            .record_block_start(&mut deps.storage, 1, &pool(1000, 1000))
+            .unwrap();
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 1, &pool(700, 1300))
+                .unwrap(),
+            None
+        );
+
+        // block start is only recorded once per block
+        circuit_breaker
+            .record_block_start(&mut deps.storage, 1, &pool(700, 1300))
+            .unwrap();
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 1, &pool(600, 1400))
+                .unwrap(),
+            Some(("denoma".to_string(), Decimal::percent(40)))
+        );
+
+        // net outflow is what matters
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 1, &pool(900, 1100))
+                .unwrap(),
+            None
+        );
+
+        // new block starts over
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 2, &pool(600, 1400))
+                .unwrap(),
+            None
+        );
+        circuit_breaker
+            .record_block_start(&mut deps.storage, 2, &pool(600, 1400))
+            .unwrap();
+        assert_eq!(
+            circuit_breaker
+                .exceeded_outflow(&deps.storage, 2, &pool(1000, 900))
+                .unwrap(),
+            Some(("denomb".to_string(), Decimal::from_ratio(500u128, 1400u128)))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+use crate::{swap_fee::SwapFeeConfig, ContractError};
+
+/// Number of most recent snapshots kept, older snapshots are pruned
+pub const MAX_CONFIG_SNAPSHOTS: u64 = 20;
+
+/// Adjustable value of a limiter
+#[cw_serde]
+pub enum LimiterValue {
+    /// Change limiter's boundary offset
+    BoundaryOffset(Decimal),
+    /// Static limiter's upper limit
+    UpperLimit(Decimal),
+}
+
+#[cw_serde]
+pub struct LimiterSetting {
+    pub denom: String,
+    pub label: String,
+    pub value: LimiterValue,
+}
+
+/// Limiter and fee settings that can be rolled back
+#[cw_serde]
+pub struct RecoverableConfig {
+    pub swap_fee: SwapFeeConfig,
+    pub limiters: Vec<LimiterSetting>,
+}
+
+/// Config as it was before being changed
+#[cw_serde]
+pub struct ConfigSnapshot {
+    pub id: u64,
+    /// Execute message that changed the config, e.g. `set_swap_fee`
+    pub changed_by: String,
+    pub taken_at: Timestamp,
+    pub config: RecoverableConfig,
+}
+
+/// Bounded history of config snapshots, so that fat-fingered changes can be rolled back
+pub struct ConfigSnapshots<'a> {
+    snapshots: Map<'a, u64, ConfigSnapshot>,
+    next_id: Item<'a, u64>,
+}
+
+impl<'a> ConfigSnapshots<'a> {
+    pub const fn new(snapshots_namespace: &'a str, next_id_namespace: &'a str) -> Self {
+        Self {
+            snapshots: Map::new(snapshots_namespace),
+            next_id: Item::new(next_id_namespace),
+        }
+    }
+
+    /// Store `config` as it was before `changed_by` changed it,
+    /// pruning the oldest snapshot once the history is full
+    pub fn take(
+        &self,
+        storage: &mut dyn Storage,
+        changed_by: &str,
+        taken_at: Timestamp,
+        config: RecoverableConfig,
+    ) -> StdResult<ConfigSnapshot> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+
+        let snapshot = ConfigSnapshot {
+            id,
+            changed_by: changed_by.to_string(),
+            taken_at,
+            config,
+        };
+        self.snapshots.save(storage, id, &snapshot)?;
+
+        if let Some(pruned_id) = id.checked_sub(MAX_CONFIG_SNAPSHOTS) {
+            self.snapshots.remove(storage, pruned_id);
+        }
+
+        Ok(snapshot)
+    }
+
+    pub fn get(&self, storage: &dyn Storage, id: u64) -> Result<ConfigSnapshot, ContractError> {
+        self.snapshots
+            .may_load(storage, id)?
+            .ok_or(ContractError::ConfigSnapshotNotFound { id })
+    }
+
+    /// Snapshots, newest first
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<ConfigSnapshot>> {
+        self.snapshots
+            .range(storage, None, None, Order::Descending)
+            .map(|item| item.map(|(_, snapshot)| snapshot))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_take_and_prune() {
+        let mut deps = mock_dependencies();
+        let snapshots = ConfigSnapshots::new("snapshots", "next_id");
+        let taken_at = Timestamp::from_seconds(1000);
+
+        for i in 0..MAX_CONFIG_SNAPSHOTS + 2 {
+            let config = RecoverableConfig {
+                swap_fee: SwapFeeConfig {
+                    rate: Decimal::permille(i),
+                    ..SwapFeeConfig::default()
+                },
+                limiters: vec![],
+            };
+            let snapshot = snapshots
+                .take(&mut deps.storage, "set_swap_fee", taken_at, config.clone())
+                .unwrap();
+            assert_eq!(snapshot.id, i);
+            assert_eq!(snapshots.get(&deps.storage, i).unwrap().config, config);
+        }
+
+        // only the most recent snapshots are kept
+        let ids = snapshots
+            .list(&deps.storage)
+            .unwrap()
+            .into_iter()
+            .map(|snapshot| snapshot.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (2..MAX_CONFIG_SNAPSHOTS + 2).rev().collect::<Vec<_>>());
+        assert_eq!(
+            snapshots.get(&deps.storage, 1).unwrap_err(),
+            ContractError::ConfigSnapshotNotFound { id: 1 }
+        );
+    }
+}
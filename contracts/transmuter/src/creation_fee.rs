@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, BankMsg, Coin, CosmosMsg, Uint128};
+use osmosis_std::types::cosmos::distribution::v1beta1::MsgFundCommunityPool;
+
+use crate::ContractError;
+
+/// Fee paid out of the funds sent with instantiation, as expected from pools created via
+/// the cosmwasmpool module. Whatever is sent beyond it is refunded, unless it becomes the
+/// initial liquidity of an adopted alloyed denom.
+#[cw_serde]
+pub struct PoolCreationFee {
+    pub amount: Vec<Coin>,
+    /// Address the fee is sent to, the community pool if not set
+    pub recipient: Option<String>,
+}
+
+impl PoolCreationFee {
+    /// Every issue with the fee and the `funds` paying it, empty if there is none
+    pub fn violations(&self, api: &dyn Api, funds: &[Coin]) -> Vec<ContractError> {
+        let mut violations = vec![];
+
+        let mut denoms = self
+            .amount
+            .iter()
+            .map(|coin| &coin.denom)
+            .collect::<Vec<_>>();
+        denoms.sort();
+        denoms.dedup();
+        if self.amount.is_empty()
+            || denoms.len() != self.amount.len()
+            || self.amount.iter().any(|coin| coin.amount.is_zero())
+        {
+            violations.push(ContractError::InvalidPoolCreationFee {});
+        }
+
+        if let Some(recipient) = &self.recipient {
+            if let Err(err) = api.addr_validate(recipient) {
+                violations.push(err.into());
+            }
+        }
+
+        for required in &self.amount {
+            let provided = funds
+                .iter()
+                .find(|coin| coin.denom == required.denom)
+                .map(|coin| coin.amount)
+                .unwrap_or_default();
+            if provided < required.amount {
+                violations.push(ContractError::InsufficientPoolCreationFee {
+                    required: required.clone(),
+                    provided: Coin::new(provided.u128(), required.denom.as_str()),
+                });
+            }
+        }
+
+        violations
+    }
+
+    /// `funds` left after paying the fee, assuming there is no violation
+    pub fn remaining_funds(&self, funds: &[Coin]) -> Result<Vec<Coin>, ContractError> {
+        let mut remaining = funds
+            .iter()
+            .map(|coin| (coin.denom.clone(), coin.amount))
+            .collect::<BTreeMap<String, Uint128>>();
+
+        for fee in &self.amount {
+            let amount = remaining.entry(fee.denom.clone()).or_default();
+            *amount = amount.checked_sub(fee.amount)?;
+        }
+
+        Ok(remaining
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(denom, amount)| Coin::new(amount.u128(), denom))
+            .collect())
+    }
+
+    /// Message paying the fee from `contract` to the recipient or the community pool
+    pub fn payment_msg(&self, contract: &Addr) -> CosmosMsg {
+        match &self.recipient {
+            Some(recipient) => BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: self.amount.clone(),
+            }
+            .into(),
+            None => MsgFundCommunityPool {
+                amount: self.amount.iter().cloned().map(Into::into).collect(),
+                depositor: contract.to_string(),
+            }
+            .into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+
+    #[test]
+    fn test_violations() {
+        let api = MockApi::default();
+        let fee = PoolCreationFee {
+            amount: vec![Coin::new(1000, "uosmo")],
+            recipient: None,
+        };
+
+        assert_eq!(fee.violations(&api, &[Coin::new(1000, "uosmo")]), vec![]);
+        assert_eq!(
+            fee.violations(&api, &[Coin::new(999, "uosmo"), Coin::new(1000, "uion")]),
+            vec![ContractError::InsufficientPoolCreationFee {
+                required: Coin::new(1000, "uosmo"),
+                provided: Coin::new(999, "uosmo"),
+            }]
+        );
+
+        let invalid_fees = [
+            vec![],
+            vec![Coin::new(0, "uosmo")],
+            vec![Coin::new(1, "uosmo"), Coin::new(1, "uosmo")],
+        ];
+        for amount in invalid_fees {
+            let fee = PoolCreationFee {
+                amount: amount.clone(),
+                recipient: None,
+            };
+            assert_eq!(
+                fee.violations(&api, &[Coin::new(2, "uosmo")]),
+                vec![ContractError::InvalidPoolCreationFee {}],
+                "{amount:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remaining_funds_and_payment() {
+        let contract = Addr::unchecked("contract");
+        let fee = PoolCreationFee {
+            amount: vec![Coin::new(1000, "uosmo")],
+            recipient: None,
+        };
+
+        assert_eq!(
+            fee.remaining_funds(&[Coin::new(1500, "uosmo"), Coin::new(100, "uion")])
+                .unwrap(),
+            vec![Coin::new(100, "uion"), Coin::new(500, "uosmo")]
+        );
+        assert_eq!(
+            fee.remaining_funds(&[Coin::new(1000, "uosmo")]).unwrap(),
+            vec![]
+        );
+
+        assert_eq!(
+            fee.payment_msg(&contract),
+            MsgFundCommunityPool {
+                amount: vec![Coin::new(1000, "uosmo").into()],
+                depositor: "contract".to_string(),
+            }
+            .into()
+        );
+
+        let fee = PoolCreationFee {
+            recipient: Some("treasury".to_string()),
+            ..fee
+        };
+        assert_eq!(
+            fee.payment_msg(&contract),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![Coin::new(1000, "uosmo")],
+            })
+        );
+    }
+}
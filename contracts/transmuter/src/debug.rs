@@ -0,0 +1,73 @@
+#[cfg(feature = "debug-events")]
+use std::cell::RefCell;
+
+use cosmwasm_std::Event;
+
+/// Type of the event emitted for each traced intermediate calculation, only when compiled with
+/// the `debug-events` feature
+#[cfg(feature = "debug-events")]
+pub const DEBUG_EVENT_TYPE: &str = "transmuter-debug";
+
+#[cfg(feature = "debug-events")]
+thread_local! {
+    static TRACES: RefCell<Vec<Event>> = const { RefCell::new(vec![]) };
+}
+
+/// Trace intermediate calculation `step` with the attributes returned by `attributes`,
+/// to aid testnet debugging. Without the `debug-events` feature, `attributes` is never called
+/// and nothing is recorded, so that production builds don't pay for it.
+#[inline]
+pub fn trace<F>(step: &str, attributes: F)
+where
+    F: FnOnce() -> Vec<(&'static str, String)>,
+{
+    #[cfg(feature = "debug-events")]
+    TRACES.with(|traces| {
+        traces.borrow_mut().push(
+            Event::new(DEBUG_EVENT_TYPE)
+                .add_attribute("step", step)
+                .add_attributes(attributes()),
+        )
+    });
+
+    #[cfg(not(feature = "debug-events"))]
+    let _ = (step, attributes);
+}
+
+/// Take events of the steps traced so far
+#[cfg(feature = "debug-events")]
+pub fn take_traces() -> Vec<Event> {
+    TRACES.with(RefCell::take)
+}
+
+/// Take events of the steps traced so far, always empty without the `debug-events` feature
+#[cfg(not(feature = "debug-events"))]
+pub fn take_traces() -> Vec<Event> {
+    vec![]
+}
+
+#[cfg(all(test, feature = "debug-events"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() {
+        take_traces();
+
+        trace("moving_average", || {
+            vec![
+                ("integral", "10".to_string()),
+                ("average", "0.5".to_string()),
+            ]
+        });
+
+        assert_eq!(
+            take_traces(),
+            vec![Event::new(DEBUG_EVENT_TYPE)
+                .add_attribute("step", "moving_average")
+                .add_attribute("integral", "10")
+                .add_attribute("average", "0.5")]
+        );
+        assert_eq!(take_traces(), vec![]);
+    }
+}
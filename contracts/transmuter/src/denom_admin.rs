@@ -0,0 +1,210 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, StdResult, Storage};
+use cw_storage_plus::Item;
+
+use crate::ContractError;
+
+/// State of the tokenfactory admin of the alloyed denom, which is held by the contract
+/// until it is handed over, e.g. to a successor contract during migration.
+#[cw_serde]
+#[derive(Default)]
+pub enum DenomAdminState {
+    #[default]
+    Contract,
+    Transferring {
+        candidate: Addr,
+    },
+    Transferred(Addr),
+}
+
+pub struct DenomAdmin<'a> {
+    state: Item<'a, DenomAdminState>,
+}
+
+impl<'a> DenomAdmin<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            state: Item::new(namespace),
+        }
+    }
+
+    pub fn state(&self, storage: &dyn Storage) -> StdResult<DenomAdminState> {
+        self.state.may_load(storage).map(Option::unwrap_or_default)
+    }
+
+    /// Address holding the tokenfactory admin, `contract` unless it has been transferred
+    pub fn current(&self, storage: &dyn Storage, contract: &Addr) -> StdResult<Addr> {
+        match self.state(storage)? {
+            DenomAdminState::Transferred(admin) => Ok(admin),
+            _ => Ok(contract.clone()),
+        }
+    }
+
+    /// Candidate that can claim the tokenfactory admin, `None` if there is none
+    pub fn candidate(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        match self.state(storage)? {
+            DenomAdminState::Transferring { candidate } => Ok(Some(candidate)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Propose `candidate` to claim the tokenfactory admin, overrides the previous candidate
+    pub fn transfer(
+        &self,
+        storage: &mut dyn Storage,
+        candidate: Addr,
+    ) -> Result<(), ContractError> {
+        self.ensure_not_transferred(storage)?;
+
+        self.state
+            .save(storage, &DenomAdminState::Transferring { candidate })
+            .map_err(Into::into)
+    }
+
+    /// Cancel the transfer, the contract keeps holding the tokenfactory admin
+    pub fn cancel_transfer(&self, storage: &mut dyn Storage) -> Result<(), ContractError> {
+        ensure!(
+            self.candidate(storage)?.is_some(),
+            ContractError::InoperableAlloyedDenomAdminTransferringState {}
+        );
+
+        self.state
+            .save(storage, &DenomAdminState::Contract)
+            .map_err(Into::into)
+    }
+
+    /// Claim the tokenfactory admin, `sender` must be the candidate
+    pub fn claim(&self, storage: &mut dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+        ensure!(
+            self.candidate(storage)?.as_ref() == Some(sender),
+            ContractError::Unauthorized {}
+        );
+
+        self.state
+            .save(storage, &DenomAdminState::Transferred(sender.clone()))
+            .map_err(Into::into)
+    }
+
+    /// Hand the tokenfactory admin over to `successor` directly, without it claiming first
+    pub fn hand_over(
+        &self,
+        storage: &mut dyn Storage,
+        successor: Addr,
+    ) -> Result<(), ContractError> {
+        self.ensure_not_transferred(storage)?;
+
+        self.state
+            .save(storage, &DenomAdminState::Transferred(successor))
+            .map_err(Into::into)
+    }
+
+    fn ensure_not_transferred(&self, storage: &dyn Storage) -> Result<(), ContractError> {
+        ensure!(
+            !matches!(self.state(storage)?, DenomAdminState::Transferred(_)),
+            ContractError::InoperableAlloyedDenomAdminTransferringState {}
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_transfer_and_claim() {
+        let mut deps = mock_dependencies();
+        let denom_admin = DenomAdmin::new("denom_admin");
+
+        let contract = Addr::unchecked("contract");
+        let successor = Addr::unchecked("successor");
+        let other = Addr::unchecked("other");
+
+        // held by the contract by default
+        assert_eq!(
+            denom_admin.current(&deps.storage, &contract).unwrap(),
+            contract
+        );
+        assert_eq!(
+            denom_admin.cancel_transfer(&mut deps.storage).unwrap_err(),
+            ContractError::InoperableAlloyedDenomAdminTransferringState {}
+        );
+
+        denom_admin
+            .transfer(&mut deps.storage, other.clone())
+            .unwrap();
+        denom_admin.cancel_transfer(&mut deps.storage).unwrap();
+        assert_eq!(denom_admin.candidate(&deps.storage).unwrap(), None);
+
+        denom_admin
+            .transfer(&mut deps.storage, successor.clone())
+            .unwrap();
+        assert_eq!(
+            denom_admin.candidate(&deps.storage).unwrap(),
+            Some(successor.clone())
+        );
+
+        // only candidate can claim
+        assert_eq!(
+            denom_admin.claim(&mut deps.storage, &other).unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            denom_admin.current(&deps.storage, &contract).unwrap(),
+            contract
+        );
+
+        denom_admin.claim(&mut deps.storage, &successor).unwrap();
+        assert_eq!(
+            denom_admin.current(&deps.storage, &contract).unwrap(),
+            successor
+        );
+
+        // cannot be transferred again by the contract
+        assert_eq!(
+            denom_admin
+                .transfer(&mut deps.storage, other.clone())
+                .unwrap_err(),
+            ContractError::InoperableAlloyedDenomAdminTransferringState {}
+        );
+    }
+
+    #[test]
+    fn test_hand_over() {
+        let mut deps = mock_dependencies();
+        let denom_admin = DenomAdmin::new("denom_admin");
+
+        let contract = Addr::unchecked("contract");
+        let candidate = Addr::unchecked("candidate");
+        let successor = Addr::unchecked("successor");
+
+        // overrides pending transfer
+        denom_admin
+            .transfer(&mut deps.storage, candidate.clone())
+            .unwrap();
+        denom_admin
+            .hand_over(&mut deps.storage, successor.clone())
+            .unwrap();
+        assert_eq!(
+            denom_admin.current(&deps.storage, &contract).unwrap(),
+            successor
+        );
+        assert_eq!(denom_admin.candidate(&deps.storage).unwrap(), None);
+        assert_eq!(
+            denom_admin
+                .claim(&mut deps.storage, &candidate)
+                .unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+
+        // cannot be handed over again by the contract
+        assert_eq!(
+            denom_admin
+                .hand_over(&mut deps.storage, candidate)
+                .unwrap_err(),
+            ContractError::InoperableAlloyedDenomAdminTransferringState {}
+        );
+    }
+}
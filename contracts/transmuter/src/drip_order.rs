@@ -0,0 +1,183 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin, Decimal, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::ContractError;
+
+/// Large swap exact amount in that gets executed incrementally by cranking,
+/// as much as limiters allow at each crank, instead of reverting as a whole.
+#[cw_serde]
+pub struct DripOrder {
+    pub id: u64,
+    pub owner: Addr,
+    /// Remaining token in that has not been swapped yet
+    pub token_in: Coin,
+    pub token_out_denom: String,
+    /// Max amount of token in paid per unit of token out at each fill, fills above it are skipped
+    pub max_execution_price: Option<Decimal>,
+    /// Total amount of token out that has been sent to the owner
+    pub token_out_filled: Uint128,
+}
+
+pub struct DripOrders<'a> {
+    orders: Map<'a, u64, DripOrder>,
+    next_id: Item<'a, u64>,
+    /// Id of the last order processed by crank, so that the next crank continues after it
+    crank_cursor: Item<'a, u64>,
+}
+
+impl<'a> DripOrders<'a> {
+    pub const fn new(
+        orders_namespace: &'a str,
+        next_id_namespace: &'a str,
+        crank_cursor_namespace: &'a str,
+    ) -> Self {
+        Self {
+            orders: Map::new(orders_namespace),
+            next_id: Item::new(next_id_namespace),
+            crank_cursor: Item::new(crank_cursor_namespace),
+        }
+    }
+
+    /// Create a new order of `owner` to swap `token_in` for `token_out_denom`, returns its id
+    pub fn create(
+        &self,
+        storage: &mut dyn Storage,
+        owner: Addr,
+        token_in: Coin,
+        token_out_denom: String,
+        max_execution_price: Option<Decimal>,
+    ) -> StdResult<u64> {
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+
+        self.orders.save(
+            storage,
+            id,
+            &DripOrder {
+                id,
+                owner,
+                token_in,
+                token_out_denom,
+                max_execution_price,
+                token_out_filled: Uint128::zero(),
+            },
+        )?;
+
+        Ok(id)
+    }
+
+    pub fn get(&self, storage: &dyn Storage, id: u64) -> Result<DripOrder, ContractError> {
+        self.orders
+            .may_load(storage, id)?
+            .ok_or(ContractError::DripOrderNotFound { order_id: id })
+    }
+
+    /// Save `order`, or remove it if all of its token in has been swapped
+    pub fn update(&self, storage: &mut dyn Storage, order: &DripOrder) -> StdResult<()> {
+        if order.token_in.amount.is_zero() {
+            self.remove(storage, order.id);
+            Ok(())
+        } else {
+            self.orders.save(storage, order.id, order)
+        }
+    }
+
+    pub fn remove(&self, storage: &mut dyn Storage, id: u64) {
+        self.orders.remove(storage, id)
+    }
+
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<DripOrder>> {
+        self.orders
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, order)| order))
+            .collect()
+    }
+
+    /// Up to `limit` orders to be processed by the next crank, continuing after the
+    /// last processed order and wrapping around, so that every order gets its turn.
+    pub fn next_crank_batch(
+        &self,
+        storage: &mut dyn Storage,
+        limit: usize,
+    ) -> StdResult<Vec<DripOrder>> {
+        let cursor = self.crank_cursor.may_load(storage)?;
+
+        let after_cursor = self.orders.range(
+            storage,
+            cursor.map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        );
+        let until_cursor = self.orders.range(
+            storage,
+            None,
+            cursor.map(Bound::inclusive),
+            Order::Ascending,
+        );
+
+        let batch = after_cursor
+            .chain(until_cursor)
+            .take(limit)
+            .map(|item| item.map(|(_, order)| order))
+            .collect::<StdResult<Vec<_>>>()?;
+
+        if let Some(last) = batch.last() {
+            self.crank_cursor.save(storage, &last.id)?;
+        }
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_next_crank_batch() {
+        let mut deps = mock_dependencies();
+        let drip_orders = DripOrders::new("drip_orders", "next_id", "crank_cursor");
+
+        let owner = Addr::unchecked("owner");
+        for _ in 0..5 {
+            drip_orders
+                .create(
+                    &mut deps.storage,
+                    owner.clone(),
+                    Coin::new(1000, "denoma"),
+                    "denomb".to_string(),
+                    None,
+                )
+                .unwrap();
+        }
+
+        let ids = |orders: Vec<DripOrder>| -> Vec<u64> {
+            orders.into_iter().map(|order| order.id).collect()
+        };
+
+        let batch = drip_orders.next_crank_batch(&mut deps.storage, 2).unwrap();
+        assert_eq!(ids(batch), vec![0, 1]);
+
+        let batch = drip_orders.next_crank_batch(&mut deps.storage, 2).unwrap();
+        assert_eq!(ids(batch), vec![2, 3]);
+
+        // wraps around
+        let batch = drip_orders.next_crank_batch(&mut deps.storage, 2).unwrap();
+        assert_eq!(ids(batch), vec![4, 0]);
+
+        // fully swapped order is removed
+        let mut order = drip_orders.get(&deps.storage, 1).unwrap();
+        order.token_in.amount = Uint128::zero();
+        drip_orders.update(&mut deps.storage, &order).unwrap();
+        assert_eq!(
+            drip_orders.get(&deps.storage, 1).unwrap_err(),
+            ContractError::DripOrderNotFound { order_id: 1 }
+        );
+
+        // never returns the same order twice in a batch
+        let batch = drip_orders.next_crank_batch(&mut deps.storage, 10).unwrap();
+        assert_eq!(ids(batch), vec![2, 3, 4, 0]);
+    }
+}
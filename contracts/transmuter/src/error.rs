@@ -1,10 +1,11 @@
 use cosmwasm_std::{
-    CheckedFromRatioError, CheckedMultiplyRatioError, Coin, ConversionOverflowError, Decimal,
-    DivideByZeroError, OverflowError, StdError, Timestamp, Uint128, Uint64,
+    Addr, CheckedFromRatioError, CheckedMultiplyRatioError, Coin, ConversionOverflowError, Decimal,
+    DivideByZeroError, Instantiate2AddressError, OverflowError, StdError, Timestamp, Uint128,
+    Uint64,
 };
 use thiserror::Error;
 
-use crate::math::MathError;
+use crate::{math::MathError, msg::LimiterRejection};
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
@@ -23,12 +24,30 @@ pub enum ContractError {
     #[error("Funds must contain at least one token")]
     AtLeastSingleTokenExpected {},
 
+    #[error("Funds must contain exactly one token")]
+    SingleTokenExpected {},
+
     #[error("Denom has no supply, it might be an invalid denom: {denom}")]
     DenomHasNoSupply { denom: String },
 
+    #[error("IBC voucher `{denom}` has no supply yet, retry in a later block")]
+    IbcVoucherSupplyUnavailable { denom: String },
+
     #[error("Subdenom must not contain extra parts (separated by '/'): {subdenom}")]
     SubDenomExtraPartsNotAllowed { subdenom: String },
 
+    #[error("Adopted denom must be an alloyed denom with the same subdenom: {denom}")]
+    InvalidAdoptedAlloyedDenom { denom: String },
+
+    #[error("Invalid instantiation: {}", join_violations(.violations))]
+    InvalidInstantiation { violations: Vec<ContractError> },
+
+    #[error("Adopted alloyed denom supply {supply} is not backed by pool value {pool_value}")]
+    UnbackedAdoptedAlloyedSupply {
+        supply: Uint128,
+        pool_value: Uint128,
+    },
+
     #[error("Unable to join pool with denom: {denom}: expected one of: {expected_denom:?}")]
     InvalidJoinPoolDenom {
         denom: String,
@@ -69,6 +88,9 @@ pub enum ContractError {
     #[error("Invalid swap fee: expected: {expected}, actual: {actual}")]
     InvalidSwapFee { expected: Decimal, actual: Decimal },
 
+    #[error("Invalid swap fee rate: {rate}, must be less than 1")]
+    InvalidSwapFeeRate { rate: Decimal },
+
     /// This error should never occur, but is here for completeness
     /// This will happens if and only if calculated token out and expected token out are not equal
     #[error("Invalid token out amount: expected: {expected}, actual: {actual}")]
@@ -93,27 +115,263 @@ pub enum ContractError {
     #[error("Excessive token in required: max acceptable token in: {limit}, required: {required}")]
     ExcessiveRequiredTokenIn { limit: Uint128, required: Uint128 },
 
+    #[error(
+        "Execution price bound exceeded: max execution price: {max_execution_price}, but got execution price: {execution_price}"
+    )]
+    ExecutionPriceBoundExceeded {
+        max_execution_price: Decimal,
+        execution_price: Decimal,
+    },
+
+    #[error("Affiliate share exceeded: max share: {max_share_bps} bps, but got: {share_bps} bps")]
+    AffiliateShareExceeded { max_share_bps: u16, share_bps: u16 },
+
+    #[error("Invalid affiliate share: {share_bps} bps, must not exceed 10000 bps")]
+    InvalidAffiliateShare { share_bps: u16 },
+
+    #[error("Fee collector is not set")]
+    FeeCollectorNotSet {},
+
+    #[error("No swap fee accrued to withdraw")]
+    NoAccruedFees {},
+
+    #[error("Deadline exceeded: deadline: {deadline}, block time: {block_time}")]
+    DeadlineExceeded {
+        deadline: Timestamp,
+        block_time: Timestamp,
+    },
+
     #[error("The pool is currently inactive")]
     InactivePool {},
 
     #[error("Attempt to set pool to active status to {status} when it is already {status}")]
     UnchangedActiveStatus { status: bool },
 
+    #[error("Reactivation time {reactivate_at} must be in the future")]
+    InvalidReactivationTime { reactivate_at: Timestamp },
+
+    #[error("Reason and reactivation time can only be set when deactivating pool")]
+    UnexpectedDeactivationOnActivation {},
+
     #[error("Duplicated pool asset denom: {denom}")]
     DuplicatedPoolAssetDenom { denom: String },
 
     #[error("Pool asset not be share denom")]
     ShareDenomNotAllowedAsPoolAsset {},
 
+    #[error("Alloyed asset is not allowed in batch swap, only transmutes between pool assets")]
+    AlloyedAssetNotAllowedInBatchSwap {},
+
+    #[error("Funds mismatch: expected: {expected:?}, actual: {actual:?}")]
+    FundsMismatch {
+        expected: Vec<Coin>,
+        actual: Vec<Coin>,
+    },
+
     #[error("Token in must not have the same denom as token out: {denom}")]
     SameDenomNotAllowed { denom: String },
 
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Unauthorized to `{action}`{}", authorized_roles_hint(.required_roles))]
+    UnauthorizedAction {
+        action: String,
+        /// Roles authorized for `action`, empty if it is not restricted to a role,
+        /// e.g. cancelling someone else's drip order
+        required_roles: Vec<String>,
+    },
+
+    #[error("Address is already a moderator: {address}")]
+    ModeratorAlreadyExists { address: Addr },
+
+    #[error("Address is not an additional moderator: {address}")]
+    ModeratorNotFound { address: Addr },
+
+    #[error("Swap amount of {denom} is below minimum: minimum: {min_amount}, got: {amount}")]
+    SwapAmountBelowMinimum {
+        denom: String,
+        min_amount: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("Invalid max outflow: {max_outflow}, must be greater than 0 and not exceed 1")]
+    InvalidMaxOutflow { max_outflow: Decimal },
+
+    #[error("Insufficient allowance of {operator} from {owner}: required: {required}, available: {available}")]
+    InsufficientAllowance {
+        owner: Addr,
+        operator: Addr,
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("`alloyed_amount_in` must be set when swapping on behalf of `owner`, and only then")]
+    InvalidOperatorSwapAmountIn {},
+
+    #[error("Invalid IBC forward: channel, receiver and timeout must be set")]
+    InvalidIbcForward {},
+
+    #[error("Cannot set both `to_address` and `ibc_forward`")]
+    IbcForwardWithToAddress {},
+
+    #[error("Invalid swap commitment: must be a sha256 hash")]
+    InvalidSwapCommitment {},
+
+    #[error("Swap commitment not found")]
+    SwapCommitmentNotFound {},
+
+    #[error("Swap must be revealed after the block it was committed: committed height: {committed_height}")]
+    SwapRevealedTooEarly { committed_height: u64 },
+
+    #[error("Drip order not found: {order_id}")]
+    DripOrderNotFound { order_id: u64 },
+
+    #[error("Alloyed asset transfer restricted for: {address}")]
+    AlloyedTransferRestricted { address: Addr },
+
+    #[error("Redemption exceeds pool value: required: {required}, available: {available}")]
+    RedemptionExceedsPoolValue {
+        required: Uint128,
+        available: Uint128,
+    },
+
+    #[error("Alloyed supply cap exceeded: cap: {cap}, supply after mint: {supply_after_mint}")]
+    AlloyedSupplyCapExceeded {
+        cap: Uint128,
+        supply_after_mint: Uint128,
+    },
+
+    #[error("Invalid supply cap schedule: steps must be in strictly increasing time")]
+    InvalidSupplyCapSchedule {},
+
+    #[error("Pool must be inactive")]
+    ActivePool {},
+
+    #[error("Unchanged alloyed normalization factor: {normalization_factor}")]
+    UnchangedAlloyedNormalizationFactor { normalization_factor: Uint128 },
+
+    #[error("Alloyed denom admin transferring state is inoperable for the requested operation")]
+    InoperableAlloyedDenomAdminTransferringState {},
+
+    #[error("Alloyed denom admin can only be transferred when pool is inactive or drained")]
+    AlloyedDenomAdminTransferNotAllowed {},
+
+    #[error("Denom is blocked from being a pool asset: {denom}")]
+    BlockedPoolAssetDenom { denom: String },
+
+    #[error("Execute message is frozen: {method}")]
+    FrozenMessage { method: String },
+
+    #[error("Execute message can not be frozen: {method}")]
+    UnfreezableMessage { method: String },
+
+    #[error("Change must be queued while timelock is enabled")]
+    TimelockedChangeMustBeQueued {},
+
+    #[error("Timelock is not enabled")]
+    TimelockNotEnabled {},
+
+    #[error("Pending change not found: {id}")]
+    PendingChangeNotFound { id: u64 },
+
+    #[error("Pending change {id} is not effective until {effective_at}")]
+    PendingChangeNotEffective { id: u64, effective_at: Timestamp },
+
+    #[error("Action requires approvals from approvers, it must be proposed instead")]
+    ApprovalRequired {},
+
+    #[error("Approval is not enabled")]
+    ApprovalNotEnabled {},
+
+    #[error("Invalid approval threshold: {threshold}, must be within 1 and {approvers}")]
+    InvalidApprovalThreshold { threshold: u32, approvers: u32 },
+
+    #[error("Duplicate approver: {approver}")]
+    DuplicateApprover { approver: Addr },
+
+    #[error("Action proposal not found: {id}")]
+    ActionProposalNotFound { id: u64 },
+
+    #[error("Config snapshot not found: {id}")]
+    ConfigSnapshotNotFound { id: u64 },
+
+    #[error("Action proposal {id} has already been approved by {approver}")]
+    AlreadyApproved { id: u64, approver: Addr },
+
+    #[error("Action proposal {id} has {approvals} approvals, requires {threshold}")]
+    InsufficientApprovals {
+        id: u64,
+        approvals: u32,
+        threshold: u32,
+    },
+
+    #[error("Trader not allowed: {address}")]
+    TraderNotAllowed { address: Addr },
+
     #[error("Admin transferring state is inoperable for the requested operation")]
     InoperableAdminTransferringState {},
 
+    #[error("Admin must not be set when chain governance is the admin")]
+    AdminConflictsWithGovAdmin {},
+
+    #[error("Admin has been renounced")]
+    AdminRenounced {},
+
+    #[error("Admin transfer expired at {expired_at}")]
+    AdminTransferExpired { expired_at: Timestamp },
+
+    #[error("Challenge does not match the one set for the admin transfer")]
+    AdminTransferChallengeMismatch {},
+
+    #[error("State export is disabled")]
+    StateExportDisabled {},
+
+    #[error("Target weight {target_weight} can't be reached")]
+    UnreachableTargetWeight { target_weight: Decimal },
+
+    #[error("Interchain query contract is not set")]
+    IcqContractNotSet {},
+
+    #[error("Remote asset {denom} is already pending")]
+    RemoteAssetAlreadyPending { denom: String },
+
+    #[error("Pending remote asset not found for query: {query_id}")]
+    RemoteAssetNotFound { query_id: u64 },
+
+    #[error("Sibling pool not found for alloyed denom: {denom}")]
+    SiblingPoolNotFound { denom: String },
+
+    #[error("Alloyed denom of sibling pool {denom} is already swappable in this pool")]
+    SiblingPoolDenomInPool { denom: String },
+
+    #[error("Alloyed asset is not held in the share ledger")]
+    ShareLedgerNotEnabled {},
+
+    #[error("Not supported when alloyed asset is held in the share ledger")]
+    UnsupportedByShareLedger {},
+
+    #[error("Pool creation fee must be non-zero coins of distinct denoms")]
+    InvalidPoolCreationFee {},
+
+    #[error("Insufficient pool creation fee: required: {required}, provided: {provided}")]
+    InsufficientPoolCreationFee { required: Coin, provided: Coin },
+
+    #[error("Meta-transaction public key must be a compressed secp256k1 key")]
+    InvalidMetaTxPubkey {},
+
+    #[error("Meta-transaction public key is not the key of {signer}")]
+    MetaTxPubkeyMismatch { signer: String },
+
+    #[error("Invalid meta-transaction nonce: expected: {expected}, actual: {actual}")]
+    InvalidMetaTxNonce { expected: u64, actual: u64 },
+
+    #[error("Invalid meta-transaction signature")]
+    InvalidMetaTxSignature {},
+
+    #[error("Meta-transaction is signed for another chain or contract")]
+    MetaTxDomainMismatch {},
+
     #[error("Limiter count for {denom} exceed maximum per denom: {max}")]
     MaxLimiterCountPerDenomExceeded { denom: String, max: Uint64 },
 
@@ -144,6 +402,28 @@ pub enum ContractError {
     #[error("Division count must not exceed {max_division_count}")]
     DivisionCountExceeded { max_division_count: Uint64 },
 
+    #[error("Limiter tuning period must be greater than zero")]
+    ZeroLimiterTuningPeriod {},
+
+    #[error("Limiter `{label}` for `{denom}` can not be tuned by limiter tuner")]
+    LimiterTuningNotAllowed { denom: String, label: String },
+
+    #[error("Limiter `{label}` for `{denom}` can only be tuned within [{min}, {max}] in the current period, got {value}")]
+    LimiterTuningOutOfBounds {
+        denom: String,
+        label: String,
+        value: Decimal,
+        min: Decimal,
+        max: Decimal,
+    },
+
+    #[error("Rate limit exceeded for `{subject}`: at most {max_actions} actions within {window_size} nanoseconds")]
+    RateLimitExceeded {
+        subject: String,
+        max_actions: u64,
+        window_size: Uint64,
+    },
+
     #[error("Moving average is undefined due to zero elapsed time since limiter started tracking")]
     UndefinedMovingAverage {},
 
@@ -165,7 +445,8 @@ pub enum ContractError {
     LimiterAlreadyExists { denom: String, label: String },
 
     #[error(
-        "Upper limit exceeded for `{denom}`, upper limit is {upper_limit}, but the resulted weight is {value}"
+        "Upper limit exceeded for `{denom}`, upper limit is {upper_limit}, but the resulted weight is {value}; {}",
+        LimiterRejection::payload(.denom, .upper_limit, .value)
     )]
     UpperLimitExceeded {
         denom: String,
@@ -200,6 +481,28 @@ pub enum ContractError {
     #[error("{0}")]
     MathError(#[from] MathError),
 
+    #[error("{0}")]
+    Instantiate2AddressError(Instantiate2AddressError),
+
+    #[error("Migrating to {target_version} would downgrade schema from {highest_version}, the highest ever applied; set `force` to override")]
+    SchemaDowngrade {
+        target_version: String,
+        highest_version: String,
+    },
+
+    #[error("Migration to {version} was already applied, state is of schema {highest_version}; set `force` to re-apply")]
+    SchemaRemigration {
+        version: String,
+        highest_version: String,
+    },
+
+    #[error("Invalid schema version: {version}")]
+    InvalidSchemaVersion { version: String },
+
+    /// Not a failure, dry run migrations always fail so that nothing is committed
+    #[error("Migration dry run succeeded, nothing is committed: {report}")]
+    MigrationDryRun { report: String },
+
     /// This error should never occur
     #[error("")]
     Never,
@@ -213,6 +516,29 @@ pub fn nonpayable(funds: &[Coin]) -> Result<(), ContractError> {
     }
 }
 
+fn authorized_roles_hint(required_roles: &[String]) -> String {
+    if required_roles.is_empty() {
+        String::new()
+    } else {
+        format!(", requires one of: {}", required_roles.join(", "))
+    }
+}
+
+fn join_violations(violations: &[ContractError]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+pub fn one_coin(funds: &[Coin]) -> Result<Coin, ContractError> {
+    match funds {
+        [coin] => Ok(coin.clone()),
+        _ => Err(ContractError::SingleTokenExpected {}),
+    }
+}
+
 pub fn non_empty_input_required<T>(field_name: &str, value: &[T]) -> Result<(), ContractError> {
     if value.is_empty() {
         Err(ContractError::NonEmptyInputRequired {
@@ -0,0 +1,884 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::Sub,
+};
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    attr, Addr, Attribute, Coin, Decimal, Event, Response, StdResult, Storage, Uint128,
+};
+
+use crate::{
+    contract::Transmuter,
+    debug,
+    limiter::{ChangeLimiter, Division, Limiter},
+    subscription::attribute_key,
+    swap::{AlloyedSupplyChange, SWAP_EVENT_TYPE},
+    swap_fee::FeeDestination,
+    ContractError,
+};
+
+/// Type of the event emitted for each pool asset whose balance changed
+pub const BALANCE_CHANGE_EVENT_TYPE: &str = "transmuter-balance_change";
+
+/// Type of the event emitted for each pool asset whose weight changed
+pub const WEIGHT_CHANGE_EVENT_TYPE: &str = "transmuter-weight_change";
+
+/// Type of the event emitted for each change limiter whose state got updated
+pub const LIMITER_UPDATE_EVENT_TYPE: &str = "transmuter-limiter_update";
+
+/// Type of the event emitted for each denom of swap fee charged
+pub const FEE_EVENT_TYPE: &str = "transmuter-fee";
+
+/// Type of the event emitted for each denom of swap fee accrued to the pool or the collector
+pub const FEE_ACCRUAL_EVENT_TYPE: &str = "transmuter-fee_accrual";
+
+/// Type of the event emitted for each share of swap fee paid out to an affiliate
+pub const FEE_REBATE_EVENT_TYPE: &str = "transmuter-fee_rebate";
+
+/// Type of the event emitted for each denom of accrued swap fee withdrawn by the collector
+pub const FEE_WITHDRAWAL_EVENT_TYPE: &str = "transmuter-fee_withdrawal";
+
+/// Type of the event emitted, while `state_diff_events` param is enabled,
+/// for every message that changes the pool
+pub const STATE_DIFF_EVENT_TYPE: &str = "transmuter-state_diff";
+
+/// Order of events in execute and sudo responses. Events of the same type keep the order
+/// they were emitted in, and events of types not listed here come after, in emitted order.
+const EVENT_ORDER: &[&str] = &[
+    SWAP_EVENT_TYPE,
+    BALANCE_CHANGE_EVENT_TYPE,
+    WEIGHT_CHANGE_EVENT_TYPE,
+    LIMITER_UPDATE_EVENT_TYPE,
+    FEE_EVENT_TYPE,
+    FEE_REBATE_EVENT_TYPE,
+    FEE_ACCRUAL_EVENT_TYPE,
+    FEE_WITHDRAWAL_EVENT_TYPE,
+    STATE_DIFF_EVENT_TYPE,
+];
+
+/// Execute and sudo messages that never change pool balances, weights nor change limiter states,
+/// so that the state before them is only snapshotted if state diff events are enabled.
+/// Messages not listed here are snapshotted, hence any new message is until it gets listed.
+const POOL_STATE_PRESERVING_METHODS: &[&str] = &[
+    // sudo
+    "block_before_send",
+    "track_before_send",
+    // config
+    "set_icq_contract",
+    "register_sibling_pool",
+    "deregister_sibling_pool",
+    "cancel_remote_asset",
+    "set_blocked_pool_asset_denom",
+    "set_frozen_message",
+    "set_alloyed_denom_metadata",
+    "cache_pool_asset_metadata",
+    "set_swap_fee",
+    "set_swap_fee_exemption",
+    "withdraw_accrued_fees",
+    "set_max_affiliate_share",
+    "set_dynamic_swap_fee",
+    "set_permissioned_mode",
+    "set_allowed_trader",
+    "set_alloyed_transfer_restriction_mode",
+    "set_alloyed_transfer_restricted_address",
+    "set_protocol_address",
+    "set_min_swap_amount",
+    "set_circuit_breaker",
+    "set_swap_hook",
+    "set_flow_quota",
+    "set_supply_hook",
+    "set_pool_registry",
+    "set_monitoring_contract",
+    "set_default_redemption_strategy",
+    "set_alloyed_supply_cap",
+    "set_alloyed_supply_cap_schedule",
+    "set_timelock_delay",
+    "queue_change",
+    "cancel_change",
+    "set_approvers",
+    "propose_action",
+    "approve_action",
+    "cancel_action",
+    // accounts and orders
+    "set_operator",
+    "transfer_shares",
+    "commit_swap",
+    "submit_drip_order",
+    "cancel_drip_order",
+    // roles
+    "transfer_admin",
+    "cancel_admin_transfer",
+    "reject_admin_transfer",
+    "claim_admin",
+    "renounce_adminship",
+    "confirm_renounce_adminship",
+    "transfer_alloyed_denom_admin",
+    "cancel_alloyed_denom_admin_transfer",
+    "claim_alloyed_denom_admin",
+    "hand_over_to_successor",
+    "assign_moderator",
+    "add_moderator",
+    "remove_moderator",
+    "grant_role",
+    "revoke_role",
+    "set_role_rate_limit",
+];
+
+/// Keys of attributes holding the address of an account involved in a flow, left out of
+/// responses while the `anonymized_events` param is enabled
+const ACCOUNT_ATTRIBUTE_KEYS: &[&str] = &[
+    "sender",
+    "recipient",
+    "receiver",
+    "owner",
+    "operator",
+    "signer",
+    "relayer",
+    "from",
+    "to",
+];
+
+/// Version of the shape of the events in [EVENT_ORDER], emitted as their `event_version`
+/// attribute. Bumped whenever an attribute of any of them is renamed, removed or changes meaning.
+pub const EVENT_VERSION: u32 = 1;
+
+/// Attribute `key` of events of type `event_type` that used to be named `legacy_key`,
+/// before event version `renamed_in`
+pub struct LegacyAttribute {
+    pub event_type: &'static str,
+    pub key: &'static str,
+    pub legacy_key: &'static str,
+    pub renamed_in: u32,
+}
+
+/// Renamed attributes, also emitted under their legacy key for one release window, i.e. while
+/// [EVENT_VERSION] is the version they were renamed in, so that indexers can migrate without
+/// gaps. Entries are inert once the event version is bumped again and can be dropped then.
+const LEGACY_ATTRIBUTES: &[LegacyAttribute] = &[];
+
+/// Alloyed asset minted and burned by the message being executed. Alloyed supply only
+/// reflects them once the mint and burn messages are executed, so they are tallied here.
+#[cw_serde]
+#[derive(Default)]
+pub struct AlloyedSupplyDelta {
+    pub minted: Uint128,
+    pub burned: Uint128,
+}
+
+/// Pool balances, weights and change limiter states before a message, and whether state diff
+/// events are enabled, to emit events for what the message changed
+pub struct StateSnapshot {
+    balances: BTreeMap<String, Uint128>,
+    weights: BTreeMap<String, Decimal>,
+    change_limiters: BTreeMap<(String, String), (Decimal, Vec<Division>)>,
+    state_diff_events_enabled: bool,
+}
+
+impl Transmuter<'_> {
+    pub(crate) fn state_snapshot(
+        &self,
+        storage: &dyn Storage,
+    ) -> Result<StateSnapshot, ContractError> {
+        // debug traces of a message start with its snapshot, earlier ones are of queries
+        debug::take_traces();
+
+        let pool = self.pool.load(storage)?;
+        let balances = pool
+            .pool_assets
+            .iter()
+            .map(|asset| (asset.denom().to_string(), asset.amount()))
+            .collect();
+        let weights = pool.weights_map()?;
+
+        let change_limiters = self
+            .limiters
+            .list_limiters(storage)?
+            .into_iter()
+            .filter_map(|(key, limiter)| match limiter {
+                Limiter::ChangeLimiter(limiter) => Some((key, change_limiter_state(&limiter))),
+                Limiter::StaticLimiter(_) => None,
+            })
+            .collect();
+
+        Ok(StateSnapshot {
+            balances,
+            weights,
+            change_limiters,
+            state_diff_events_enabled: self.is_state_diff_events_enabled(storage)?,
+        })
+    }
+
+    /// Snapshot of the state before `operation`, unless it is one of
+    /// [POOL_STATE_PRESERVING_METHODS] and state diff events are disabled
+    pub(crate) fn state_snapshot_before(
+        &self,
+        storage: &dyn Storage,
+        operation: &str,
+    ) -> Result<Option<StateSnapshot>, ContractError> {
+        if POOL_STATE_PRESERVING_METHODS.contains(&operation)
+            && !self.is_state_diff_events_enabled(storage)?
+        {
+            // debug traces of a message start here, earlier ones are of queries
+            debug::take_traces();
+            return Ok(None);
+        }
+
+        self.state_snapshot(storage).map(Some)
+    }
+
+    /// Add balance change, weight change, limiter update and state diff events for what
+    /// `operation` changed since `before`, if snapshotted, and debug events if enabled,
+    /// to `response`, then order its events by [EVENT_ORDER] and version them
+    pub(crate) fn with_ordered_events(
+        &self,
+        storage: &mut dyn Storage,
+        operation: &str,
+        before: Option<StateSnapshot>,
+        response: Response,
+    ) -> Result<Response, ContractError> {
+        // taken before the snapshot after, which discards earlier traces
+        let debug_traces = debug::take_traces();
+
+        let alloyed_supply_delta = self.pending_alloyed_supply_delta.may_load(storage)?;
+        if alloyed_supply_delta.is_some() {
+            self.pending_alloyed_supply_delta.remove(storage);
+        }
+
+        let response = match before {
+            Some(before) => {
+                let after = self.state_snapshot(storage)?;
+
+                // state diff is emitted if it was enabled when the message started
+                let state_diff_event = if before.state_diff_events_enabled {
+                    state_diff_event(
+                        &before.balances,
+                        &after.balances,
+                        &alloyed_supply_delta.unwrap_or_default(),
+                        &before.weights,
+                        &after.weights,
+                    )
+                } else {
+                    None
+                };
+
+                response
+                    .add_events(balance_change_events(&before.balances, &after.balances))
+                    .add_events(weight_change_events(
+                        operation,
+                        &before.weights,
+                        &after.weights,
+                    ))
+                    .add_events(limiter_update_events(
+                        &before.change_limiters,
+                        &after.change_limiters,
+                    ))
+                    .add_events(state_diff_event)
+            }
+            None => response,
+        };
+
+        let mut response = response.add_events(debug_traces);
+
+        // stable sort keeps the emitted order within each type
+        response.events.sort_by_key(|event| event_rank(&event.ty));
+
+        response.events = response
+            .events
+            .into_iter()
+            .map(|event| versioned_event(event, EVENT_VERSION, LEGACY_ATTRIBUTES))
+            .collect();
+
+        Ok(response)
+    }
+
+    /// Leave attributes identifying the accounts involved out of `response`, its events
+    /// included, while the `anonymized_events` param is enabled
+    pub(crate) fn with_anonymized_events(
+        &self,
+        storage: &dyn Storage,
+        response: Response,
+    ) -> StdResult<Response> {
+        if !self.is_anonymized_events_enabled(storage)? {
+            return Ok(response);
+        }
+
+        Ok(anonymized(response))
+    }
+
+    /// Tally alloyed asset `amount` minted or burned by the message being executed,
+    /// while state diff events are enabled
+    pub(crate) fn record_alloyed_supply_change(
+        &self,
+        storage: &mut dyn Storage,
+        change: &AlloyedSupplyChange,
+        amount: Uint128,
+    ) -> Result<(), ContractError> {
+        if !self.is_state_diff_events_enabled(storage)? {
+            return Ok(());
+        }
+
+        let mut delta = self
+            .pending_alloyed_supply_delta
+            .may_load(storage)?
+            .unwrap_or_default();
+        match change {
+            AlloyedSupplyChange::Mint { .. } => delta.minted = delta.minted.checked_add(amount)?,
+            AlloyedSupplyChange::Burn { .. } => delta.burned = delta.burned.checked_add(amount)?,
+        }
+        self.pending_alloyed_supply_delta.save(storage, &delta)?;
+
+        Ok(())
+    }
+}
+
+/// Latest value and divisions, which change whenever the limiter gets updated,
+/// unlike its params
+fn change_limiter_state(limiter: &ChangeLimiter) -> (Decimal, Vec<Division>) {
+    (limiter.latest_value(), limiter.divisions().to_vec())
+}
+
+fn event_rank(ty: &str) -> usize {
+    EVENT_ORDER
+        .iter()
+        .position(|t| *t == ty)
+        .unwrap_or(EVENT_ORDER.len())
+}
+
+/// `event` with its `event_version` as first attribute, and attributes renamed in `version`
+/// duplicated under their legacy key at the end, if it is one of the events in [EVENT_ORDER]
+fn versioned_event(mut event: Event, version: u32, legacy_attributes: &[LegacyAttribute]) -> Event {
+    if !EVENT_ORDER.contains(&event.ty.as_str()) {
+        return event;
+    }
+
+    let legacy = event
+        .attributes
+        .iter()
+        .filter_map(|attribute| {
+            legacy_attributes
+                .iter()
+                .find(|legacy| {
+                    legacy.event_type == event.ty
+                        && legacy.key == attribute.key
+                        && legacy.renamed_in == version
+                })
+                .map(|legacy| attr(legacy.legacy_key, &attribute.value))
+        })
+        .collect::<Vec<_>>();
+
+    event
+        .attributes
+        .insert(0, attr("event_version", version.to_string()));
+    event.attributes.extend(legacy);
+    event
+}
+
+/// Balance change event of each denom whose balance differs, ordered by denom.
+/// Denoms removed from the pool are changed to zero balance.
+fn balance_change_events(
+    before: &BTreeMap<String, Uint128>,
+    after: &BTreeMap<String, Uint128>,
+) -> Vec<Event> {
+    let denoms: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    denoms
+        .into_iter()
+        .filter_map(|denom| {
+            let prev = before.get(denom).copied().unwrap_or_default();
+            let balance = after.get(denom).copied().unwrap_or_default();
+            let change = signed_delta(prev, balance)?;
+
+            Some(
+                Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", denom)
+                    .add_attribute("change", change)
+                    .add_attribute("balance", balance),
+            )
+        })
+        .collect()
+}
+
+/// Weight change event of each denom whose weight differs, ordered by denom, tagged with the
+/// `operation` that changed it. Denoms removed from the pool, or all of them if the pool
+/// is drained, are changed to zero weight.
+fn weight_change_events(
+    operation: &str,
+    before: &BTreeMap<String, Decimal>,
+    after: &BTreeMap<String, Decimal>,
+) -> Vec<Event> {
+    let denoms: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    denoms
+        .into_iter()
+        .filter_map(|denom| {
+            let old_weight = before.get(denom).copied().unwrap_or_default();
+            let new_weight = after.get(denom).copied().unwrap_or_default();
+            if old_weight == new_weight {
+                return None;
+            }
+
+            Some(
+                Event::new(WEIGHT_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", denom)
+                    .add_attribute("old_weight", old_weight.to_string())
+                    .add_attribute("new_weight", new_weight.to_string())
+                    .add_attribute("operation", operation),
+            )
+        })
+        .collect()
+}
+
+/// Limiter update event of each change limiter, kept registered, whose state differs,
+/// ordered by denom then label
+fn limiter_update_events(
+    before: &BTreeMap<(String, String), (Decimal, Vec<Division>)>,
+    after: &BTreeMap<(String, String), (Decimal, Vec<Division>)>,
+) -> Vec<Event> {
+    after
+        .iter()
+        .filter(|(key, state)| matches!(before.get(*key), Some(prev) if prev != *state))
+        .map(|((denom, label), (latest_value, _))| {
+            Event::new(LIMITER_UPDATE_EVENT_TYPE)
+                .add_attribute("denom", denom)
+                .add_attribute("label", label)
+                .add_attribute("latest_value", latest_value.to_string())
+        })
+        .collect()
+}
+
+/// State diff event, `None` if nothing changed. Deltas are comma separated `denom:delta`,
+/// e.g. `uaaa:500,ubbb:-500`, ordered by denom.
+fn state_diff_event(
+    balances_before: &BTreeMap<String, Uint128>,
+    balances_after: &BTreeMap<String, Uint128>,
+    alloyed_supply_delta: &AlloyedSupplyDelta,
+    weights_before: &BTreeMap<String, Decimal>,
+    weights_after: &BTreeMap<String, Decimal>,
+) -> Option<Event> {
+    let balance_deltas = denom_deltas(balances_before, balances_after);
+    let alloyed_supply_delta =
+        signed_delta(alloyed_supply_delta.burned, alloyed_supply_delta.minted);
+    let weight_deltas = denom_deltas(weights_before, weights_after);
+
+    if balance_deltas.is_empty() && alloyed_supply_delta.is_none() && weight_deltas.is_empty() {
+        return None;
+    }
+
+    Some(
+        Event::new(STATE_DIFF_EVENT_TYPE)
+            .add_attribute("balance_deltas", balance_deltas)
+            .add_attribute(
+                "alloyed_supply_delta",
+                alloyed_supply_delta.unwrap_or_else(|| "0".to_string()),
+            )
+            .add_attribute("weight_deltas", weight_deltas),
+    )
+}
+
+/// Comma separated `denom:delta` of each denom whose value differs, ordered by denom.
+/// Missing denoms are valued zero.
+fn denom_deltas<T>(before: &BTreeMap<String, T>, after: &BTreeMap<String, T>) -> String
+where
+    T: Copy + Default + PartialOrd + Sub<Output = T> + Display,
+{
+    let denoms: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+
+    denoms
+        .into_iter()
+        .filter_map(|denom| {
+            let delta = signed_delta(
+                before.get(denom).copied().unwrap_or_default(),
+                after.get(denom).copied().unwrap_or_default(),
+            )?;
+            Some(format!("{denom}:{delta}"))
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Signed change from `prev` to `next`, e.g. `500` or `-500`, `None` if unchanged
+fn signed_delta<T>(prev: T, next: T) -> Option<String>
+where
+    T: PartialOrd + Sub<Output = T> + Display,
+{
+    if next > prev {
+        Some((next - prev).to_string())
+    } else if next < prev {
+        Some(format!("-{}", prev - next))
+    } else {
+        None
+    }
+}
+
+/// `response` without [ACCOUNT_ATTRIBUTE_KEYS] attributes, in its attributes and events alike,
+/// including the versioned `sender` of the subscription event
+fn anonymized(mut response: Response) -> Response {
+    let subscription_sender_key = attribute_key("sender");
+    let is_kept = |attribute: &Attribute| {
+        !ACCOUNT_ATTRIBUTE_KEYS.contains(&attribute.key.as_str())
+            && attribute.key != subscription_sender_key
+    };
+
+    response.attributes.retain(is_kept);
+    for event in response.events.iter_mut() {
+        event.attributes.retain(is_kept);
+    }
+
+    response
+}
+
+/// Fee event of each non-zero `fee` coin
+pub fn fee_events(fee: &[Coin]) -> Vec<Event> {
+    fee.iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .map(|coin| {
+            Event::new(FEE_EVENT_TYPE)
+                .add_attribute("denom", &coin.denom)
+                .add_attribute("amount", coin.amount)
+        })
+        .collect()
+}
+
+/// Fee accrual event of each non-zero `fee` coin, accrued to `destination`
+pub fn fee_accrual_events(fee: &[Coin], destination: &FeeDestination) -> Vec<Event> {
+    let destination = match destination {
+        FeeDestination::Pool => "pool",
+        FeeDestination::Collector(_) => "collector",
+    };
+
+    fee.iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .map(|coin| {
+            Event::new(FEE_ACCRUAL_EVENT_TYPE)
+                .add_attribute("denom", &coin.denom)
+                .add_attribute("amount", coin.amount)
+                .add_attribute("destination", destination)
+        })
+        .collect()
+}
+
+/// Fee rebate event of `fee` share paid out to `recipient`
+pub fn fee_rebate_event(recipient: &str, fee: &Coin) -> Event {
+    Event::new(FEE_REBATE_EVENT_TYPE)
+        .add_attribute("recipient", recipient)
+        .add_attribute("denom", &fee.denom)
+        .add_attribute("amount", fee.amount)
+}
+
+/// Fee withdrawal event of each `fee` coin withdrawn by `collector`
+pub fn fee_withdrawal_events(collector: &Addr, fee: &[Coin]) -> Vec<Event> {
+    fee.iter()
+        .map(|coin| {
+            Event::new(FEE_WITHDRAWAL_EVENT_TYPE)
+                .add_attribute("collector", collector)
+                .add_attribute("denom", &coin.denom)
+                .add_attribute("amount", coin.amount)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{asset::Asset, transmuter_pool::TransmuterPool};
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_balance_change_events() {
+        let before = BTreeMap::from([
+            ("uaaa".to_string(), Uint128::new(1000)),
+            ("ubbb".to_string(), Uint128::new(1000)),
+            ("uccc".to_string(), Uint128::new(500)),
+            ("uddd".to_string(), Uint128::new(100)),
+        ]);
+        let after = BTreeMap::from([
+            ("uaaa".to_string(), Uint128::new(1500)),
+            ("ubbb".to_string(), Uint128::new(1000)),
+            ("uccc".to_string(), Uint128::new(200)),
+        ]);
+
+        assert_eq!(
+            balance_change_events(&before, &after),
+            vec![
+                Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "uaaa")
+                    .add_attribute("change", "500")
+                    .add_attribute("balance", "1500"),
+                Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "uccc")
+                    .add_attribute("change", "-300")
+                    .add_attribute("balance", "200"),
+                Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "uddd")
+                    .add_attribute("change", "-100")
+                    .add_attribute("balance", "0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weight_change_events() {
+        let before = BTreeMap::from([
+            ("uaaa".to_string(), Decimal::percent(50)),
+            ("ubbb".to_string(), Decimal::percent(50)),
+        ]);
+        let after = BTreeMap::from([
+            ("uaaa".to_string(), Decimal::percent(60)),
+            ("ubbb".to_string(), Decimal::percent(40)),
+        ]);
+
+        assert_eq!(
+            weight_change_events("swap_exact_amount_in", &before, &after),
+            vec![
+                Event::new(WEIGHT_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "uaaa")
+                    .add_attribute("old_weight", "0.5")
+                    .add_attribute("new_weight", "0.6")
+                    .add_attribute("operation", "swap_exact_amount_in"),
+                Event::new(WEIGHT_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "ubbb")
+                    .add_attribute("old_weight", "0.5")
+                    .add_attribute("new_weight", "0.4")
+                    .add_attribute("operation", "swap_exact_amount_in"),
+            ]
+        );
+
+        // proportional join keeps the weights
+        assert_eq!(weight_change_events("join_pool", &before, &before), vec![]);
+
+        // drained pool has no weights
+        assert_eq!(
+            weight_change_events("exit_pool", &before, &BTreeMap::new()),
+            vec![
+                Event::new(WEIGHT_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "uaaa")
+                    .add_attribute("old_weight", "0.5")
+                    .add_attribute("new_weight", "0")
+                    .add_attribute("operation", "exit_pool"),
+                Event::new(WEIGHT_CHANGE_EVENT_TYPE)
+                    .add_attribute("denom", "ubbb")
+                    .add_attribute("old_weight", "0.5")
+                    .add_attribute("new_weight", "0")
+                    .add_attribute("operation", "exit_pool"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_order() {
+        let mut events = vec![
+            Event::new("circuit_breaker_tripped"),
+            Event::new(FEE_EVENT_TYPE).add_attribute("denom", "uaaa"),
+            Event::new(BALANCE_CHANGE_EVENT_TYPE).add_attribute("denom", "ubbb"),
+            Event::new(SWAP_EVENT_TYPE),
+            Event::new(BALANCE_CHANGE_EVENT_TYPE).add_attribute("denom", "uaaa"),
+            Event::new("operator_allowance_spent"),
+        ];
+        events.sort_by_key(|event| event_rank(&event.ty));
+
+        assert_eq!(
+            events,
+            vec![
+                Event::new(SWAP_EVENT_TYPE),
+                Event::new(BALANCE_CHANGE_EVENT_TYPE).add_attribute("denom", "ubbb"),
+                Event::new(BALANCE_CHANGE_EVENT_TYPE).add_attribute("denom", "uaaa"),
+                Event::new(FEE_EVENT_TYPE).add_attribute("denom", "uaaa"),
+                Event::new("circuit_breaker_tripped"),
+                Event::new("operator_allowance_spent"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_versioned_event() {
+        let legacy_attributes = [LegacyAttribute {
+            event_type: BALANCE_CHANGE_EVENT_TYPE,
+            key: "change",
+            legacy_key: "delta",
+            renamed_in: 2,
+        }];
+        let event = Event::new(BALANCE_CHANGE_EVENT_TYPE)
+            .add_attribute("denom", "uaaa")
+            .add_attribute("change", "500")
+            .add_attribute("balance", "1500");
+
+        // legacy key is emitted alongside in the version it was renamed in
+        assert_eq!(
+            versioned_event(event.clone(), 2, &legacy_attributes),
+            Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                .add_attribute("event_version", "2")
+                .add_attribute("denom", "uaaa")
+                .add_attribute("change", "500")
+                .add_attribute("balance", "1500")
+                .add_attribute("delta", "500")
+        );
+
+        // and dropped in the next
+        assert_eq!(
+            versioned_event(event, 3, &legacy_attributes),
+            Event::new(BALANCE_CHANGE_EVENT_TYPE)
+                .add_attribute("event_version", "3")
+                .add_attribute("denom", "uaaa")
+                .add_attribute("change", "500")
+                .add_attribute("balance", "1500")
+        );
+
+        // events not in the event order are not versioned
+        assert_eq!(
+            versioned_event(Event::new("circuit_breaker_tripped"), 2, &legacy_attributes),
+            Event::new("circuit_breaker_tripped")
+        );
+    }
+
+    #[test]
+    fn test_fee_events() {
+        assert_eq!(
+            fee_events(&[Coin::new(0, "uaaa"), Coin::new(10, "ubbb")]),
+            vec![Event::new(FEE_EVENT_TYPE)
+                .add_attribute("denom", "ubbb")
+                .add_attribute("amount", "10")]
+        );
+    }
+
+    #[test]
+    fn test_anonymized() {
+        let response = Response::new()
+            .add_attribute("method", "swap_exact_amount_in")
+            .add_attribute("to", "osmo1recipient")
+            .add_event(
+                Event::new(SWAP_EVENT_TYPE)
+                    .add_attribute("sender", "osmo1sender")
+                    .add_attribute("token_in", "1000uaaa"),
+            )
+            .add_event(
+                Event::new("transmuter")
+                    .add_attribute(attribute_key("action"), "swap_exact_amount_in")
+                    .add_attribute(attribute_key("sender"), "osmo1sender"),
+            );
+
+        assert_eq!(
+            anonymized(response),
+            Response::new()
+                .add_attribute("method", "swap_exact_amount_in")
+                .add_event(Event::new(SWAP_EVENT_TYPE).add_attribute("token_in", "1000uaaa"))
+                .add_event(
+                    Event::new("transmuter")
+                        .add_attribute(attribute_key("action"), "swap_exact_amount_in")
+                )
+        );
+    }
+
+    #[test]
+    fn test_fee_accrual_events() {
+        let fee = [Coin::new(0, "uaaa"), Coin::new(10, "ubbb")];
+
+        assert_eq!(
+            fee_accrual_events(&fee, &FeeDestination::Pool),
+            vec![Event::new(FEE_ACCRUAL_EVENT_TYPE)
+                .add_attribute("denom", "ubbb")
+                .add_attribute("amount", "10")
+                .add_attribute("destination", "pool")]
+        );
+        assert_eq!(
+            fee_accrual_events(
+                &fee,
+                &FeeDestination::Collector(Addr::unchecked("collector"))
+            ),
+            vec![Event::new(FEE_ACCRUAL_EVENT_TYPE)
+                .add_attribute("denom", "ubbb")
+                .add_attribute("amount", "10")
+                .add_attribute("destination", "collector")]
+        );
+    }
+
+    #[test]
+    fn test_state_snapshot_before() {
+        let mut deps = mock_dependencies();
+        let transmuter = Transmuter::new();
+        let pool = TransmuterPool::new(vec![
+            Asset::new(1000u128, "uaaa", 1u128).unwrap(),
+            Asset::new(1000u128, "ubbb", 1u128).unwrap(),
+        ])
+        .unwrap();
+        transmuter.pool.save(deps.as_mut().storage, &pool).unwrap();
+
+        // messages that can change pool state are always snapshotted
+        assert!(transmuter
+            .state_snapshot_before(deps.as_ref().storage, "swap_exact_amount_in")
+            .unwrap()
+            .is_some());
+
+        // others are not, while state diff events are disabled
+        assert!(transmuter
+            .state_snapshot_before(deps.as_ref().storage, "set_operator")
+            .unwrap()
+            .is_none());
+
+        // state diff events need a snapshot of any message
+        transmuter
+            .state_diff_events_enabled
+            .save(deps.as_mut().storage, &true)
+            .unwrap();
+        assert!(transmuter
+            .state_snapshot_before(deps.as_ref().storage, "set_operator")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_state_diff_event() {
+        let balances_before = BTreeMap::from([
+            ("uaaa".to_string(), Uint128::new(1000)),
+            ("ubbb".to_string(), Uint128::new(1000)),
+        ]);
+        let weights_before = BTreeMap::from([
+            ("uaaa".to_string(), Decimal::percent(50)),
+            ("ubbb".to_string(), Decimal::percent(50)),
+        ]);
+
+        // nothing changed
+        assert_eq!(
+            state_diff_event(
+                &balances_before,
+                &balances_before,
+                &AlloyedSupplyDelta::default(),
+                &weights_before,
+                &weights_before
+            ),
+            None
+        );
+
+        // exit pool with uaaa
+        let balances_after = BTreeMap::from([
+            ("uaaa".to_string(), Uint128::new(500)),
+            ("ubbb".to_string(), Uint128::new(1000)),
+        ]);
+        let weights_after = BTreeMap::from([
+            ("uaaa".to_string(), Decimal::from_ratio(1u128, 3u128)),
+            ("ubbb".to_string(), Decimal::from_ratio(2u128, 3u128)),
+        ]);
+        assert_eq!(
+            state_diff_event(
+                &balances_before,
+                &balances_after,
+                &AlloyedSupplyDelta {
+                    minted: Uint128::zero(),
+                    burned: Uint128::new(500),
+                },
+                &weights_before,
+                &weights_after
+            ),
+            Some(
+                Event::new(STATE_DIFF_EVENT_TYPE)
+                    .add_attribute("balance_deltas", "uaaa:-500")
+                    .add_attribute("alloyed_supply_delta", "-500")
+                    .add_attribute(
+                        "weight_deltas",
+                        "uaaa:-0.166666666666666667,ubbb:0.166666666666666666"
+                    )
+            )
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, Coin, StdResult, Storage, WasmMsg};
+use cw_storage_plus::Item;
+
+/// Message the registered flow quota contract receives after each swap, join and exit pool
+#[cw_serde]
+pub enum FlowQuotaMsg {
+    RecordFlow {
+        sender: String,
+        /// Tokens that flowed into the pool
+        inflow: Vec<Coin>,
+        /// Tokens that flowed out of the pool
+        outflow: Vec<Coin>,
+    },
+}
+
+/// Optional contract holding a quota shared with the chain's IBC rate limiter, so that
+/// pool exits plus IBC withdrawals of a denom can't exceed a global net-flow quota together.
+/// Unlike the swap hook, it is called with a plain message, so that the quota contract
+/// rejecting the flow reverts the swap.
+pub struct FlowQuota<'a> {
+    contract: Item<'a, Addr>,
+}
+
+impl<'a> FlowQuota<'a> {
+    pub const fn new(contract_namespace: &'a str) -> Self {
+        Self {
+            contract: Item::new(contract_namespace),
+        }
+    }
+
+    /// Registered quota contract, `None` if there is none
+    pub fn contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.contract.may_load(storage)
+    }
+
+    /// Register quota contract, `None` to unregister
+    pub fn set_contract(&self, storage: &mut dyn Storage, contract: Option<Addr>) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.contract.save(storage, &contract),
+            None => {
+                self.contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Message to call the quota contract with `msg`, `None` if there is no quota contract
+    pub fn call(&self, storage: &dyn Storage, msg: &FlowQuotaMsg) -> StdResult<Option<WasmMsg>> {
+        self.contract(storage)?
+            .map(|contract| {
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract.into_string(),
+                    msg: to_json_binary(msg)?,
+                    funds: vec![],
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_call() {
+        let mut deps = mock_dependencies();
+        let flow_quota = FlowQuota::new("flow_quota");
+
+        let msg = FlowQuotaMsg::RecordFlow {
+            sender: "sender".to_string(),
+            inflow: vec![Coin::new(1000, "denoma")],
+            outflow: vec![Coin::new(1000, "denomb")],
+        };
+
+        // no quota contract registered
+        assert_eq!(flow_quota.call(&deps.storage, &msg).unwrap(), None);
+
+        flow_quota
+            .set_contract(&mut deps.storage, Some(Addr::unchecked("quota")))
+            .unwrap();
+        assert_eq!(
+            flow_quota.call(&deps.storage, &msg).unwrap(),
+            Some(WasmMsg::Execute {
+                contract_addr: "quota".to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        flow_quota.set_contract(&mut deps.storage, None).unwrap();
+        assert_eq!(flow_quota.contract(&deps.storage).unwrap(), None);
+        assert_eq!(flow_quota.call(&deps.storage, &msg).unwrap(), None);
+    }
+}
@@ -1,29 +1,144 @@
+// everything but `msg` is the contract itself, see the `contract` feature
+#[cfg(feature = "contract")]
+mod account_activity;
+#[cfg(feature = "contract")]
+mod active_status;
+#[cfg(feature = "contract")]
 mod alloyed_asset;
+#[cfg(feature = "contract")]
+mod alloyed_backend;
+#[cfg(feature = "contract")]
+mod approval;
+#[cfg(feature = "contract")]
 mod asset;
+#[cfg(feature = "contract")]
+mod audit_log;
+#[cfg(feature = "contract")]
+mod authz;
+#[cfg(feature = "contract")]
+mod bank_supply;
+#[cfg(feature = "contract")]
+mod circuit_breaker;
+#[cfg(feature = "contract")]
+mod config_snapshot;
+#[cfg(feature = "contract")]
 pub mod contract;
+#[cfg(feature = "contract")]
+mod creation_fee;
+#[cfg(feature = "contract")]
+mod debug;
+#[cfg(feature = "contract")]
+mod denom_admin;
+#[cfg(feature = "contract")]
+mod drip_order;
+#[cfg(feature = "contract")]
 mod error;
+#[cfg(feature = "contract")]
+mod events;
+#[cfg(feature = "contract")]
+mod flow_quota;
+#[cfg(feature = "contract")]
 mod limiter;
+#[cfg(feature = "contract")]
 mod math;
+#[cfg(feature = "contract")]
+mod meta_tx;
+#[cfg(feature = "contract")]
+mod metrics;
+#[cfg(feature = "contract")]
 mod migrations;
+#[cfg(feature = "contract")]
+mod monitoring;
+pub mod msg;
+#[cfg(feature = "contract")]
+mod operator;
+#[cfg(feature = "contract")]
+mod param;
+#[cfg(feature = "contract")]
+mod pool_registry;
+#[cfg(feature = "contract")]
+mod redemption;
+#[cfg(feature = "contract")]
+mod remote_asset;
+#[cfg(feature = "contract")]
+mod response_encoding;
+#[cfg(feature = "contract")]
 mod role;
+#[cfg(feature = "contract")]
+mod sibling_pool;
+#[cfg(feature = "contract")]
+mod state_export;
+#[cfg(feature = "contract")]
+mod subscription;
+#[cfg(feature = "contract")]
 mod sudo;
+#[cfg(feature = "contract")]
+mod supply_cap;
+#[cfg(feature = "contract")]
+mod supply_hook;
+#[cfg(feature = "contract")]
 mod swap;
+#[cfg(feature = "contract")]
+mod swap_commitment;
+#[cfg(feature = "contract")]
+mod swap_fee;
+#[cfg(feature = "contract")]
+mod swap_hook;
+#[cfg(feature = "contract")]
+mod swap_volume;
+#[cfg(feature = "contract")]
+mod timelock;
+#[cfg(feature = "contract")]
+mod trader_allow_list;
+#[cfg(feature = "contract")]
+mod transfer_restriction;
+#[cfg(feature = "contract")]
 mod transmuter_pool;
+#[cfg(feature = "contract")]
+mod versioned;
+#[cfg(feature = "contract")]
+mod weight_checkpoint;
+#[cfg(feature = "contract")]
 pub use crate::error::ContractError;
 
-#[cfg(test)]
+// message and nested types not reachable through `contract`, exported for schema generation
+#[cfg(feature = "contract")]
+pub use crate::active_status::{Deactivation, InactiveReason};
+#[cfg(feature = "contract")]
+pub use crate::alloyed_backend::AlloyedBackendKind;
+#[cfg(feature = "contract")]
+pub use crate::authz::{AuthzExampleMsg, AuthzGrant};
+#[cfg(feature = "contract")]
+pub use crate::creation_fee::PoolCreationFee;
+#[cfg(feature = "contract")]
+pub use crate::limiter::{
+    ChangeLimiter, Division, Limiter, LimiterParams, StaticLimiter, WindowConfig,
+};
+#[cfg(feature = "contract")]
+pub use crate::meta_tx::{MetaTxMsg, MetaTxPayload};
+#[cfg(feature = "contract")]
+pub use crate::migrations::{MigrateMsg, MigrationChange, MigrationReport, MigrationStepReport};
+#[cfg(feature = "contract")]
+pub use crate::sudo::SudoMsg;
+#[cfg(feature = "contract")]
+pub use crate::versioned::VersionedResponse;
+
+#[cfg(all(test, feature = "contract"))]
 mod test;
 
-#[cfg(not(feature = "library"))]
+#[cfg(all(feature = "contract", not(feature = "library")))]
 mod entry_points {
     use cosmwasm_std::{
-        ensure, entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
+        ensure, entry_point, to_json_vec, Binary, Deps, DepsMut, Env, MessageInfo, Reply, Response,
     };
 
+    use crate::audit_log::{audited_msg, exec_method, method_of};
+    use crate::contract::authorized_actions;
     use crate::contract::sv::{ContractExecMsg, ContractQueryMsg, ExecMsg, InstantiateMsg};
     use crate::contract::Transmuter;
     use crate::error::ContractError;
     use crate::migrations;
+    use crate::subscription::subscription_event;
     use crate::sudo::SudoMsg;
 
     const CONTRACT: Transmuter = Transmuter::new();
@@ -64,47 +179,168 @@ mod entry_points {
 
     #[entry_point]
     pub fn execute(
-        deps: DepsMut,
+        mut deps: DepsMut,
         env: Env,
         info: MessageInfo,
         msg: ContractExecMsg,
     ) -> Result<Response, ContractError> {
+        // individually frozen messages are rejected before anything else
+        match &msg {
+            ContractExecMsg::Transmuter(exec_msg) => {
+                CONTRACT.ensure_message_not_frozen(deps.storage, exec_msg)?
+            }
+        }
+
         ensure_active_status!(
             msg,
             deps,
             env,
-            except: ContractExecMsg::Transmuter(ExecMsg::SetActiveStatus { .. })
+            except: ContractExecMsg::Transmuter(
+                ExecMsg::SetActiveStatus { .. }
+                    | ExecMsg::TransferAlloyedDenomAdmin { .. }
+                    | ExecMsg::CancelAlloyedDenomAdminTransfer { .. }
+                    | ExecMsg::ClaimAlloyedDenomAdmin { .. }
+                    | ExecMsg::HandOverToSuccessor { .. }
+                    | ExecMsg::ProposeAction { .. }
+                    | ExecMsg::ApproveAction { .. }
+                    | ExecMsg::ExecuteAction { .. }
+                    | ExecMsg::CancelAction { .. }
+                    | ExecMsg::CancelDripOrder { .. }
+            )
         );
 
-        msg.dispatch(&CONTRACT, (deps, env, info))
+        let (method, audited) = match &msg {
+            ContractExecMsg::Transmuter(exec_msg) => {
+                (exec_method(exec_msg)?, audited_msg(exec_msg)?)
+            }
+        };
+        let actor = info.sender.clone();
+
+        // rate limits apply to the roles the actor has before the action,
+        // and limiter and fee settings are snapshotted in case the action changes them
+        let config_before = match audited {
+            Some(_) => {
+                CONTRACT.consume_role_rate_limits(deps.branch(), &env, &actor)?;
+                Some(CONTRACT.recoverable_config(deps.storage)?)
+            }
+            None => None,
+        };
+
+        // name the attempted action in unauthorized errors, if enabled
+        let attempted_action = if CONTRACT.has_detailed_unauthorized_errors(deps.storage)? {
+            Some(method.clone())
+        } else {
+            None
+        };
+
+        // pool registry is notified if pool asset denoms change, however they change
+        let pool_asset_denoms_before = match CONTRACT.pool_registry.contract(deps.storage)? {
+            Some(_) => Some(CONTRACT.pool_asset_denoms(deps.storage)?),
+            None => None,
+        };
+
+        // monitoring contract is alerted of critical events, however they are caused
+        let alert_state = CONTRACT.alert_state(deps.as_ref(), env.block.time)?;
+
+        let state_before = CONTRACT.state_snapshot_before(deps.storage, &method)?;
+
+        let response = msg
+            .dispatch(&CONTRACT, (deps.branch(), env.clone(), info))
+            .map_err(|err| match (err, attempted_action) {
+                (ContractError::Unauthorized {}, Some(action)) => {
+                    ContractError::UnauthorizedAction {
+                        required_roles: authorized_actions::roles_for(&action),
+                        action,
+                    }
+                }
+                (err, _) => err,
+            })?;
+
+        let response = match pool_asset_denoms_before {
+            Some(denoms) if denoms != CONTRACT.pool_asset_denoms(deps.storage)? => {
+                CONTRACT.register_pool(deps.storage, response)?
+            }
+            _ => response,
+        };
+
+        let response = CONTRACT.with_alerts(
+            deps.as_ref(),
+            env.block.time,
+            &method,
+            alert_state,
+            response,
+        )?;
+
+        let response =
+            CONTRACT.with_ordered_events(deps.storage, &method, state_before, response)?;
+
+        // every response carries the same versioned attributes for event subscription filters
+        let response = response.add_event(subscription_event(&method, Some(&actor)));
+        let response = CONTRACT.with_anonymized_events(deps.storage, response)?;
+
+        // record privileged actions only once they succeed
+        if let (Some((method, params)), Some(config_before)) = (audited, config_before) {
+            CONTRACT.snapshot_config_change(deps.storage, &env, &method, config_before)?;
+            CONTRACT
+                .audit_log
+                .append(deps.storage, &method, actor, env.block.time, &params)?;
+        }
+
+        Ok(response)
     }
 
     #[entry_point]
     pub fn query(deps: Deps, env: Env, msg: ContractQueryMsg) -> Result<Binary, ContractError> {
+        // protobuf encoded responses are returned as is, instead of serialized as json
+        let protobuf_response = match &msg {
+            ContractQueryMsg::Transmuter(query_msg) => {
+                CONTRACT.protobuf_response(deps, &env, query_msg)?
+            }
+        };
+        if let Some(response) = protobuf_response {
+            return Ok(response);
+        }
+
         msg.dispatch(&CONTRACT, (deps, env))
     }
 
     #[entry_point]
-    pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    pub fn sudo(mut deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
         ensure_active_status!(
             msg,
             deps,
             env,
             except: SudoMsg::SetActive { .. }
+                | SudoMsg::BlockBeforeSend { .. }
+                | SudoMsg::TrackBeforeSend { .. }
+                | SudoMsg::ChangeAlloyedNormalizationFactor { .. }
+                | SudoMsg::OverrideParam { .. }
+                | SudoMsg::EndEpoch { .. }
         );
 
-        msg.dispatch(&CONTRACT, (deps, env))
+        let method = method_of(&to_json_vec(&msg)?)?;
+        let now = env.block.time;
+        let alert_state = CONTRACT.alert_state(deps.as_ref(), now)?;
+        let state_before = CONTRACT.state_snapshot_before(deps.storage, &method)?;
+        let response = msg.dispatch(&CONTRACT, (deps.branch(), env))?;
+        let response = CONTRACT.with_alerts(deps.as_ref(), now, &method, alert_state, response)?;
+        let response =
+            CONTRACT.with_ordered_events(deps.storage, &method, state_before, response)?;
+
+        let response = response.add_event(subscription_event(&method, None));
+
+        Ok(CONTRACT.with_anonymized_events(deps.storage, response)?)
     }
 
     #[entry_point]
     pub fn migrate(
         deps: DepsMut,
-        _env: Env,
-        _msg: migrations::v3_2_0::MigrateMsg,
+        env: Env,
+        msg: migrations::MigrateMsg,
     ) -> Result<Response, ContractError> {
-        migrations::v3_2_0::execute_migration(deps)
+        migrations::execute_migration(deps, env, msg)
     }
 }
 
-#[cfg(not(feature = "library"))]
+#[cfg(all(feature = "contract", not(feature = "library")))]
 pub use crate::entry_points::*;
@@ -1,5 +1,5 @@
 use super::helpers::*;
-use crate::ContractError;
+use crate::{debug, ContractError};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{ensure, Decimal, StdError, Timestamp, Uint64};
 
@@ -198,6 +198,17 @@ impl Division {
                         .checked_mul(from_uint(remaining_division_size))?
                 };
 
+                debug::trace("moving_average_division", || {
+                    vec![
+                        ("started_at", division.started_at.nanos().to_string()),
+                        (
+                            "remaining_division_size",
+                            remaining_division_size.to_string(),
+                        ),
+                        ("cumsum", integral.to_string()),
+                    ]
+                });
+
                 (division, integral)
             }
             None => {
@@ -237,6 +248,14 @@ impl Division {
                 .checked_add(division.integral)?
                 .checked_add(division.latest_value_integral(division_size, block_time)?)?;
 
+            debug::trace("moving_average_division", || {
+                vec![
+                    ("started_at", division.started_at.nanos().to_string()),
+                    ("gap", gap.to_string()),
+                    ("cumsum", integral.to_string()),
+                ]
+            });
+
             processed_division = division;
         }
 
@@ -260,9 +279,19 @@ impl Division {
                     .latest_value
                     .checked_mul(from_uint(elasped_time_after_latest_division))?,
             )?;
+
+            debug::trace("moving_average_after_latest_division", || {
+                vec![
+                    (
+                        "elapsed_time",
+                        elasped_time_after_latest_division.to_string(),
+                    ),
+                    ("cumsum", integral.to_string()),
+                ]
+            });
         }
 
-        match latest_removed_division {
+        let (integral, integral_range) = match latest_removed_division {
             Some(latest_removed_division) => {
                 // if a division gets removed, it must be older than window_started_at
                 // its latest value should be static throughout window start until first division within window
@@ -284,12 +313,9 @@ impl Division {
                     .latest_value
                     .checked_mul(from_uint(missing_period))?;
 
-                integral
-                    .checked_add(missing_integral)?
-                    // for this case, we can be sure that total integral range is window size
-                    // since it integrates from window stared at
-                    .checked_div(from_uint(window_size))
-                    .map_err(Into::into)
+                // for this case, we can be sure that total integral range is window size
+                // since it integrates from window stared at
+                (integral.checked_add(missing_integral)?, window_size)
             }
             None => {
                 // if there is no removed division, then the total integral range can be either case
@@ -319,11 +345,22 @@ impl Division {
                     return Err(ContractError::UndefinedMovingAverage {});
                 }
 
-                integral
-                    .checked_div(from_uint(total_elapsed_time))
-                    .map_err(Into::into)
+                (integral, total_elapsed_time)
             }
-        }
+        };
+
+        let average = integral.checked_div(from_uint(integral_range))?;
+
+        debug::trace("moving_average", || {
+            vec![
+                ("window_started_at", window_started_at.to_string()),
+                ("integral_range", integral_range.to_string()),
+                ("integral", integral.to_string()),
+                ("average", average.to_string()),
+            ]
+        });
+
+        Ok(average)
     }
 
     /// In case the window start time is within the first observing division and before latest division updated_at,
@@ -2,9 +2,9 @@ use std::collections::HashMap;
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{ensure, Decimal, StdError, Storage, Timestamp, Uint64};
-use cw_storage_plus::Map;
+use cw_storage_plus::{Item, Map};
 
-use crate::ContractError;
+use crate::{debug, ContractError};
 
 use super::division::Division;
 
@@ -36,11 +36,37 @@ pub struct WindowConfig {
 }
 
 impl WindowConfig {
-    fn division_size(&self) -> Result<Uint64, ContractError> {
+    pub(crate) fn division_size(&self) -> Result<Uint64, ContractError> {
         self.window_size
             .checked_div(self.division_count)
             .map_err(Into::into)
     }
+
+    pub(super) fn validate(&self) -> Result<(), ContractError> {
+        // window size must be greater than zero
+        ensure!(
+            self.window_size > Uint64::zero(),
+            ContractError::ZeroWindowSize {}
+        );
+
+        // division count must not exceed MAX_DIVISION_COUNT
+        ensure!(
+            self.division_count <= MAX_DIVISION_COUNT,
+            ContractError::DivisionCountExceeded {
+                max_division_count: MAX_DIVISION_COUNT
+            }
+        );
+
+        // division count must evenly divide window size
+        let is_window_evenly_dividable =
+            self.window_size.checked_rem(self.division_count)? == Uint64::zero();
+        ensure!(
+            is_window_evenly_dividable,
+            ContractError::UnevenWindowDivision {}
+        );
+
+        Ok(())
+    }
 }
 
 /// Limiter that determines limit by upper bound of SMA (Simple Moving Average) of the value.
@@ -89,6 +115,10 @@ impl ChangeLimiter {
         self.latest_value
     }
 
+    pub fn boundary_offset(&self) -> Decimal {
+        self.boundary_offset
+    }
+
     pub fn reset(self) -> Self {
         Self {
             divisions: vec![],
@@ -108,29 +138,7 @@ impl ChangeLimiter {
     }
 
     fn ensure_window_config_constraint(self) -> Result<Self, ContractError> {
-        let config = &self.window_config;
-
-        // window size must be greater than zero
-        ensure!(
-            config.window_size > Uint64::zero(),
-            ContractError::ZeroWindowSize {}
-        );
-
-        // division count must not exceed MAX_DIVISION_COUNT
-        ensure!(
-            config.division_count <= MAX_DIVISION_COUNT,
-            ContractError::DivisionCountExceeded {
-                max_division_count: MAX_DIVISION_COUNT
-            }
-        );
-
-        // division count must evenly divide window size
-        let is_window_evenly_dividable =
-            config.window_size.checked_rem(config.division_count)? == Uint64::zero();
-        ensure!(
-            is_window_evenly_dividable,
-            ContractError::UnevenWindowDivision {}
-        );
+        self.window_config.validate()?;
 
         Ok(self)
     }
@@ -144,21 +152,16 @@ impl ChangeLimiter {
         let (latest_removed_division, updated_limiter) =
             self.clean_up_outdated_divisions(block_time)?;
 
-        // Check for upper limit if there is any existing division or there is any removed divisions
-        let has_any_prev_data_points =
-            !updated_limiter.divisions.is_empty() || latest_removed_division.is_some();
-
-        if has_any_prev_data_points {
-            let avg = Division::compressed_moving_average(
-                latest_removed_division,
-                &updated_limiter.divisions,
-                updated_limiter.window_config.division_size()?,
-                updated_limiter.window_config.window_size,
-                block_time,
-            )?;
-
-            // using saturating_add/sub since the overflowed value can't be exceeded anyway
-            let upper_limit = avg.saturating_add(updated_limiter.boundary_offset);
+        if let Some(upper_limit) =
+            updated_limiter.cleaned_up_upper_limit(latest_removed_division, block_time)?
+        {
+            debug::trace("change_limiter_upper_limit", || {
+                vec![
+                    ("denom", denom.to_string()),
+                    ("value", value.to_string()),
+                    ("upper_limit", upper_limit.to_string()),
+                ]
+            });
 
             ensure!(
                 value <= upper_limit,
@@ -173,6 +176,39 @@ impl ChangeLimiter {
         Ok(updated_limiter)
     }
 
+    /// Upper limit in effect at `block_time`, `None` if there is no data point to limit against yet
+    pub fn upper_limit(&self, block_time: Timestamp) -> Result<Option<Decimal>, ContractError> {
+        let (latest_removed_division, limiter) =
+            self.clone().clean_up_outdated_divisions(block_time)?;
+
+        limiter.cleaned_up_upper_limit(latest_removed_division, block_time)
+    }
+
+    fn cleaned_up_upper_limit(
+        &self,
+        latest_removed_division: Option<Division>,
+        block_time: Timestamp,
+    ) -> Result<Option<Decimal>, ContractError> {
+        // There is an upper limit if there is any existing division or there is any removed divisions
+        let has_any_prev_data_points =
+            !self.divisions.is_empty() || latest_removed_division.is_some();
+
+        if !has_any_prev_data_points {
+            return Ok(None);
+        }
+
+        let avg = Division::compressed_moving_average(
+            latest_removed_division,
+            &self.divisions,
+            self.window_config.division_size()?,
+            self.window_config.window_size,
+            block_time,
+        )?;
+
+        // using saturating_add/sub since the overflowed value can't be exceeded anyway
+        Ok(Some(avg.saturating_add(self.boundary_offset)))
+    }
+
     fn update(self, block_time: Timestamp, value: Decimal) -> Result<Self, ContractError> {
         let mut updated_limiter = self;
 
@@ -236,6 +272,22 @@ impl ChangeLimiter {
         Ok(updated_limiter)
     }
 
+    /// Remove divisions the window has completely passed at `block_time`, except the latest one
+    /// which the moving average still depends on, so that the upper limit is unaffected.
+    fn prune_outdated_divisions(self, block_time: Timestamp) -> Result<Self, ContractError> {
+        let (latest_removed_division, limiter) = self.clean_up_outdated_divisions(block_time)?;
+
+        let divisions = latest_removed_division
+            .into_iter()
+            .chain(limiter.divisions)
+            .collect();
+
+        Ok(Self {
+            divisions,
+            ..limiter
+        })
+    }
+
     fn clean_up_outdated_divisions(
         self,
         block_time: Timestamp,
@@ -273,6 +325,10 @@ impl StaticLimiter {
         Self { upper_limit }.ensure_upper_limit_constraint()
     }
 
+    pub fn upper_limit(&self) -> Decimal {
+        self.upper_limit
+    }
+
     fn ensure_upper_limit_constraint(self) -> Result<Self, ContractError> {
         ensure!(
             self.upper_limit > Decimal::zero(),
@@ -322,18 +378,69 @@ pub enum LimiterParams {
     },
 }
 
+impl Limiter {
+    /// Upper limit in effect at `block_time`, `None` if there is nothing to limit against yet
+    pub fn upper_limit(&self, block_time: Timestamp) -> Result<Option<Decimal>, ContractError> {
+        match self {
+            Limiter::ChangeLimiter(limiter) => limiter.upper_limit(block_time),
+            Limiter::StaticLimiter(limiter) => Ok(Some(limiter.upper_limit())),
+        }
+    }
+}
+
+/// Current value of the limited denom against the upper limit in effect
+#[cw_serde]
+pub struct LimiterSummary {
+    pub denom: String,
+    pub label: String,
+    pub value: Decimal,
+    pub upper_limit: Option<Decimal>,
+}
+
+/// Whether the limiter would pass or fail
+#[cw_serde]
+pub struct LimiterVerdict {
+    pub denom: String,
+    pub label: String,
+    pub passed: bool,
+}
+
 pub struct Limiters<'a> {
     /// Map of (denom, label) -> Limiter
     limiters: Map<'a, (&'a str, &'a str), Limiter>,
+
+    /// Number of registered limiters, so that checking limits can be skipped
+    /// without reading any limiter when there is none
+    count: Item<'a, u64>,
 }
 
 impl<'a> Limiters<'a> {
-    pub const fn new(limiters_namespace: &'a str) -> Self {
+    pub const fn new(limiters_namespace: &'a str, count_namespace: &'a str) -> Self {
         Self {
             limiters: Map::new(limiters_namespace),
+            count: Item::new(count_namespace),
         }
     }
 
+    /// Whether there is any registered limiter.
+    /// Limiters registered before the count was tracked are assumed to exist.
+    pub fn any_registered(&self, storage: &dyn Storage) -> Result<bool, ContractError> {
+        match self.count.may_load(storage)? {
+            Some(count) => Ok(count > 0),
+            None => Ok(true),
+        }
+    }
+
+    /// Recount registered limiters, returning the count. Only called on instantiation, migration,
+    /// registering and deregistering which are not on the hot path, and the number of limiters
+    /// is expected to be small.
+    pub fn sync_count(&self, storage: &mut dyn Storage) -> Result<u64, ContractError> {
+        let count = self.list_limiters(storage)?.len() as u64;
+        self.count.save(storage, &count)?;
+
+        Ok(count)
+    }
+
     pub fn register(
         &self,
         storage: &mut dyn Storage,
@@ -374,9 +481,10 @@ impl<'a> Limiters<'a> {
             }
         );
 
-        self.limiters
-            .save(storage, (denom, label), &limiter)
-            .map_err(Into::into)
+        self.limiters.save(storage, (denom, label), &limiter)?;
+        self.sync_count(storage)?;
+
+        Ok(())
     }
 
     /// Deregsiter all limiters for the denom without checking if it will be empty.
@@ -392,6 +500,8 @@ impl<'a> Limiters<'a> {
             self.limiters.remove(storage, (denom, &label));
         }
 
+        self.sync_count(storage)?;
+
         Ok(())
     }
 
@@ -414,6 +524,7 @@ impl<'a> Limiters<'a> {
                 );
 
                 self.limiters.remove(storage, (denom, label));
+                self.sync_count(storage)?;
                 Ok(limiter)
             }
             None => Err(ContractError::LimiterDoesNotExist {
@@ -493,6 +604,20 @@ impl<'a> Limiters<'a> {
         Ok(())
     }
 
+    pub fn get(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+    ) -> Result<Limiter, ContractError> {
+        self.limiters
+            .may_load(storage, (denom, label))?
+            .ok_or_else(|| ContractError::LimiterDoesNotExist {
+                denom: denom.to_string(),
+                label: label.to_string(),
+            })
+    }
+
     pub fn list_limiters_by_denom(
         &self,
         storage: &dyn Storage,
@@ -524,31 +649,24 @@ impl<'a> Limiters<'a> {
         denom_value_pairs: Vec<(String, (Decimal, Decimal))>,
         block_time: Timestamp,
     ) -> Result<(), ContractError> {
+        if !self.any_registered(storage)? {
+            return Ok(());
+        }
+
         for (denom, (prev_value, value)) in denom_value_pairs {
             let limiters = self.list_limiters_by_denom(storage, denom.as_str())?;
-            let is_not_decreasing = value >= prev_value;
 
             for (label, limiter) in limiters {
-                // Enforce limiter only if value is increasing, because if the value is decreasing from the previous value,
-                // for the specific denom, it is a balancing act to move away from the limit.
-                let limiter = match limiter {
-                    Limiter::ChangeLimiter(limiter) => Limiter::ChangeLimiter({
-                        if is_not_decreasing {
-                            limiter
-                                .ensure_upper_limit(block_time, denom.as_str(), value)?
-                                .update(block_time, value)?
-                        } else {
-                            limiter.update(block_time, value)?
-                        }
-                    }),
-                    Limiter::StaticLimiter(limiter) => Limiter::StaticLimiter({
-                        if is_not_decreasing {
-                            limiter.ensure_upper_limit(denom.as_str(), value)?
-                        } else {
-                            limiter
-                        }
-                    }),
-                };
+                debug::trace("limiter_check", || {
+                    vec![
+                        ("denom", denom.clone()),
+                        ("label", label.clone()),
+                        ("prev_value", prev_value.to_string()),
+                        ("value", value.to_string()),
+                    ]
+                });
+
+                let limiter = check_limit(limiter, denom.as_str(), prev_value, value, block_time)?;
 
                 // save updated limiter
                 self.limiters
@@ -559,6 +677,88 @@ impl<'a> Limiters<'a> {
         Ok(())
     }
 
+    /// Prune outdated divisions of every change limiter, which otherwise only gets cleaned up
+    /// when its denom is swapped. Limits are unaffected.
+    pub fn prune_outdated_divisions(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        for ((denom, label), limiter) in self.list_limiters(storage)? {
+            let Limiter::ChangeLimiter(limiter) = limiter else {
+                continue;
+            };
+
+            let division_count = limiter.divisions.len();
+            let limiter = limiter.prune_outdated_divisions(block_time)?;
+
+            if limiter.divisions.len() != division_count {
+                self.limiters
+                    .save(storage, (&denom, &label), &Limiter::ChangeLimiter(limiter))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check limits without updating limiter states,
+    /// returns whether each limiter of the given denoms would pass or fail.
+    pub fn check_limits(
+        &self,
+        storage: &dyn Storage,
+        denom_value_pairs: Vec<(String, (Decimal, Decimal))>,
+        block_time: Timestamp,
+    ) -> Result<Vec<LimiterVerdict>, ContractError> {
+        let mut verdicts = vec![];
+
+        if !self.any_registered(storage)? {
+            return Ok(verdicts);
+        }
+
+        for (denom, (prev_value, value)) in denom_value_pairs {
+            let limiters = self.list_limiters_by_denom(storage, denom.as_str())?;
+
+            for (label, limiter) in limiters {
+                let passed =
+                    match check_limit(limiter, denom.as_str(), prev_value, value, block_time) {
+                        Ok(_) => true,
+                        Err(ContractError::UpperLimitExceeded { .. }) => false,
+                        Err(err) => return Err(err),
+                    };
+
+                verdicts.push(LimiterVerdict {
+                    denom: denom.clone(),
+                    label,
+                    passed,
+                });
+            }
+        }
+
+        Ok(verdicts)
+    }
+
+    /// Summarize every limiter with the current `weights`, which are zero for missing denoms.
+    pub fn summarize(
+        &self,
+        storage: &dyn Storage,
+        weights: Vec<(String, Decimal)>,
+        block_time: Timestamp,
+    ) -> Result<Vec<LimiterSummary>, ContractError> {
+        let weights: HashMap<String, Decimal> = weights.into_iter().collect();
+
+        self.list_limiters(storage)?
+            .into_iter()
+            .map(|((denom, label), limiter)| {
+                Ok(LimiterSummary {
+                    value: weights.get(&denom).copied().unwrap_or_default(),
+                    upper_limit: limiter.upper_limit(block_time)?,
+                    denom,
+                    label,
+                })
+            })
+            .collect()
+    }
+
     /// If the normalization factor has a non-uniform update, staled divisions will become invalid.
     /// In case of adding new assets, even if there is nothing wrong with the normalization factor,
     /// the asset composition change required some time to be properly reflected.
@@ -594,6 +794,38 @@ impl<'a> Limiters<'a> {
     }
 }
 
+/// Check limit of the limiter and returns its updated state.
+fn check_limit(
+    limiter: Limiter,
+    denom: &str,
+    prev_value: Decimal,
+    value: Decimal,
+    block_time: Timestamp,
+) -> Result<Limiter, ContractError> {
+    // Enforce limiter only if value is increasing, because if the value is decreasing from the previous value,
+    // for the specific denom, it is a balancing act to move away from the limit.
+    let is_not_decreasing = value >= prev_value;
+
+    Ok(match limiter {
+        Limiter::ChangeLimiter(limiter) => Limiter::ChangeLimiter({
+            if is_not_decreasing {
+                limiter
+                    .ensure_upper_limit(block_time, denom, value)?
+                    .update(block_time, value)?
+            } else {
+                limiter.update(block_time, value)?
+            }
+        }),
+        Limiter::StaticLimiter(limiter) => Limiter::StaticLimiter({
+            if is_not_decreasing {
+                limiter.ensure_upper_limit(denom, value)?
+            } else {
+                limiter
+            }
+        }),
+    })
+}
+
 /// This is used for testing if all change limiters has been newly created or reset.
 #[cfg(test)]
 #[macro_export]
@@ -665,7 +897,7 @@ mod tests {
         #[test]
         fn test_register_limiter_works() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -879,7 +1111,7 @@ mod tests {
         #[test]
         fn test_register_with_empty_label_fails() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             let err = limiter
                 .register(
@@ -902,7 +1134,7 @@ mod tests {
         #[test]
         fn test_register_same_key_fail() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -946,7 +1178,7 @@ mod tests {
         #[test]
         fn test_register_limiter_exceed_max_limiter_per_denom() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             for h in 1..=10u64 {
                 let label = format!("{}h", h);
@@ -1017,7 +1249,7 @@ mod tests {
         #[test]
         fn test_deregister() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -1250,7 +1482,7 @@ mod tests {
         fn test_fail_due_to_div_count_does_not_evenly_divide_the_window() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             let err = limiter
                 .register(
@@ -1274,7 +1506,7 @@ mod tests {
         fn test_fail_due_to_div_size_is_zero() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             let err = limiter
                 .register(
@@ -1303,7 +1535,7 @@ mod tests {
         fn test_fail_due_to_window_size_is_zero() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             let err = limiter
                 .register(
@@ -1327,7 +1559,7 @@ mod tests {
         fn test_fail_due_to_max_division_count_exceeded() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             let err = limiter
                 .register(
@@ -1356,7 +1588,7 @@ mod tests {
         fn test_successful() {
             let mut deps = mock_dependencies();
 
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -1884,7 +2116,7 @@ mod tests {
         #[test]
         fn test_change_limiter_no_clean_up_outdated() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(2u64),              // 30 mins each
@@ -2065,7 +2297,7 @@ mod tests {
         #[test]
         fn test_change_limiter_with_clean_up_outdated() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(4u64),              // 15 mins each
@@ -2216,7 +2448,7 @@ mod tests {
         #[test]
         fn test_change_limiter_with_skipped_windows() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
             let config = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(4u64),              // 15 mins each
@@ -2296,7 +2528,7 @@ mod tests {
         #[test]
         fn test_change_limiters_away_from_limit() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -2406,7 +2638,7 @@ mod tests {
         #[test]
         fn test_static_limiter() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
 
             limiter
                 .register(
@@ -2561,7 +2793,7 @@ mod tests {
         #[test]
         fn test_multiple_registered_limiters() {
             let mut deps = mock_dependencies();
-            let limiter = Limiters::new("limiters");
+            let limiter = Limiters::new("limiters", "limiter_count");
             let config_1h = WindowConfig {
                 window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                 division_count: Uint64::from(2u64),              // 30 mins each
@@ -2766,7 +2998,7 @@ mod tests {
             #[test]
             fn test_set_boundary_offset() {
                 let mut deps = mock_dependencies();
-                let limiters = Limiters::new("limiters");
+                let limiters = Limiters::new("limiters", "limiter_count");
                 let config = WindowConfig {
                     window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                     division_count: Uint64::from(4u64),              // 15 mins each
@@ -2848,7 +3080,7 @@ mod tests {
             #[test]
             fn test_set_upper_limit() {
                 let mut deps = mock_dependencies();
-                let limiters = Limiters::new("limiters");
+                let limiters = Limiters::new("limiters", "limiter_count");
                 let config = WindowConfig {
                     window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
                     division_count: Uint64::from(4u64),              // 15 mins each
@@ -2912,6 +3144,322 @@ mod tests {
         }
     }
 
+    mod check_limits {
+        use super::*;
+
+        #[test]
+        fn test_check_limits_does_not_update_states() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new("limiters", "limiter_count");
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(60),
+                    },
+                )
+                .unwrap();
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "1h",
+                    LimiterParams::ChangeLimiter {
+                        window_config: WindowConfig {
+                            window_size: Uint64::from(3_600_000_000_000u64),
+                            division_count: Uint64::from(2u64),
+                        },
+                        boundary_offset: Decimal::percent(20),
+                    },
+                )
+                .unwrap();
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denomb",
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(70),
+                    },
+                )
+                .unwrap();
+
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            let limiters_before = limiter.list_limiters(&deps.storage).unwrap();
+
+            let value_a = Decimal::percent(65);
+            let value_b = Decimal::one() - value_a;
+
+            let verdicts = limiter
+                .check_limits(
+                    &deps.storage,
+                    vec![
+                        ("denoma".to_string(), (value_a - EPSILON, value_a)),
+                        ("denomb".to_string(), (value_b + EPSILON, value_b)),
+                    ],
+                    block_time,
+                )
+                .unwrap();
+
+            assert_eq!(
+                verdicts,
+                vec![
+                    LimiterVerdict {
+                        denom: "denoma".to_string(),
+                        label: "1h".to_string(),
+                        passed: true,
+                    },
+                    LimiterVerdict {
+                        denom: "denoma".to_string(),
+                        label: "static".to_string(),
+                        passed: false,
+                    },
+                    LimiterVerdict {
+                        denom: "denomb".to_string(),
+                        label: "static".to_string(),
+                        passed: true,
+                    },
+                ]
+            );
+
+            // limiter states are not updated
+            assert_eq!(
+                limiter.list_limiters(&deps.storage).unwrap(),
+                limiters_before
+            );
+        }
+    }
+
+    mod summarize {
+        use super::*;
+
+        #[test]
+        fn test_summarize() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new("limiters", "limiter_count");
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(60),
+                    },
+                )
+                .unwrap();
+
+            limiter
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "1h",
+                    LimiterParams::ChangeLimiter {
+                        window_config: WindowConfig {
+                            window_size: Uint64::from(3_600_000_000_000u64),
+                            division_count: Uint64::from(2u64),
+                        },
+                        boundary_offset: Decimal::percent(20),
+                    },
+                )
+                .unwrap();
+
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            let weights = vec![
+                ("denoma".to_string(), Decimal::percent(40)),
+                ("denomb".to_string(), Decimal::percent(60)),
+            ];
+
+            // change limiter has no upper limit until it has seen a value
+            assert_eq!(
+                limiter
+                    .summarize(&deps.storage, weights.clone(), block_time)
+                    .unwrap(),
+                vec![
+                    LimiterSummary {
+                        denom: "denoma".to_string(),
+                        label: "1h".to_string(),
+                        value: Decimal::percent(40),
+                        upper_limit: None,
+                    },
+                    LimiterSummary {
+                        denom: "denoma".to_string(),
+                        label: "static".to_string(),
+                        value: Decimal::percent(40),
+                        upper_limit: Some(Decimal::percent(60)),
+                    },
+                ]
+            );
+
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![(
+                        "denoma".to_string(),
+                        (Decimal::percent(40), Decimal::percent(40)),
+                    )],
+                    block_time,
+                )
+                .unwrap();
+
+            // missing weight is summarized as zero
+            assert_eq!(
+                limiter
+                    .summarize(&deps.storage, vec![], block_time.plus_seconds(60))
+                    .unwrap(),
+                vec![
+                    LimiterSummary {
+                        denom: "denoma".to_string(),
+                        label: "1h".to_string(),
+                        value: Decimal::zero(),
+                        upper_limit: Some(Decimal::percent(60)),
+                    },
+                    LimiterSummary {
+                        denom: "denoma".to_string(),
+                        label: "static".to_string(),
+                        value: Decimal::zero(),
+                        upper_limit: Some(Decimal::percent(60)),
+                    },
+                ]
+            );
+        }
+    }
+
+    mod prune_outdated_divisions {
+        use super::*;
+
+        #[test]
+        fn test_prune_keeps_upper_limit() {
+            let mut deps = mock_dependencies();
+            let limiters = Limiters::new("limiters", "limiter_count");
+
+            limiters
+                .register(
+                    &mut deps.storage,
+                    "denoma",
+                    "1h",
+                    LimiterParams::ChangeLimiter {
+                        window_config: WindowConfig {
+                            window_size: Uint64::from(3_600_000_000_000u64), // 1 hrs
+                            division_count: Uint64::from(2u64),              // 30 mins each
+                        },
+                        boundary_offset: Decimal::percent(10),
+                    },
+                )
+                .unwrap();
+
+            let block_time = Timestamp::from_nanos(1661231280000000000);
+            for (minutes, value) in [(0, 40), (31, 45), (62, 50)] {
+                limiters
+                    .check_limits_and_update(
+                        &mut deps.storage,
+                        vec![(
+                            "denoma".to_string(),
+                            (Decimal::percent(value), Decimal::percent(value)),
+                        )],
+                        block_time.plus_minutes(minutes),
+                    )
+                    .unwrap();
+            }
+            let divisions = list_divisions(&limiters, "denoma", "1h", &deps.storage);
+            assert_eq!(divisions.len(), 3);
+            let latest_division = divisions[2].clone();
+
+            // every division is outdated, only the latest one is kept
+            let block_time = block_time.plus_hours(3);
+            let summary = limiters
+                .summarize(&deps.storage, vec![], block_time)
+                .unwrap();
+
+            limiters
+                .prune_outdated_divisions(&mut deps.storage, block_time)
+                .unwrap();
+
+            assert_eq!(
+                list_divisions(&limiters, "denoma", "1h", &deps.storage),
+                vec![latest_division]
+            );
+            assert_eq!(
+                limiters
+                    .summarize(&deps.storage, vec![], block_time)
+                    .unwrap(),
+                summary
+            );
+        }
+    }
+
+    mod limiter_count {
+        use super::*;
+
+        #[test]
+        fn test_limiter_count() {
+            let mut deps = mock_dependencies();
+            let limiter = Limiters::new("limiters", "limiter_count");
+
+            // untracked count is assumed to have limiters
+            assert!(limiter.any_registered(&deps.storage).unwrap());
+
+            limiter.sync_count(&mut deps.storage).unwrap();
+            assert!(!limiter.any_registered(&deps.storage).unwrap());
+
+            for label in ["static1", "static2"] {
+                limiter
+                    .register(
+                        &mut deps.storage,
+                        "denoma",
+                        label,
+                        LimiterParams::StaticLimiter {
+                            upper_limit: Decimal::percent(60),
+                        },
+                    )
+                    .unwrap();
+            }
+            assert_eq!(limiter.count.load(&deps.storage).unwrap(), 2);
+            assert!(limiter.any_registered(&deps.storage).unwrap());
+
+            limiter
+                .deregister(&mut deps.storage, "denoma", "static1")
+                .unwrap();
+            assert_eq!(limiter.count.load(&deps.storage).unwrap(), 1);
+
+            limiter
+                .uncheck_deregister_all_for_denom(&mut deps.storage, "denoma")
+                .unwrap();
+            assert_eq!(limiter.count.load(&deps.storage).unwrap(), 0);
+            assert!(!limiter.any_registered(&deps.storage).unwrap());
+
+            // nothing to check without limiters
+            limiter
+                .check_limits_and_update(
+                    &mut deps.storage,
+                    vec![(
+                        "denoma".to_string(),
+                        (Decimal::percent(50), Decimal::percent(100)),
+                    )],
+                    Timestamp::from_nanos(1000),
+                )
+                .unwrap();
+            assert_eq!(
+                limiter
+                    .check_limits(
+                        &deps.storage,
+                        vec![(
+                            "denoma".to_string(),
+                            (Decimal::percent(50), Decimal::percent(100)),
+                        )],
+                        Timestamp::from_nanos(1000),
+                    )
+                    .unwrap(),
+                vec![]
+            );
+        }
+    }
+
     mod reset_change_limiter_states {
         use cosmwasm_std::Order;
 
@@ -2920,7 +3468,7 @@ mod tests {
         #[test]
         fn test_reset_change_limiter_states() {
             let mut deps = mock_dependencies();
-            let limiters = Limiters::new("limiters");
+            let limiters = Limiters::new("limiters", "limiter_count");
 
             // register 2 change limiters
             let config_1h = WindowConfig {
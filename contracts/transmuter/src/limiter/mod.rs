@@ -1,10 +1,13 @@
 mod division;
 mod helpers;
 mod limiters;
+mod rate_limiter;
+mod tuning;
 
-pub use limiters::{Limiter, LimiterParams, Limiters};
-
-#[cfg(test)]
 pub use division::Division;
-#[cfg(test)]
-pub use limiters::{ChangeLimiter, StaticLimiter, WindowConfig};
+pub use limiters::{
+    ChangeLimiter, Limiter, LimiterParams, LimiterSummary, LimiterVerdict, Limiters, StaticLimiter,
+    WindowConfig,
+};
+pub use rate_limiter::{RateLimiter, RateLimiters};
+pub use tuning::{LimiterTuning, LimiterTuningBounds, LimiterTuningState};
@@ -0,0 +1,374 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Order, StdResult, Storage, Timestamp, Uint64};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+use super::{helpers::*, limiters::WindowConfig};
+
+/// Number of actions counted within a division of the window
+#[cw_serde]
+pub struct ActionDivision {
+    started_at: Timestamp,
+    count: u64,
+}
+
+/// Limiter that caps the number of actions within a sliding window.
+///
+/// Actions are counted per division, the same way [`super::ChangeLimiter`] compresses
+/// its data points, so that the count in the window is bounded in size. Divisions that
+/// partially overlap with the window are counted as a whole, which errs on the side of
+/// limiting more.
+#[cw_serde]
+pub struct RateLimiter {
+    window_config: WindowConfig,
+
+    /// Maximum number of actions allowed within the window
+    max_actions: u64,
+
+    /// Divisions in the window, ordered from oldest to newest
+    divisions: Vec<ActionDivision>,
+}
+
+impl RateLimiter {
+    pub fn new(window_config: WindowConfig, max_actions: u64) -> Result<Self, ContractError> {
+        window_config.validate()?;
+
+        Ok(Self {
+            window_config,
+            max_actions,
+            divisions: vec![],
+        })
+    }
+
+    pub fn window_config(&self) -> &WindowConfig {
+        &self.window_config
+    }
+
+    pub fn max_actions(&self) -> u64 {
+        self.max_actions
+    }
+
+    /// Number of actions counted within the window at `block_time`
+    pub fn actions_in_window(&self, block_time: Timestamp) -> Result<u64, ContractError> {
+        let window_started_at =
+            backward(block_time.nanos(), self.window_config.window_size).unwrap_or(Uint64::zero());
+        let division_size = self.window_config.division_size()?;
+
+        let mut count = 0u64;
+        for division in &self.divisions {
+            if forward(division.started_at.nanos(), division_size)? > window_started_at {
+                count = count.saturating_add(division.count);
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Remove divisions the window has completely passed at `block_time`
+    fn clean_up_outdated_divisions(self, block_time: Timestamp) -> Result<Self, ContractError> {
+        let window_started_at =
+            backward(block_time.nanos(), self.window_config.window_size).unwrap_or(Uint64::zero());
+        let division_size = self.window_config.division_size()?;
+
+        let mut divisions = vec![];
+        for division in self.divisions {
+            if forward(division.started_at.nanos(), division_size)? > window_started_at {
+                divisions.push(division);
+            }
+        }
+
+        Ok(Self { divisions, ..self })
+    }
+
+    /// Count an action at `block_time`, failing if it exceeds the maximum number of actions
+    /// within the window for `subject`
+    fn consume(self, subject: &str, block_time: Timestamp) -> Result<Self, ContractError> {
+        let limiter = self.clean_up_outdated_divisions(block_time)?;
+        let division_size = limiter.window_config.division_size()?;
+        let mut divisions = limiter.divisions;
+
+        let count = divisions
+            .iter()
+            .fold(0u64, |count, division| count.saturating_add(division.count));
+        ensure!(
+            count < limiter.max_actions,
+            ContractError::RateLimitExceeded {
+                subject: subject.to_string(),
+                max_actions: limiter.max_actions,
+                window_size: limiter.window_config.window_size,
+            }
+        );
+
+        match divisions.last_mut() {
+            Some(latest_division)
+                if elapsed_time(latest_division.started_at.nanos(), block_time.nanos())?
+                    < division_size =>
+            {
+                latest_division.count += 1;
+            }
+            latest_division => {
+                // align new division with the previous ones
+                let started_at = match latest_division {
+                    Some(latest_division) => {
+                        let elapsed_time_within_next_div =
+                            elapsed_time(latest_division.started_at.nanos(), block_time.nanos())?
+                                .checked_rem(division_size)?;
+                        Timestamp::from_nanos(
+                            backward(block_time.nanos(), elapsed_time_within_next_div)?.u64(),
+                        )
+                    }
+                    None => block_time,
+                };
+
+                divisions.push(ActionDivision {
+                    started_at,
+                    count: 1,
+                });
+            }
+        }
+
+        Ok(Self {
+            divisions,
+            ..limiter
+        })
+    }
+}
+
+/// Rate limiters keyed by what they limit, e.g. a role
+pub struct RateLimiters<'a> {
+    limiters: Map<'a, &'a str, RateLimiter>,
+}
+
+impl<'a> RateLimiters<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            limiters: Map::new(namespace),
+        }
+    }
+
+    /// Set rate limit for `subject`, resetting its count, `None` to remove it
+    pub fn set(
+        &self,
+        storage: &mut dyn Storage,
+        subject: &str,
+        rate_limit: Option<(WindowConfig, u64)>,
+    ) -> Result<(), ContractError> {
+        match rate_limit {
+            Some((window_config, max_actions)) => {
+                let limiter = RateLimiter::new(window_config, max_actions)?;
+                self.limiters.save(storage, subject, &limiter)?;
+            }
+            None => self.limiters.remove(storage, subject),
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, storage: &dyn Storage, subject: &str) -> StdResult<Option<RateLimiter>> {
+        self.limiters.may_load(storage, subject)
+    }
+
+    /// Prune outdated divisions of every rate limiter, which otherwise only gets cleaned up
+    /// when its subject acts
+    pub fn prune_outdated_divisions(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let limiters = self
+            .limiters
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for (subject, limiter) in limiters {
+            let division_count = limiter.divisions.len();
+            let limiter = limiter.clean_up_outdated_divisions(block_time)?;
+
+            if limiter.divisions.len() != division_count {
+                self.limiters.save(storage, &subject, &limiter)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Count an action of `subject` if it is rate limited
+    pub fn consume(
+        &self,
+        storage: &mut dyn Storage,
+        subject: &str,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        if let Some(limiter) = self.get(storage, subject)? {
+            let limiter = limiter.consume(subject, block_time)?;
+            self.limiters.save(storage, subject, &limiter)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    const HOUR: u64 = 60 * 60 * 1_000_000_000;
+
+    #[test]
+    fn test_consume_within_sliding_window() {
+        let mut deps = mock_dependencies();
+        let rate_limiters = RateLimiters::new("rate_limiters");
+        let start = Timestamp::from_seconds(1_000_000);
+
+        // not rate limited
+        for _ in 0..10 {
+            rate_limiters
+                .consume(&mut deps.storage, "moderator", start)
+                .unwrap();
+        }
+
+        assert_eq!(
+            rate_limiters
+                .set(
+                    &mut deps.storage,
+                    "moderator",
+                    Some((
+                        WindowConfig {
+                            window_size: Uint64::new(24 * HOUR),
+                            division_count: Uint64::new(5),
+                        },
+                        2,
+                    )),
+                )
+                .unwrap_err(),
+            ContractError::UnevenWindowDivision {}
+        );
+
+        // at most 2 actions per day, counted per 6 hours division
+        rate_limiters
+            .set(
+                &mut deps.storage,
+                "moderator",
+                Some((
+                    WindowConfig {
+                        window_size: Uint64::new(24 * HOUR),
+                        division_count: Uint64::new(4),
+                    },
+                    2,
+                )),
+            )
+            .unwrap();
+
+        rate_limiters
+            .consume(&mut deps.storage, "moderator", start)
+            .unwrap();
+        rate_limiters
+            .consume(&mut deps.storage, "moderator", start.plus_nanos(7 * HOUR))
+            .unwrap();
+
+        let err = rate_limiters
+            .consume(&mut deps.storage, "moderator", start.plus_nanos(23 * HOUR))
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::RateLimitExceeded {
+                subject: "moderator".to_string(),
+                max_actions: 2,
+                window_size: Uint64::new(24 * HOUR),
+            }
+        );
+
+        // other subjects are not affected
+        rate_limiters
+            .consume(&mut deps.storage, "pauser", start.plus_nanos(23 * HOUR))
+            .unwrap();
+
+        // first division is out of the window once the window passed its end
+        let limiter = rate_limiters
+            .get(&deps.storage, "moderator")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            limiter
+                .actions_in_window(start.plus_nanos(29 * HOUR))
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            limiter
+                .actions_in_window(start.plus_nanos(30 * HOUR))
+                .unwrap(),
+            1
+        );
+
+        rate_limiters
+            .consume(&mut deps.storage, "moderator", start.plus_nanos(30 * HOUR))
+            .unwrap();
+        let limiter = rate_limiters
+            .get(&deps.storage, "moderator")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            limiter.divisions,
+            vec![
+                ActionDivision {
+                    started_at: start.plus_nanos(6 * HOUR),
+                    count: 1,
+                },
+                ActionDivision {
+                    started_at: start.plus_nanos(30 * HOUR),
+                    count: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_outdated_divisions() {
+        let mut deps = mock_dependencies();
+        let rate_limiters = RateLimiters::new("rate_limiters");
+        let start = Timestamp::from_seconds(1_000_000);
+
+        // at most 2 actions per day, counted per 6 hours division
+        rate_limiters
+            .set(
+                &mut deps.storage,
+                "moderator",
+                Some((
+                    WindowConfig {
+                        window_size: Uint64::new(24 * HOUR),
+                        division_count: Uint64::new(4),
+                    },
+                    2,
+                )),
+            )
+            .unwrap();
+        rate_limiters
+            .consume(&mut deps.storage, "moderator", start)
+            .unwrap();
+        rate_limiters
+            .consume(&mut deps.storage, "moderator", start.plus_nanos(7 * HOUR))
+            .unwrap();
+
+        // the first division is out of the window
+        let block_time = start.plus_nanos(31 * HOUR);
+        rate_limiters
+            .prune_outdated_divisions(&mut deps.storage, block_time)
+            .unwrap();
+
+        let limiter = rate_limiters
+            .get(&deps.storage, "moderator")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            limiter.divisions,
+            vec![ActionDivision {
+                started_at: start.plus_nanos(6 * HOUR),
+                count: 1,
+            }]
+        );
+        assert_eq!(limiter.actions_in_window(block_time), Ok(1));
+    }
+}
@@ -0,0 +1,237 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Decimal, StdResult, Storage, Timestamp};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+/// Bounds within which limiter tuner can adjust a limiter
+#[cw_serde]
+pub struct LimiterTuningBounds {
+    /// Maximum change relative to the value at the start of the period,
+    /// e.g. 0.05 allows the value to move within ±5% per period
+    pub max_change_ratio: Decimal,
+    /// Length of the period in seconds
+    pub period: u64,
+}
+
+/// Value that changes are bounded around within the current period
+#[cw_serde]
+pub struct LimiterTuningState {
+    pub reference_value: Decimal,
+    pub period_started_at: Timestamp,
+}
+
+/// Admin defined bounds on how much limiter tuner can adjust each limiter's parameter,
+/// i.e. change limiter's boundary offset or static limiter's upper limit.
+pub struct LimiterTuning<'a> {
+    /// Map of (denom, label) -> bounds
+    bounds: Map<'a, (&'a str, &'a str), LimiterTuningBounds>,
+
+    /// Map of (denom, label) -> state of the current period
+    states: Map<'a, (&'a str, &'a str), LimiterTuningState>,
+}
+
+impl<'a> LimiterTuning<'a> {
+    pub const fn new(bounds_namespace: &'a str, states_namespace: &'a str) -> Self {
+        Self {
+            bounds: Map::new(bounds_namespace),
+            states: Map::new(states_namespace),
+        }
+    }
+
+    /// Set bounds for the limiter, `None` to disallow tuning it
+    pub fn set_bounds(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        bounds: Option<LimiterTuningBounds>,
+    ) -> Result<(), ContractError> {
+        match bounds {
+            Some(bounds) => {
+                ensure!(bounds.period > 0, ContractError::ZeroLimiterTuningPeriod {});
+                self.bounds.save(storage, (denom, label), &bounds)?;
+            }
+            None => self.bounds.remove(storage, (denom, label)),
+        }
+        self.reset(storage, denom, label);
+
+        Ok(())
+    }
+
+    pub fn bounds(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+    ) -> StdResult<Option<LimiterTuningBounds>> {
+        self.bounds.may_load(storage, (denom, label))
+    }
+
+    pub fn state(
+        &self,
+        storage: &dyn Storage,
+        denom: &str,
+        label: &str,
+    ) -> StdResult<Option<LimiterTuningState>> {
+        self.states.may_load(storage, (denom, label))
+    }
+
+    /// Ensure changing the limiter from `current_value` to `value` at `block_time` is within its bounds.
+    /// The value at the start of each period is the reference the bounds are relative to.
+    pub fn ensure_within_bounds(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        label: &str,
+        current_value: Decimal,
+        value: Decimal,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let bounds = self.bounds(storage, denom, label)?.ok_or_else(|| {
+            ContractError::LimiterTuningNotAllowed {
+                denom: denom.to_string(),
+                label: label.to_string(),
+            }
+        })?;
+
+        let state = match self.state(storage, denom, label)? {
+            Some(state) if block_time < state.period_started_at.plus_seconds(bounds.period) => {
+                state
+            }
+            _ => LimiterTuningState {
+                reference_value: current_value,
+                period_started_at: block_time,
+            },
+        };
+
+        let max_change = state.reference_value.checked_mul(bounds.max_change_ratio)?;
+        let min = state.reference_value.saturating_sub(max_change);
+        let max = state.reference_value.saturating_add(max_change);
+        ensure!(
+            min <= value && value <= max,
+            ContractError::LimiterTuningOutOfBounds {
+                denom: denom.to_string(),
+                label: label.to_string(),
+                value,
+                min,
+                max
+            }
+        );
+
+        self.states.save(storage, (denom, label), &state)?;
+
+        Ok(())
+    }
+
+    /// Forget the current period, e.g. when admin sets the value directly
+    pub fn reset(&self, storage: &mut dyn Storage, denom: &str, label: &str) {
+        self.states.remove(storage, (denom, label));
+    }
+
+    /// Remove bounds and state of the limiter, e.g. when it is deregistered
+    pub fn remove(&self, storage: &mut dyn Storage, denom: &str, label: &str) {
+        self.bounds.remove(storage, (denom, label));
+        self.reset(storage, denom, label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_ensure_within_bounds() {
+        let mut deps = mock_dependencies();
+        let tuning = LimiterTuning::new("bounds", "states");
+        let now = Timestamp::from_seconds(1_000_000);
+
+        // not allowed without bounds
+        assert_eq!(
+            tuning
+                .ensure_within_bounds(
+                    &mut deps.storage,
+                    "uosmo",
+                    "1h",
+                    Decimal::percent(50),
+                    Decimal::percent(51),
+                    now
+                )
+                .unwrap_err(),
+            ContractError::LimiterTuningNotAllowed {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string()
+            }
+        );
+
+        tuning
+            .set_bounds(
+                &mut deps.storage,
+                "uosmo",
+                "1h",
+                Some(LimiterTuningBounds {
+                    max_change_ratio: Decimal::percent(5),
+                    period: DAY,
+                }),
+            )
+            .unwrap();
+
+        // ±5% of 0.5 per day
+        tuning
+            .ensure_within_bounds(
+                &mut deps.storage,
+                "uosmo",
+                "1h",
+                Decimal::percent(50),
+                Decimal::permille(510),
+                now,
+            )
+            .unwrap();
+
+        // bounded around the value at the start of the period, not the latest value
+        assert_eq!(
+            tuning
+                .ensure_within_bounds(
+                    &mut deps.storage,
+                    "uosmo",
+                    "1h",
+                    Decimal::permille(510),
+                    Decimal::permille(530),
+                    now.plus_seconds(DAY - 1)
+                )
+                .unwrap_err(),
+            ContractError::LimiterTuningOutOfBounds {
+                denom: "uosmo".to_string(),
+                label: "1h".to_string(),
+                value: Decimal::permille(530),
+                min: Decimal::permille(475),
+                max: Decimal::permille(525),
+            }
+        );
+
+        // next period is bounded around the value at its start
+        tuning
+            .ensure_within_bounds(
+                &mut deps.storage,
+                "uosmo",
+                "1h",
+                Decimal::permille(510),
+                Decimal::permille(530),
+                now.plus_seconds(DAY),
+            )
+            .unwrap();
+        assert_eq!(
+            tuning.state(&deps.storage, "uosmo", "1h").unwrap(),
+            Some(LimiterTuningState {
+                reference_value: Decimal::permille(510),
+                period_started_at: now.plus_seconds(DAY),
+            })
+        );
+
+        // other limiters are not affected
+        assert_eq!(tuning.bounds(&deps.storage, "uosmo", "1w").unwrap(), None);
+    }
+}
@@ -0,0 +1,309 @@
+use bech32::{ToBase32, Variant};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    ensure, ensure_eq, to_json_vec, Addr, Api, Binary, Coin, CosmosMsg, Env, StdResult, Storage,
+    Timestamp,
+};
+use cw_storage_plus::Map;
+use osmosis_std::{
+    shim::Any,
+    types::cosmos::{authz::v1beta1::MsgExec, bank::v1beta1::MsgSend},
+};
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use crate::ContractError;
+
+/// Message a signer can have a relayer execute on their behalf
+#[cw_serde]
+pub enum MetaTxMsg {
+    /// Join pool with `tokens_in` of the signer, pulled via the bank `SendAuthorization`
+    /// the signer granted to the contract
+    JoinPool { tokens_in: Vec<Coin> },
+    /// Exit pool for `tokens_out`, burning the signer's alloyed asset
+    ExitPool { tokens_out: Vec<Coin> },
+}
+
+/// Payload signed by `signer`. Chain id and contract address keep the signature from being
+/// replayed on another chain or pool, and `nonce` from being replayed on this one.
+/// `deadline` is required, so that a relayer can't hold a payload to submit it at any later time.
+#[cw_serde]
+pub struct MetaTxPayload {
+    pub chain_id: String,
+    pub contract: String,
+    pub signer: String,
+    pub nonce: u64,
+    pub deadline: Timestamp,
+    pub msg: MetaTxMsg,
+}
+
+impl MetaTxPayload {
+    /// Bytes the signer signs: sha256 of the JSON encoded payload
+    pub fn sign_bytes(&self) -> StdResult<[u8; 32]> {
+        Ok(Sha256::digest(to_json_vec(self)?).into())
+    }
+}
+
+/// Address with `prefix` of the account with compressed secp256k1 `pubkey`,
+/// i.e. bech32 of ripemd160(sha256(pubkey))
+pub fn pubkey_address(prefix: &str, pubkey: &[u8]) -> Result<String, ContractError> {
+    let hash = Ripemd160::digest(Sha256::digest(pubkey));
+
+    bech32::encode(prefix, hash.to_base32(), Variant::Bech32)
+        .map_err(|_| ContractError::InvalidMetaTxPubkey {})
+}
+
+/// Signers' next nonce
+pub struct MetaTxs<'a> {
+    nonces: Map<'a, &'a Addr, u64>,
+}
+
+impl<'a> MetaTxs<'a> {
+    pub const fn new(nonces_namespace: &'a str) -> Self {
+        Self {
+            nonces: Map::new(nonces_namespace),
+        }
+    }
+
+    pub fn next_nonce(&self, storage: &dyn Storage, signer: &Addr) -> StdResult<u64> {
+        self.nonces
+            .may_load(storage, signer)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Verify `signature` of `payload` with `pubkey`, which must be the key of the signer
+    /// account, and consume its nonce, returning the signer
+    pub fn verify_and_consume(
+        &self,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        env: &Env,
+        payload: &MetaTxPayload,
+        pubkey: &Binary,
+        signature: &Binary,
+    ) -> Result<Addr, ContractError> {
+        ensure!(
+            payload.chain_id == env.block.chain_id
+                && payload.contract == env.contract.address.as_str(),
+            ContractError::MetaTxDomainMismatch {}
+        );
+
+        // only compressed keys derive account addresses
+        ensure!(pubkey.len() == 33, ContractError::InvalidMetaTxPubkey {});
+
+        let signer = api.addr_validate(&payload.signer)?;
+        let (prefix, _, _) =
+            bech32::decode(signer.as_str()).map_err(|_| ContractError::MetaTxPubkeyMismatch {
+                signer: signer.to_string(),
+            })?;
+        ensure!(
+            pubkey_address(&prefix, pubkey)? == signer.as_str(),
+            ContractError::MetaTxPubkeyMismatch {
+                signer: signer.to_string(),
+            }
+        );
+
+        let expected = self.next_nonce(storage, &signer)?;
+        ensure_eq!(
+            payload.nonce,
+            expected,
+            ContractError::InvalidMetaTxNonce {
+                expected,
+                actual: payload.nonce
+            }
+        );
+
+        let verified = api
+            .secp256k1_verify(&payload.sign_bytes()?, signature, pubkey)
+            .unwrap_or(false);
+        ensure!(verified, ContractError::InvalidMetaTxSignature {});
+
+        self.nonces.save(storage, &signer, &(expected + 1))?;
+
+        Ok(signer)
+    }
+}
+
+/// Message pulling `amount` from `signer` to `contract` with the contract's bank
+/// `SendAuthorization` granted by the signer
+pub fn pull_funds_msg(contract: &Addr, signer: &Addr, amount: &[Coin]) -> CosmosMsg {
+    let send = MsgSend {
+        from_address: signer.to_string(),
+        to_address: contract.to_string(),
+        amount: amount.iter().cloned().map(Into::into).collect(),
+    };
+
+    MsgExec {
+        grantee: contract.to_string(),
+        msgs: vec![Any {
+            type_url: MsgSend::TYPE_URL.to_string(),
+            value: Binary::from(send).to_vec(),
+        }],
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, Signature, SigningKey};
+
+    #[test]
+    fn test_pubkey_address() {
+        // secp256k1 key of private key 1
+        let pubkey = Binary::from_base64("Anm+Zn753LusVaBilc6HCwcCm/zbLc4o2VnygVsW+BeY").unwrap();
+
+        assert_eq!(
+            pubkey_address("cosmos", &pubkey).unwrap(),
+            "cosmos1w508d6qejxtdg4y5r3zarvary0c5xw7k6ah60c"
+        );
+    }
+
+    #[test]
+    fn test_verify_and_consume() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let meta_txs = MetaTxs::new("nonces");
+
+        let signing_key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let pubkey = Binary::from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        );
+        let signer = Addr::unchecked(pubkey_address("osmo", &pubkey).unwrap());
+        let sign = |payload: &MetaTxPayload| -> Binary {
+            let signature: Signature = signing_key
+                .sign_prehash(&payload.sign_bytes().unwrap())
+                .unwrap();
+            Binary::from(signature.to_bytes().as_slice())
+        };
+
+        let payload = MetaTxPayload {
+            chain_id: env.block.chain_id.clone(),
+            contract: env.contract.address.to_string(),
+            signer: signer.to_string(),
+            nonce: 0,
+            deadline: env.block.time.plus_seconds(60),
+            msg: MetaTxMsg::ExitPool {
+                tokens_out: vec![Coin::new(100, "uosmo")],
+            },
+        };
+        let signature = sign(&payload);
+
+        // pubkey must be compressed and of the signer account
+        let uncompressed = Binary::from(
+            signing_key
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        );
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &payload,
+                    &uncompressed,
+                    &signature
+                )
+                .unwrap_err(),
+            ContractError::InvalidMetaTxPubkey {}
+        );
+
+        let other_key = SigningKey::from_bytes(&[2u8; 32].into()).unwrap();
+        let other_pubkey =
+            Binary::from(other_key.verifying_key().to_encoded_point(true).as_bytes());
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &payload,
+                    &other_pubkey,
+                    &signature
+                )
+                .unwrap_err(),
+            ContractError::MetaTxPubkeyMismatch {
+                signer: signer.to_string()
+            }
+        );
+
+        // signature must be of the exact payload
+        let tampered = MetaTxPayload {
+            msg: MetaTxMsg::ExitPool {
+                tokens_out: vec![Coin::new(1000, "uosmo")],
+            },
+            ..payload.clone()
+        };
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &tampered,
+                    &pubkey,
+                    &signature
+                )
+                .unwrap_err(),
+            ContractError::InvalidMetaTxSignature {}
+        );
+
+        // payload for another pool is rejected
+        let other_pool = MetaTxPayload {
+            contract: "other_pool".to_string(),
+            ..payload.clone()
+        };
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &other_pool,
+                    &pubkey,
+                    &sign(&other_pool)
+                )
+                .unwrap_err(),
+            ContractError::MetaTxDomainMismatch {}
+        );
+
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &payload,
+                    &pubkey,
+                    &signature
+                )
+                .unwrap(),
+            signer
+        );
+        assert_eq!(meta_txs.next_nonce(&deps.storage, &signer).unwrap(), 1);
+
+        // nonce can't be replayed
+        assert_eq!(
+            meta_txs
+                .verify_and_consume(
+                    &mut deps.storage,
+                    &deps.api,
+                    &env,
+                    &payload,
+                    &pubkey,
+                    &signature
+                )
+                .unwrap_err(),
+            ContractError::InvalidMetaTxNonce {
+                expected: 1,
+                actual: 0
+            }
+        );
+    }
+}
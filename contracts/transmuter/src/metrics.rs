@@ -0,0 +1,151 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{StdResult, Storage};
+use cw_storage_plus::Item;
+
+use crate::account_activity::ActivityKind;
+
+/// Pool-wide activity counters. Transactions that revert, e.g. swaps rejected by a limiter,
+/// roll back their state changes, so they can't be counted on chain. `limiter_capped_count`
+/// counts swaps that went through but were capped by limiters instead: partially filled swaps
+/// and drip order fills that didn't swap the whole remaining amount.
+#[cw_serde]
+#[derive(Default)]
+pub struct MetricCounters {
+    pub swap_count: u64,
+    pub join_count: u64,
+    pub exit_count: u64,
+    pub limiter_capped_count: u64,
+}
+
+/// Counter to increment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Metric {
+    Swap,
+    Join,
+    Exit,
+    LimiterCapped,
+}
+
+impl From<ActivityKind> for Metric {
+    fn from(kind: ActivityKind) -> Self {
+        match kind {
+            ActivityKind::Join => Metric::Join,
+            ActivityKind::Exit => Metric::Exit,
+            ActivityKind::Swap => Metric::Swap,
+        }
+    }
+}
+
+impl MetricCounters {
+    fn increment(&mut self, metric: Metric) {
+        let count = match metric {
+            Metric::Swap => &mut self.swap_count,
+            Metric::Join => &mut self.join_count,
+            Metric::Exit => &mut self.exit_count,
+            Metric::LimiterCapped => &mut self.limiter_capped_count,
+        };
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Lifetime counters along with counters of the current epoch, which are reset by the epoch hook
+pub struct Metrics<'a> {
+    total: Item<'a, MetricCounters>,
+    current_epoch: Item<'a, MetricCounters>,
+}
+
+impl<'a> Metrics<'a> {
+    pub const fn new(total_namespace: &'a str, current_epoch_namespace: &'a str) -> Self {
+        Self {
+            total: Item::new(total_namespace),
+            current_epoch: Item::new(current_epoch_namespace),
+        }
+    }
+
+    pub fn increment(&self, storage: &mut dyn Storage, metric: Metric) -> StdResult<()> {
+        for counters in [&self.total, &self.current_epoch] {
+            let mut value = counters.may_load(storage)?.unwrap_or_default();
+            value.increment(metric);
+            counters.save(storage, &value)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn total(&self, storage: &dyn Storage) -> StdResult<MetricCounters> {
+        self.total.may_load(storage).map(Option::unwrap_or_default)
+    }
+
+    pub fn current_epoch(&self, storage: &dyn Storage) -> StdResult<MetricCounters> {
+        self.current_epoch
+            .may_load(storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Counters of the epoch that just ended, resetting them for the next epoch
+    pub fn end_epoch(&self, storage: &mut dyn Storage) -> StdResult<MetricCounters> {
+        let counters = self.current_epoch(storage)?;
+        self.current_epoch.remove(storage);
+
+        Ok(counters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_metrics() {
+        let mut deps = mock_dependencies();
+        let metrics = Metrics::new("total", "current_epoch");
+
+        assert_eq!(
+            metrics.total(&deps.storage).unwrap(),
+            MetricCounters::default()
+        );
+
+        metrics.increment(&mut deps.storage, Metric::Swap).unwrap();
+        metrics.increment(&mut deps.storage, Metric::Swap).unwrap();
+        metrics
+            .increment(&mut deps.storage, ActivityKind::Join.into())
+            .unwrap();
+        metrics
+            .increment(&mut deps.storage, Metric::LimiterCapped)
+            .unwrap();
+
+        let expected = MetricCounters {
+            swap_count: 2,
+            join_count: 1,
+            exit_count: 0,
+            limiter_capped_count: 1,
+        };
+        assert_eq!(metrics.total(&deps.storage).unwrap(), expected);
+        assert_eq!(metrics.current_epoch(&deps.storage).unwrap(), expected);
+
+        // ending epoch returns its counters and resets them, lifetime counters are kept
+        assert_eq!(metrics.end_epoch(&mut deps.storage).unwrap(), expected);
+        assert_eq!(
+            metrics.current_epoch(&deps.storage).unwrap(),
+            MetricCounters::default()
+        );
+
+        metrics.increment(&mut deps.storage, Metric::Exit).unwrap();
+
+        assert_eq!(
+            metrics.total(&deps.storage).unwrap(),
+            MetricCounters {
+                exit_count: 1,
+                ..expected
+            }
+        );
+        assert_eq!(
+            metrics.current_epoch(&deps.storage).unwrap(),
+            MetricCounters {
+                exit_count: 1,
+                ..MetricCounters::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, ops::Bound};
+
+use cosmwasm_std::{Order, Record, Storage};
+
+/// Storage that reads through to `base` but keeps every write in memory, so that migration steps
+/// can run against live state without committing anything. Writes can also be committed to `base`
+/// afterwards with [commit_writes], to run an operation that must not leave state behind on failure.
+pub struct DryRunStorage<'a> {
+    base: &'a dyn Storage,
+    /// Written values, `None` for removed keys
+    writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> DryRunStorage<'a> {
+    pub fn new(base: &'a dyn Storage) -> Self {
+        Self {
+            base,
+            writes: BTreeMap::new(),
+        }
+    }
+
+    /// Writes kept in memory, `None` for removed keys
+    pub fn into_writes(self) -> BTreeMap<Vec<u8>, Option<Vec<u8>>> {
+        self.writes
+    }
+}
+
+/// Apply `writes` taken from [DryRunStorage::into_writes] to `storage`
+pub fn commit_writes(storage: &mut dyn Storage, writes: BTreeMap<Vec<u8>, Option<Vec<u8>>>) {
+    for (key, value) in writes {
+        match value {
+            Some(value) => storage.set(&key, &value),
+            None => storage.remove(&key),
+        }
+    }
+}
+
+impl Storage for DryRunStorage<'_> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.writes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if start >= end {
+                return Box::new(std::iter::empty());
+            }
+        }
+
+        let mut records: BTreeMap<Vec<u8>, Vec<u8>> =
+            self.base.range(start, end, Order::Ascending).collect();
+
+        let bounds = (
+            start.map_or(Bound::Unbounded, |start| Bound::Included(start.to_vec())),
+            end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_vec())),
+        );
+        for (key, value) in self.writes.range(bounds) {
+            match value {
+                Some(value) => records.insert(key.clone(), value.clone()),
+                None => records.remove(key),
+            };
+        }
+
+        match order {
+            Order::Ascending => Box::new(records.into_iter()),
+            Order::Descending => Box::new(records.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.writes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.writes.insert(key.to_vec(), None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    #[test]
+    fn test_dry_run_storage() {
+        let mut base = MockStorage::new();
+        base.set(b"a", b"1");
+        base.set(b"b", b"2");
+        base.set(b"c", b"3");
+
+        let mut storage = DryRunStorage::new(&base);
+        storage.set(b"b", b"20");
+        storage.set(b"d", b"4");
+        storage.remove(b"a");
+
+        assert_eq!(storage.get(b"a"), None);
+        assert_eq!(storage.get(b"b"), Some(b"20".to_vec()));
+        assert_eq!(storage.get(b"c"), Some(b"3".to_vec()));
+        assert_eq!(storage.get(b"d"), Some(b"4".to_vec()));
+
+        assert_eq!(
+            storage
+                .range(None, None, Order::Ascending)
+                .collect::<Vec<_>>(),
+            vec![
+                (b"b".to_vec(), b"20".to_vec()),
+                (b"c".to_vec(), b"3".to_vec()),
+                (b"d".to_vec(), b"4".to_vec()),
+            ]
+        );
+        assert_eq!(
+            storage
+                .range(Some(b"a"), Some(b"d"), Order::Descending)
+                .collect::<Vec<_>>(),
+            vec![
+                (b"c".to_vec(), b"3".to_vec()),
+                (b"b".to_vec(), b"20".to_vec()),
+            ]
+        );
+        assert_eq!(
+            storage
+                .range(Some(b"d"), Some(b"a"), Order::Ascending)
+                .count(),
+            0
+        );
+
+        // base is left untouched
+        assert_eq!(
+            base.range(None, None, Order::Ascending).collect::<Vec<_>>(),
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec()),
+                (b"c".to_vec(), b"3".to_vec()),
+            ]
+        );
+
+        // until writes are committed
+        let writes = storage.into_writes();
+        commit_writes(&mut base, writes);
+        assert_eq!(
+            base.range(None, None, Order::Ascending).collect::<Vec<_>>(),
+            vec![
+                (b"b".to_vec(), b"20".to_vec()),
+                (b"c".to_vec(), b"3".to_vec()),
+                (b"d".to_vec(), b"4".to_vec()),
+            ]
+        );
+    }
+}
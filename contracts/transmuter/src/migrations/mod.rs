@@ -1 +1,783 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, ensure_eq, to_json_string, DepsMut, Env, Response, Storage, Timestamp};
+use cw2::{ContractVersion, VersionError, CONTRACT};
+
+use crate::{
+    contract::{Transmuter, CONTRACT_NAME, CONTRACT_VERSION},
+    ContractError,
+};
+
+pub(crate) mod dry_run;
 pub mod v3_2_0;
+pub mod v3_3_0;
+
+use dry_run::DryRunStorage;
+
+/// Migration steps, in version order. Each step transforms state of any of its `from_versions`
+/// into that of its `to_version`, so that a pool can be migrated across several versions at once.
+const STEPS: &[MigrationStep] = &[v3_2_0::STEP, v3_3_0::STEP];
+
+/// Migrate message, tagged by the version to migrate to, which must be the version of the code
+/// being migrated to, e.g. `{ "v3_3_0": {} }`. With `dry_run`, the migration runs without
+/// committing anything and fails with a [`MigrationReport`] of what it would have changed.
+/// Migrating to a schema older than the highest ever applied, or re-applying steps the state
+/// already went through, is refused unless `force` is set.
+#[cw_serde]
+pub enum MigrateMsg {
+    V3_3_0 {
+        #[serde(default)]
+        dry_run: bool,
+        #[serde(default)]
+        force: bool,
+    },
+}
+
+impl MigrateMsg {
+    pub fn target_version(&self) -> &'static str {
+        match self {
+            MigrateMsg::V3_3_0 { .. } => v3_3_0::TO_VERSION,
+        }
+    }
+
+    pub fn dry_run(&self) -> bool {
+        match self {
+            MigrateMsg::V3_3_0 { dry_run, .. } => *dry_run,
+        }
+    }
+
+    pub fn force(&self) -> bool {
+        match self {
+            MigrateMsg::V3_3_0 { force, .. } => *force,
+        }
+    }
+}
+
+/// State transform from any of `from_versions` to `to_version`, returning what it changed
+pub struct MigrationStep {
+    pub from_versions: &'static [&'static str],
+    pub to_version: &'static str,
+    pub migrate: fn(&mut dyn Storage, &Env) -> Result<Vec<MigrationChange>, ContractError>,
+}
+
+/// Number of `subject`s a migration step changed, e.g. limiters rewritten or assets re-keyed
+#[cw_serde]
+pub struct MigrationChange {
+    pub subject: String,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub struct MigrationStepReport {
+    pub from_version: String,
+    pub to_version: String,
+    pub changes: Vec<MigrationChange>,
+}
+
+/// What a dry run migration would have changed, serialized as JSON into the error message,
+/// e.g. `{"migration_report":{"steps":[{"from_version":"3.2.0","to_version":"3.3.0","changes":[{"subject":"alloyed_backend_recorded","count":1},{"subject":"limiters_counted","count":0}]}]}}`
+#[cw_serde]
+pub struct MigrationReport {
+    pub steps: Vec<MigrationStepReport>,
+}
+
+#[cw_serde]
+struct MigrationReportPayload {
+    migration_report: MigrationReport,
+}
+
+impl MigrationReport {
+    pub(crate) fn payload(self) -> String {
+        to_json_string(&MigrationReportPayload {
+            migration_report: self,
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// Migration the contract has undergone, recorded on every migrate call
+#[cw_serde]
+pub struct MigrationRecord {
+    pub from_version: String,
+    pub to_version: String,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// Migrate from the stored cw2 version to the target version of `msg`, applying every step
+/// in between in order
+pub fn execute_migration(
+    deps: DepsMut,
+    env: Env,
+    msg: MigrateMsg,
+) -> Result<Response, ContractError> {
+    let target_version = msg.target_version();
+
+    // the target version must be the version of the code being migrated to
+    ensure_eq!(
+        CONTRACT_VERSION,
+        target_version,
+        VersionError::WrongVersion {
+            expected: target_version.to_string(),
+            found: CONTRACT_VERSION.to_string()
+        }
+    );
+
+    migrate_to(deps, env, target_version, msg.dry_run(), msg.force())
+}
+
+/// Migrate from the stored cw2 version to `target_version`, refusing to downgrade the schema
+/// or re-apply steps unless `force` is set
+fn migrate_to(
+    deps: DepsMut,
+    env: Env,
+    target_version: &str,
+    dry_run: bool,
+    force: bool,
+) -> Result<Response, ContractError> {
+    let from_version = stored_version(deps.storage, CONTRACT_NAME)?;
+    let highest_version = match Transmuter::new()
+        .highest_schema_version
+        .may_load(deps.storage)?
+    {
+        Some(highest_version) => max_version(highest_version, from_version.clone())?,
+        None => from_version.clone(),
+    };
+
+    let steps = if parse_version(target_version)? < parse_version(&highest_version)? {
+        // an older wasm would misread state written in the newer layout
+        ensure!(
+            force,
+            ContractError::SchemaDowngrade {
+                target_version: target_version.to_string(),
+                highest_version
+            }
+        );
+
+        // there are no steps back, the version is set as is
+        vec![]
+    } else {
+        let steps = migration_path(STEPS, &from_version, target_version)?;
+
+        // steps up to the highest version were already applied before a forced downgrade
+        for step in steps.iter() {
+            ensure!(
+                force || parse_version(step.to_version)? > parse_version(&highest_version)?,
+                ContractError::SchemaRemigration {
+                    version: step.to_version.to_string(),
+                    highest_version
+                }
+            );
+        }
+
+        steps
+    };
+
+    if dry_run {
+        let mut storage = DryRunStorage::new(deps.storage);
+        let steps = apply_steps(
+            &mut storage,
+            &env,
+            &from_version,
+            target_version,
+            &highest_version,
+            &steps,
+        )?;
+
+        return Err(ContractError::MigrationDryRun {
+            report: MigrationReport { steps }.payload(),
+        });
+    }
+
+    apply_steps(
+        deps.storage,
+        &env,
+        &from_version,
+        target_version,
+        &highest_version,
+        &steps,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", from_version)
+        .add_attribute("to_version", target_version))
+}
+
+/// Apply `steps` in order from `from_version` to `target_version`. Each step sets the stored
+/// version and is recorded in migration history, so that state transformations of the deployment
+/// can be traced. The highest schema version ever applied is kept, to refuse later downgrades.
+fn apply_steps(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from_version: &str,
+    target_version: &str,
+    highest_version: &str,
+    steps: &[&MigrationStep],
+) -> Result<Vec<MigrationStepReport>, ContractError> {
+    let mut reports = vec![];
+    let mut version = from_version.to_string();
+
+    for step in steps {
+        let changes = (step.migrate)(storage, env)?;
+        cw2::set_contract_version(storage, CONTRACT_NAME, step.to_version)?;
+        Transmuter::new().record_migration(
+            storage,
+            env,
+            version.clone(),
+            step.to_version.to_string(),
+        )?;
+
+        reports.push(MigrationStepReport {
+            from_version: version,
+            to_version: step.to_version.to_string(),
+            changes,
+        });
+        version = step.to_version.to_string();
+    }
+
+    // forced downgrade, for which there are no steps
+    if version != target_version {
+        cw2::set_contract_version(storage, CONTRACT_NAME, target_version)?;
+        Transmuter::new().record_migration(
+            storage,
+            env,
+            version.clone(),
+            target_version.to_string(),
+        )?;
+
+        reports.push(MigrationStepReport {
+            from_version: version,
+            to_version: target_version.to_string(),
+            changes: vec![],
+        });
+    }
+
+    Transmuter::new().highest_schema_version.save(
+        storage,
+        &max_version(highest_version.to_string(), target_version.to_string())?,
+    )?;
+
+    Ok(reports)
+}
+
+/// Numeric `major.minor.patch` of `version`, ignoring pre-release and build metadata
+fn parse_version(version: &str) -> Result<(u64, u64, u64), ContractError> {
+    let invalid = || ContractError::InvalidSchemaVersion {
+        version: version.to_string(),
+    };
+
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    let mut parts = core.split('.').map(|part| part.parse::<u64>());
+
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Ok((major, minor, patch)),
+        _ => Err(invalid()),
+    }
+}
+
+fn max_version(a: String, b: String) -> Result<String, ContractError> {
+    if parse_version(&a)? >= parse_version(&b)? {
+        Ok(a)
+    } else {
+        Ok(b)
+    }
+}
+
+/// Stored cw2 version, making sure that it is of `expected_contract`
+fn stored_version(storage: &dyn Storage, expected_contract: &str) -> Result<String, VersionError> {
+    let ContractVersion { contract, version } = match CONTRACT.may_load(storage)? {
+        Some(contract) => contract,
+        None => return Err(VersionError::NotFound),
+    };
+
+    if contract != expected_contract {
+        return Err(VersionError::WrongContract {
+            expected: expected_contract.into(),
+            found: contract,
+        });
+    }
+
+    Ok(version)
+}
+
+/// Steps of `steps` leading from `from_version` to `target_version`, in the order to apply them
+fn migration_path<'a>(
+    steps: &'a [MigrationStep],
+    from_version: &str,
+    target_version: &str,
+) -> Result<Vec<&'a MigrationStep>, VersionError> {
+    let mut path = vec![];
+    let mut version = from_version;
+
+    // every step is applied at most once, so the path can't be longer than all steps
+    while version != target_version && path.len() < steps.len() {
+        let Some(step) = steps
+            .iter()
+            .find(|step| step.from_versions.contains(&version))
+        else {
+            break;
+        };
+
+        path.push(step);
+        version = step.to_version;
+    }
+
+    if version != target_version {
+        return Err(VersionError::WrongVersion {
+            expected: supported_from_versions(steps, target_version).join(","),
+            found: from_version.to_string(),
+        });
+    }
+
+    Ok(path)
+}
+
+/// Versions that can be migrated to `target_version`, possibly via several steps
+fn supported_from_versions<'a>(steps: &'a [MigrationStep], target_version: &str) -> Vec<&'a str> {
+    let mut versions: Vec<&str> = vec![];
+    let mut targets = vec![target_version];
+
+    while let Some(target) = targets.pop() {
+        for step in steps.iter().filter(|step| step.to_version == target) {
+            for from_version in step.from_versions {
+                if !versions.contains(from_version) {
+                    versions.push(from_version);
+                    targets.push(from_version);
+                }
+            }
+        }
+    }
+
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cw_storage_plus::Item;
+
+    use super::*;
+
+    const MIGRATED: Item<Vec<String>> = Item::new("migrated");
+
+    fn record(
+        storage: &mut dyn Storage,
+        to_version: &str,
+    ) -> Result<Vec<MigrationChange>, ContractError> {
+        let mut migrated = MIGRATED.may_load(storage)?.unwrap_or_default();
+        migrated.push(to_version.to_string());
+        MIGRATED.save(storage, &migrated)?;
+
+        Ok(vec![MigrationChange {
+            subject: "records".to_string(),
+            count: migrated.len() as u64,
+        }])
+    }
+
+    const TEST_STEPS: &[MigrationStep] = &[
+        MigrationStep {
+            from_versions: &["1.0.0", "1.1.0"],
+            to_version: "2.0.0",
+            migrate: |storage, _env| record(storage, "2.0.0"),
+        },
+        MigrationStep {
+            from_versions: &["2.0.0"],
+            to_version: "2.1.0",
+            migrate: |storage, _env| record(storage, "2.1.0"),
+        },
+        MigrationStep {
+            from_versions: &["2.1.0"],
+            to_version: "3.0.0",
+            migrate: |storage, _env| record(storage, "3.0.0"),
+        },
+    ];
+
+    #[test]
+    fn test_migration_path() {
+        let to_versions = |from_version: &str, target_version: &str| {
+            migration_path(TEST_STEPS, from_version, target_version).map(|path| {
+                path.into_iter()
+                    .map(|step| step.to_version)
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // several versions are jumped in one go
+        assert_eq!(
+            to_versions("1.1.0", "3.0.0").unwrap(),
+            vec!["2.0.0", "2.1.0", "3.0.0"]
+        );
+        assert_eq!(to_versions("2.0.0", "2.1.0").unwrap(), vec!["2.1.0"]);
+
+        // nothing to apply if already at target version
+        assert_eq!(to_versions("3.0.0", "3.0.0").unwrap(), Vec::<&str>::new());
+
+        // unknown version or target before stored version has no path
+        assert_eq!(
+            to_versions("0.9.0", "3.0.0").unwrap_err(),
+            VersionError::WrongVersion {
+                expected: "2.1.0,2.0.0,1.0.0,1.1.0".to_string(),
+                found: "0.9.0".to_string()
+            }
+        );
+        assert_eq!(
+            to_versions("2.1.0", "2.0.0").unwrap_err(),
+            VersionError::WrongVersion {
+                expected: "1.0.0,1.1.0".to_string(),
+                found: "2.1.0".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_steps_applied_in_order() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        for step in migration_path(TEST_STEPS, "1.0.0", "3.0.0").unwrap() {
+            (step.migrate)(&mut deps.storage, &env).unwrap();
+        }
+
+        assert_eq!(
+            MIGRATED.load(&deps.storage).unwrap(),
+            vec!["2.0.0", "2.1.0", "3.0.0"]
+        );
+    }
+
+    #[test]
+    fn test_dry_run_steps() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "1.0.0").unwrap();
+        let steps = migration_path(TEST_STEPS, "1.0.0", "3.0.0").unwrap();
+
+        let mut storage = DryRunStorage::new(&deps.storage);
+        let reports = apply_steps(&mut storage, &env, "1.0.0", "3.0.0", "1.0.0", &steps).unwrap();
+
+        assert_eq!(
+            reports,
+            vec![
+                MigrationStepReport {
+                    from_version: "1.0.0".to_string(),
+                    to_version: "2.0.0".to_string(),
+                    changes: vec![MigrationChange {
+                        subject: "records".to_string(),
+                        count: 1
+                    }],
+                },
+                MigrationStepReport {
+                    from_version: "2.0.0".to_string(),
+                    to_version: "2.1.0".to_string(),
+                    changes: vec![MigrationChange {
+                        subject: "records".to_string(),
+                        count: 2
+                    }],
+                },
+                MigrationStepReport {
+                    from_version: "2.1.0".to_string(),
+                    to_version: "3.0.0".to_string(),
+                    changes: vec![MigrationChange {
+                        subject: "records".to_string(),
+                        count: 3
+                    }],
+                },
+            ]
+        );
+        assert_eq!(
+            cw2::get_contract_version(&storage).unwrap().version,
+            "3.0.0"
+        );
+
+        // nothing is committed
+        assert_eq!(MIGRATED.may_load(&deps.storage).unwrap(), None);
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "1.0.0"
+        );
+        assert_eq!(
+            Transmuter::new()
+                .migration_history
+                .may_load(&deps.storage)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_dry_run_migration() {
+        let mut deps = mock_dependencies();
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.1.0").unwrap();
+
+        let err = execute_migration(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::V3_3_0 {
+                dry_run: true,
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Migration dry run succeeded, nothing is committed: {\"migration_report\":{\"steps\":[{\"from_version\":\"3.1.0\",\"to_version\":\"3.2.0\",\"changes\":[]},{\"from_version\":\"3.2.0\",\"to_version\":\"3.3.0\",\"changes\":[{\"subject\":\"alloyed_backend_recorded\",\"count\":1},{\"subject\":\"limiters_counted\",\"count\":0}]}]}}"
+        );
+
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "3.1.0"
+        );
+    }
+
+    #[test]
+    fn test_execute_migration() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.2.0").unwrap();
+
+        let res = execute_migration(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_attribute("method", "migrate")
+                .add_attribute("from_version", "3.2.0")
+                .add_attribute("to_version", "3.3.0")
+        );
+
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap(),
+            ContractVersion {
+                contract: CONTRACT_NAME.to_string(),
+                version: "3.3.0".to_string(),
+            }
+        );
+        assert_eq!(
+            Transmuter::new()
+                .highest_schema_version
+                .load(&deps.storage)
+                .unwrap(),
+            "3.3.0"
+        );
+        assert_eq!(
+            Transmuter::new()
+                .migration_history
+                .load(&deps.storage)
+                .unwrap(),
+            vec![MigrationRecord {
+                from_version: "3.2.0".to_string(),
+                to_version: "3.3.0".to_string(),
+                height: env.block.height,
+                time: env.block.time,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_downgrade() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let migrate_msg = |force| MigrateMsg::V3_3_0 {
+            dry_run: false,
+            force,
+        };
+
+        // state was migrated to 3.4.0 before an older wasm was deployed
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.4.0").unwrap();
+        let err = execute_migration(deps.as_mut(), env.clone(), migrate_msg(false)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SchemaDowngrade {
+                target_version: "3.3.0".to_string(),
+                highest_version: "3.4.0".to_string()
+            }
+        );
+
+        execute_migration(deps.as_mut(), env.clone(), migrate_msg(true)).unwrap();
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "3.3.0"
+        );
+
+        let transmuter = Transmuter::new();
+        assert_eq!(
+            transmuter
+                .highest_schema_version
+                .load(&deps.storage)
+                .unwrap(),
+            "3.4.0"
+        );
+        assert_eq!(
+            transmuter.migration_history.load(&deps.storage).unwrap(),
+            vec![MigrationRecord {
+                from_version: "3.4.0".to_string(),
+                to_version: "3.3.0".to_string(),
+                height: env.block.height,
+                time: env.block.time,
+            }]
+        );
+
+        // downgrade is still refused, even though the stored version is the target version
+        let err = execute_migration(deps.as_mut(), env.clone(), migrate_msg(false)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SchemaDowngrade {
+                target_version: "3.3.0".to_string(),
+                highest_version: "3.4.0".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_downgrade_to_previous_version() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.2.0").unwrap();
+        execute_migration(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap();
+
+        // 3.2.0 doesn't know about the state written since, so migrating back is refused
+        let err = migrate_to(deps.as_mut(), env.clone(), "3.2.0", false, false).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SchemaDowngrade {
+                target_version: "3.2.0".to_string(),
+                highest_version: "3.3.0".to_string()
+            }
+        );
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "3.3.0"
+        );
+
+        migrate_to(deps.as_mut(), env, "3.2.0", false, true).unwrap();
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "3.2.0"
+        );
+        assert_eq!(
+            Transmuter::new()
+                .highest_schema_version
+                .load(&deps.storage)
+                .unwrap(),
+            "3.3.0"
+        );
+    }
+
+    #[test]
+    fn test_remigration() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let migrate_msg = |force| MigrateMsg::V3_3_0 {
+            dry_run: false,
+            force,
+        };
+
+        // state was migrated to 3.2.0 before being forced back to 3.1.0
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.1.0").unwrap();
+        Transmuter::new()
+            .highest_schema_version
+            .save(&mut deps.storage, &"3.2.0".to_string())
+            .unwrap();
+
+        let err = execute_migration(deps.as_mut(), env.clone(), migrate_msg(false)).unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::SchemaRemigration {
+                version: "3.2.0".to_string(),
+                highest_version: "3.2.0".to_string()
+            }
+        );
+
+        execute_migration(deps.as_mut(), env, migrate_msg(true)).unwrap();
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap().version,
+            "3.3.0"
+        );
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("3.2.0").unwrap(), (3, 2, 0));
+        assert_eq!(parse_version("3.10.1-rc.1").unwrap(), (3, 10, 1));
+        assert!(parse_version("3.10.1").unwrap() > parse_version("3.9.0").unwrap());
+
+        for version in ["3.2", "3.2.0.1", "v3.2.0", ""] {
+            assert_eq!(
+                parse_version(version).unwrap_err(),
+                ContractError::InvalidSchemaVersion {
+                    version: version.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_stored_version() {
+        let mut deps = mock_dependencies();
+
+        let err = execute_migration(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::VersionError(VersionError::NotFound));
+
+        cw2::set_contract_version(&mut deps.storage, "crates.io:other", "3.1.0").unwrap();
+        let err = execute_migration(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::VersionError(VersionError::WrongContract {
+                expected: CONTRACT_NAME.to_string(),
+                found: "crates.io:other".to_string()
+            })
+        );
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "2.0.0").unwrap();
+        let err = execute_migration(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::VersionError(VersionError::WrongVersion {
+                expected: "3.2.0,3.0.0,3.1.0".to_string(),
+                found: "2.0.0".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use cosmwasm_std::{Env, Storage};
+
+use crate::{
+    alloyed_backend::AlloyedBackendKind,
+    contract::{key, Transmuter},
+    ContractError,
+};
+
+use super::{MigrationChange, MigrationStep};
+
+const FROM_VERSIONS: &[&str] = &["3.2.0"];
+pub const TO_VERSION: &str = "3.3.0";
+
+pub const STEP: MigrationStep = MigrationStep {
+    from_versions: FROM_VERSIONS,
+    to_version: TO_VERSION,
+    migrate,
+};
+
+/// Most storage introduced in 3.3.0 is lazily initialized. Alloyed backend is recorded,
+/// since every pool before 3.3.0 holds alloyed asset as tokenfactory denom, instead of
+/// leaving it to the default. So is limiter count, without which limiters are assumed to be
+/// registered, so that swaps wouldn't skip limiter checks when there is none.
+fn migrate(storage: &mut dyn Storage, _env: &Env) -> Result<Vec<MigrationChange>, ContractError> {
+    let Transmuter {
+        alloyed_asset,
+        limiters,
+        ..
+    } = Transmuter::new();
+
+    let backend_recorded = if storage.get(key::ALLOYED_BACKEND_KIND.as_bytes()).is_none() {
+        alloyed_asset.set_backend_kind(storage, AlloyedBackendKind::TokenFactory)?;
+        1
+    } else {
+        0
+    };
+
+    let limiter_count = limiters.sync_count(storage)?;
+
+    Ok(vec![
+        MigrationChange {
+            subject: "alloyed_backend_recorded".to_string(),
+            count: backend_recorded,
+        },
+        MigrationChange {
+            subject: "limiters_counted".to_string(),
+            count: limiter_count,
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        Decimal,
+    };
+    use cw2::ContractVersion;
+
+    use crate::{
+        contract::CONTRACT_NAME,
+        limiter::LimiterParams,
+        migrations::{execute_migration, MigrateMsg, MigrationRecord},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        assert_eq!(
+            migrate(&mut deps.storage, &env).unwrap(),
+            vec![
+                MigrationChange {
+                    subject: "alloyed_backend_recorded".to_string(),
+                    count: 1,
+                },
+                MigrationChange {
+                    subject: "limiters_counted".to_string(),
+                    count: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            Transmuter::new()
+                .alloyed_asset
+                .get_backend_kind(&deps.storage)
+                .unwrap(),
+            AlloyedBackendKind::TokenFactory
+        );
+        assert!(deps
+            .storage
+            .get(key::ALLOYED_BACKEND_KIND.as_bytes())
+            .is_some());
+
+        // no limiter is counted as such, so that limiter checks are skipped
+        assert!(!Transmuter::new()
+            .limiters
+            .any_registered(&deps.storage)
+            .unwrap());
+
+        // recorded backend is kept
+        assert_eq!(
+            migrate(&mut deps.storage, &env).unwrap(),
+            vec![
+                MigrationChange {
+                    subject: "alloyed_backend_recorded".to_string(),
+                    count: 0,
+                },
+                MigrationChange {
+                    subject: "limiters_counted".to_string(),
+                    count: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step_with_registered_limiters() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let limiters = Transmuter::new().limiters;
+
+        // limiters registered on 3.2.0, before limiter count was recorded
+        for denom in ["denoma", "denomb"] {
+            limiters
+                .register(
+                    &mut deps.storage,
+                    denom,
+                    "static",
+                    LimiterParams::StaticLimiter {
+                        upper_limit: Decimal::percent(60),
+                    },
+                )
+                .unwrap();
+        }
+        deps.storage.remove(key::LIMITER_COUNT.as_bytes());
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.2.0").unwrap();
+
+        let err = execute_migration(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::V3_3_0 {
+                dry_run: true,
+                force: false,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Migration dry run succeeded, nothing is committed: {\"migration_report\":{\"steps\":[{\"from_version\":\"3.2.0\",\"to_version\":\"3.3.0\",\"changes\":[{\"subject\":\"alloyed_backend_recorded\",\"count\":1},{\"subject\":\"limiters_counted\",\"count\":2}]}]}}"
+        );
+
+        execute_migration(
+            deps.as_mut(),
+            env,
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap();
+
+        // limiters keep being checked
+        assert!(limiters.any_registered(&deps.storage).unwrap());
+        assert_eq!(limiters.sync_count(&mut deps.storage).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_successful_migration() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, "3.2.0").unwrap();
+
+        execute_migration(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::V3_3_0 {
+                dry_run: false,
+                force: false,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            cw2::get_contract_version(&deps.storage).unwrap(),
+            ContractVersion {
+                contract: CONTRACT_NAME.to_string(),
+                version: TO_VERSION.to_string(),
+            }
+        );
+
+        // only this step is applied
+        assert_eq!(
+            Transmuter::new()
+                .migration_history
+                .load(&deps.storage)
+                .unwrap(),
+            vec![MigrationRecord {
+                from_version: "3.2.0".to_string(),
+                to_version: TO_VERSION.to_string(),
+                height: env.block.height,
+                time: env.block.time,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_migration_across_versions() {
+        let env = mock_env();
+
+        for from_version in ["3.0.0", "3.1.0"] {
+            let mut deps = mock_dependencies();
+            cw2::set_contract_version(&mut deps.storage, CONTRACT_NAME, from_version).unwrap();
+
+            execute_migration(
+                deps.as_mut(),
+                env.clone(),
+                MigrateMsg::V3_3_0 {
+                    dry_run: false,
+                    force: false,
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                cw2::get_contract_version(&deps.storage).unwrap().version,
+                TO_VERSION
+            );
+            assert_eq!(
+                Transmuter::new()
+                    .migration_history
+                    .load(&deps.storage)
+                    .unwrap(),
+                vec![
+                    MigrationRecord {
+                        from_version: from_version.to_string(),
+                        to_version: "3.2.0".to_string(),
+                        height: env.block.height,
+                        time: env.block.time,
+                    },
+                    MigrationRecord {
+                        from_version: "3.2.0".to_string(),
+                        to_version: TO_VERSION.to_string(),
+                        height: env.block.height,
+                        time: env.block.time,
+                    },
+                ]
+            );
+        }
+    }
+}
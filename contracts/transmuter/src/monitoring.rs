@@ -0,0 +1,189 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Deps, Response, StdResult, Storage, SubMsg, Timestamp, WasmMsg,
+};
+use cw_storage_plus::Item;
+
+use crate::{
+    active_status::InactiveReason,
+    contract::{Transmuter, MONITORING_REPLY_ID},
+    ContractError,
+};
+
+/// Alert the registered monitoring contract receives on critical events, tagged by the action
+/// that caused it
+#[cw_serde]
+pub enum MonitoringMsg {
+    /// Limiters capped `count` swaps of `action`, i.e. partially filled them
+    LimiterCapped { action: String, count: u64 },
+    /// Circuit breaker paused the pool during `action`
+    CircuitBreakerTripped { action: String },
+    /// Admin changed, `new_admin` is `None` if adminship has been renounced
+    AdminChanged {
+        action: String,
+        previous_admin: Option<String>,
+        new_admin: Option<String>,
+    },
+}
+
+/// Optional contract that gets alerted on critical events, so that notification relayers
+/// and other on-chain alerting integrations don't have to poll the pool.
+pub struct Monitoring<'a> {
+    contract: Item<'a, Addr>,
+}
+
+impl<'a> Monitoring<'a> {
+    pub const fn new(contract_namespace: &'a str) -> Self {
+        Self {
+            contract: Item::new(contract_namespace),
+        }
+    }
+
+    /// Registered monitoring contract, `None` if there is none
+    pub fn contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.contract.may_load(storage)
+    }
+
+    /// Register monitoring contract, `None` to unregister
+    pub fn set_contract(&self, storage: &mut dyn Storage, contract: Option<Addr>) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.contract.save(storage, &contract),
+            None => {
+                self.contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Message to call the monitoring contract with `msg`, `None` if there is none
+    pub fn call(&self, storage: &dyn Storage, msg: &MonitoringMsg) -> StdResult<Option<WasmMsg>> {
+        self.contract(storage)?
+            .map(|contract| {
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract.into_string(),
+                    msg: to_json_binary(msg)?,
+                    funds: vec![],
+                })
+            })
+            .transpose()
+    }
+}
+
+/// State that critical events change, compared before and after each message to alert on
+/// however they happened
+pub struct AlertState {
+    limiter_capped_count: u64,
+    circuit_breaker_tripped: bool,
+    admin: Option<Addr>,
+}
+
+impl Transmuter<'_> {
+    /// Alert state before a message, `None` if there is no monitoring contract to alert
+    pub(crate) fn alert_state(
+        &self,
+        deps: Deps,
+        now: Timestamp,
+    ) -> Result<Option<AlertState>, ContractError> {
+        if self.monitoring.contract(deps.storage)?.is_none() {
+            return Ok(None);
+        }
+
+        let circuit_breaker_tripped = matches!(
+            self.active_status.deactivation(deps.storage, now)?,
+            Some(deactivation) if deactivation.reason == InactiveReason::CircuitBreaker
+        );
+
+        Ok(Some(AlertState {
+            limiter_capped_count: self.metrics.total(deps.storage)?.limiter_capped_count,
+            circuit_breaker_tripped,
+            admin: self.role.admin.get(deps)?,
+        }))
+    }
+
+    /// Alert the monitoring contract of critical events `action` caused since `before`,
+    /// via submessages that reply on error, so that a failing monitoring contract can't
+    /// block operations of the pool
+    pub(crate) fn with_alerts(
+        &self,
+        deps: Deps,
+        now: Timestamp,
+        action: &str,
+        before: Option<AlertState>,
+        response: Response,
+    ) -> Result<Response, ContractError> {
+        let (Some(before), Some(after)) = (before, self.alert_state(deps, now)?) else {
+            return Ok(response);
+        };
+
+        let mut alerts = vec![];
+
+        let count = after
+            .limiter_capped_count
+            .saturating_sub(before.limiter_capped_count);
+        if count > 0 {
+            alerts.push(MonitoringMsg::LimiterCapped {
+                action: action.to_string(),
+                count,
+            });
+        }
+
+        if after.circuit_breaker_tripped && !before.circuit_breaker_tripped {
+            alerts.push(MonitoringMsg::CircuitBreakerTripped {
+                action: action.to_string(),
+            });
+        }
+
+        if after.admin != before.admin {
+            alerts.push(MonitoringMsg::AdminChanged {
+                action: action.to_string(),
+                previous_admin: before.admin.map(Addr::into_string),
+                new_admin: after.admin.map(Addr::into_string),
+            });
+        }
+
+        let mut response = response;
+        for alert in alerts {
+            if let Some(msg) = self.monitoring.call(deps.storage, &alert)? {
+                response =
+                    response.add_submessage(SubMsg::reply_on_error(msg, MONITORING_REPLY_ID));
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_call() {
+        let mut deps = mock_dependencies();
+        let monitoring = Monitoring::new("monitoring");
+
+        let msg = MonitoringMsg::CircuitBreakerTripped {
+            action: "swap_exact_amount_in".to_string(),
+        };
+
+        // no monitoring contract registered
+        assert_eq!(monitoring.call(&deps.storage, &msg).unwrap(), None);
+
+        monitoring
+            .set_contract(&mut deps.storage, Some(Addr::unchecked("monitor")))
+            .unwrap();
+        assert_eq!(
+            monitoring.call(&deps.storage, &msg).unwrap(),
+            Some(WasmMsg::Execute {
+                contract_addr: "monitor".to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        monitoring.set_contract(&mut deps.storage, None).unwrap();
+        assert_eq!(monitoring.contract(&deps.storage).unwrap(), None);
+        assert_eq!(monitoring.call(&deps.storage, &msg).unwrap(), None);
+    }
+}
@@ -0,0 +1,569 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult,
+    Uint128, WasmMsg,
+};
+use osmosis_std::types::osmosis::poolmanager::v1beta1::{
+    MsgSwapExactAmountIn, MsgSwapExactAmountOut, SwapAmountInRoute, SwapAmountOutRoute,
+};
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "contract")]
+pub use crate::contract::sv::{ExecMsg, InstantiateMsg, QueryMsg};
+#[cfg(feature = "contract")]
+pub use crate::sudo::SudoMsg;
+
+#[cfg(not(feature = "contract"))]
+pub use composable::{ExecMsg, QueryMsg};
+
+/// Affiliate that receives a share of swap fee, e.g. aggregator that routes the swap
+#[cw_serde]
+pub struct Affiliate {
+    /// Address that receives the fee share
+    pub address: String,
+
+    /// Share of swap fee in basis points
+    pub share_bps: u16,
+}
+
+/// Instruction to forward token out over IBC after the swap
+#[cw_serde]
+pub struct IbcForward {
+    /// Channel on this chain to transfer token out through
+    pub channel: String,
+    /// Receiver on the counterparty chain
+    pub receiver: String,
+    /// Seconds from the block time of the swap until the transfer times out
+    pub timeout_seconds: u64,
+}
+
+/// Encoding of query responses that support more than json
+#[cw_serde]
+#[derive(Default)]
+pub enum ResponseEncoding {
+    #[default]
+    Json,
+    /// Protobuf `google.protobuf.Any` wrapping the poolmanager response message,
+    /// so that the chain can decode it without going through json
+    Protobuf,
+}
+
+#[cw_serde]
+pub struct GetShareDenomResponse {
+    pub share_denom: String,
+}
+
+#[cw_serde]
+pub struct GetTotalSharesResponse {
+    pub total_shares: Uint128,
+}
+
+#[cw_serde]
+pub struct GetTotalPoolLiquidityResponse {
+    pub total_pool_liquidity: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct SpotPriceResponse {
+    pub spot_price: Decimal,
+}
+
+/// Messages that composing contracts send to the transmuter, serialized the same as
+/// the corresponding variants of the contract's messages. They stand in for the contract's
+/// messages when built without the `contract` feature, since those are generated from
+/// the contract itself.
+pub mod composable {
+    use cosmwasm_schema::{cw_serde, QueryResponses};
+    use cosmwasm_std::{Coin, Decimal, Timestamp, Uint128};
+
+    use super::{
+        Affiliate, GetShareDenomResponse, GetTotalPoolLiquidityResponse, GetTotalSharesResponse,
+        IbcForward, ResponseEncoding, SpotPriceResponse,
+    };
+
+    #[cw_serde]
+    #[non_exhaustive]
+    pub enum ExecMsg {
+        JoinPool {
+            deadline: Option<Timestamp>,
+        },
+        ExitPool {
+            tokens_out: Vec<Coin>,
+            owner: Option<String>,
+            to_address: Option<String>,
+            deadline: Option<Timestamp>,
+        },
+        SwapExactAmountIn {
+            token_out_denom: String,
+            token_out_min_amount: Uint128,
+            max_execution_price: Option<Decimal>,
+            allow_partial_fill: Option<bool>,
+            affiliate: Option<Affiliate>,
+            owner: Option<String>,
+            alloyed_amount_in: Option<Uint128>,
+            to_address: Option<String>,
+            ibc_forward: Option<IbcForward>,
+            deadline: Option<Timestamp>,
+        },
+        SwapExactAmountOut {
+            token_out: Coin,
+            token_in_max_amount: Uint128,
+            max_execution_price: Option<Decimal>,
+            affiliate: Option<Affiliate>,
+            owner: Option<String>,
+            to_address: Option<String>,
+            ibc_forward: Option<IbcForward>,
+            deadline: Option<Timestamp>,
+        },
+    }
+
+    #[cw_serde]
+    #[derive(QueryResponses)]
+    #[non_exhaustive]
+    pub enum QueryMsg {
+        #[returns(GetShareDenomResponse)]
+        GetShareDenom {},
+        #[returns(GetTotalSharesResponse)]
+        GetTotalShares {},
+        #[returns(GetTotalPoolLiquidityResponse)]
+        GetTotalPoolLiquidity {},
+        #[returns(SpotPriceResponse)]
+        SpotPrice {
+            base_asset_denom: String,
+            quote_asset_denom: String,
+            encoding: Option<ResponseEncoding>,
+        },
+    }
+}
+
+/// Transmuter at `contract_addr`, building messages to it for contracts composing with it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransmuterRef {
+    contract_addr: Addr,
+}
+
+impl TransmuterRef {
+    pub fn new(contract_addr: Addr) -> Self {
+        Self { contract_addr }
+    }
+
+    pub fn addr(&self) -> &Addr {
+        &self.contract_addr
+    }
+
+    /// Execute `msg` with `funds` on the transmuter
+    pub fn execute(&self, msg: &ExecMsg, funds: Vec<Coin>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.contract_addr.to_string(),
+            msg: to_json_binary(msg)?,
+            funds,
+        }
+        .into())
+    }
+
+    /// Join pool with `tokens_in`, minting alloyed asset to the sender
+    pub fn join_pool(&self, tokens_in: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.execute(&ExecMsg::JoinPool { deadline: None }, tokens_in)
+    }
+
+    /// Exit pool for `tokens_out`, burning alloyed asset from the sender
+    pub fn exit_pool(&self, tokens_out: Vec<Coin>) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecMsg::ExitPool {
+                tokens_out,
+                owner: None,
+                to_address: None,
+                deadline: None,
+            },
+            vec![],
+        )
+    }
+
+    /// Swap `token_in` for at least `token_out_min_amount` of `token_out_denom`,
+    /// without the taker fee of swapping via poolmanager
+    pub fn swap_exact_amount_in(
+        &self,
+        token_in: Coin,
+        token_out_denom: &str,
+        token_out_min_amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        self.execute(
+            &ExecMsg::SwapExactAmountIn {
+                token_out_denom: token_out_denom.to_string(),
+                token_out_min_amount,
+                max_execution_price: None,
+                allow_partial_fill: None,
+                affiliate: None,
+                owner: None,
+                alloyed_amount_in: None,
+                to_address: None,
+                ibc_forward: None,
+                deadline: None,
+            },
+            vec![token_in],
+        )
+    }
+
+    /// Swap at most `token_in_max` for `token_out`, with the unused token in refunded,
+    /// without the taker fee of swapping via poolmanager
+    pub fn swap_exact_amount_out(
+        &self,
+        token_in_max: Coin,
+        token_out: Coin,
+    ) -> StdResult<CosmosMsg> {
+        let token_in_max_amount = token_in_max.amount;
+        self.execute(
+            &ExecMsg::SwapExactAmountOut {
+                token_out,
+                token_in_max_amount,
+                max_execution_price: None,
+                affiliate: None,
+                owner: None,
+                to_address: None,
+                ibc_forward: None,
+                deadline: None,
+            },
+            vec![token_in_max],
+        )
+    }
+
+    /// Query the transmuter with `msg`
+    pub fn query<T: DeserializeOwned>(
+        &self,
+        querier: &QuerierWrapper,
+        msg: &QueryMsg,
+    ) -> StdResult<T> {
+        querier.query_wasm_smart(&self.contract_addr, msg)
+    }
+}
+
+/// Swap `token_in` of `sender` for `token_out_denom` through pool `pool_id` via poolmanager,
+/// which is how swaps get the chain's taker fee and routing
+pub fn poolmanager_swap_exact_amount_in(
+    sender: &Addr,
+    pool_id: u64,
+    token_in: Coin,
+    token_out_denom: &str,
+    token_out_min_amount: Uint128,
+) -> CosmosMsg {
+    MsgSwapExactAmountIn {
+        sender: sender.to_string(),
+        routes: vec![SwapAmountInRoute {
+            pool_id,
+            token_out_denom: token_out_denom.to_string(),
+        }],
+        token_in: Some(token_in.into()),
+        token_out_min_amount: token_out_min_amount.to_string(),
+    }
+    .into()
+}
+
+/// Swap at most `token_in_max_amount` of `token_in_denom` of `sender` for `token_out`
+/// through pool `pool_id` via poolmanager
+pub fn poolmanager_swap_exact_amount_out(
+    sender: &Addr,
+    pool_id: u64,
+    token_in_denom: &str,
+    token_in_max_amount: Uint128,
+    token_out: Coin,
+) -> CosmosMsg {
+    MsgSwapExactAmountOut {
+        sender: sender.to_string(),
+        routes: vec![SwapAmountOutRoute {
+            pool_id,
+            token_in_denom: token_in_denom.to_string(),
+        }],
+        token_in_max_amount: token_in_max_amount.to_string(),
+        token_out: Some(token_out.into()),
+    }
+    .into()
+}
+
+/// Limiter rejection of a swap, join or exit, serialized as JSON into the error message,
+/// e.g. `{"limiter_rejection":{"denom":"uaaa","limit":"0.6","value":"0.75"}}`, so that callers
+/// which only get the error string, like routers calling via submessage, can parse it
+#[cw_serde]
+pub struct LimiterRejection {
+    pub denom: String,
+    /// Upper limit of the weight of `denom`
+    pub limit: Decimal,
+    /// Weight of `denom` the rejected operation would have resulted in
+    pub value: Decimal,
+}
+
+#[cw_serde]
+struct LimiterRejectionPayload {
+    limiter_rejection: LimiterRejection,
+}
+
+const LIMITER_REJECTION_PREFIX: &str = "{\"limiter_rejection\":";
+
+impl LimiterRejection {
+    /// Rejection embedded in `error`, `None` if `error` is not a limiter rejection.
+    /// `error` can be wrapped with more context, as long as the payload is kept intact.
+    pub fn from_error(error: &str) -> Option<Self> {
+        let start = error.find(LIMITER_REJECTION_PREFIX)?;
+        // neither denoms nor decimals contain braces, so the payload ends at the first `}}`
+        let len = error[start..].find("}}")? + 2;
+
+        from_json::<LimiterRejectionPayload>(&error[start..start + len])
+            .ok()
+            .map(|payload| payload.limiter_rejection)
+    }
+
+    #[cfg(feature = "contract")]
+    pub(crate) fn payload(denom: &str, limit: &Decimal, value: &Decimal) -> String {
+        cosmwasm_std::to_json_string(&LimiterRejectionPayload {
+            limiter_rejection: LimiterRejection {
+                denom: denom.to_string(),
+                limit: *limit,
+                value: *value,
+            },
+        })
+        .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::Timestamp;
+
+    #[test]
+    fn test_transmuter_ref() {
+        let transmuter = TransmuterRef::new(Addr::unchecked("transmuter"));
+
+        let CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) = transmuter
+            .swap_exact_amount_in(Coin::new(1000, "uaaa"), "ubbb", Uint128::new(990))
+            .unwrap()
+        else {
+            panic!("not a wasm execute message");
+        };
+        assert_eq!(contract_addr, "transmuter");
+        assert_eq!(funds, vec![Coin::new(1000, "uaaa")]);
+        assert_eq!(
+            from_json::<ExecMsg>(&msg).unwrap(),
+            ExecMsg::SwapExactAmountIn {
+                token_out_denom: "ubbb".to_string(),
+                token_out_min_amount: Uint128::new(990),
+                max_execution_price: None,
+                allow_partial_fill: None,
+                affiliate: None,
+                owner: None,
+                alloyed_amount_in: None,
+                to_address: None,
+                ibc_forward: None,
+                deadline: None,
+            }
+        );
+
+        assert_eq!(
+            transmuter.join_pool(vec![Coin::new(1000, "uaaa")]).unwrap(),
+            WasmMsg::Execute {
+                contract_addr: "transmuter".to_string(),
+                msg: to_json_binary(&ExecMsg::JoinPool { deadline: None }).unwrap(),
+                funds: vec![Coin::new(1000, "uaaa")],
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_poolmanager_swap() {
+        assert_eq!(
+            poolmanager_swap_exact_amount_in(
+                &Addr::unchecked("user"),
+                1,
+                Coin::new(1000, "uaaa"),
+                "ubbb",
+                Uint128::new(990)
+            ),
+            MsgSwapExactAmountIn {
+                sender: "user".to_string(),
+                routes: vec![SwapAmountInRoute {
+                    pool_id: 1,
+                    token_out_denom: "ubbb".to_string(),
+                }],
+                token_in: Some(Coin::new(1000, "uaaa").into()),
+                token_out_min_amount: "990".to_string(),
+            }
+            .into()
+        );
+    }
+
+    #[cfg(feature = "contract")]
+    #[test]
+    fn test_composable_msgs_match_contract_msgs() {
+        use crate::contract::sv;
+
+        let exec_msgs = vec![
+            (
+                composable::ExecMsg::JoinPool {
+                    deadline: Some(Timestamp::from_seconds(100)),
+                },
+                sv::ExecMsg::JoinPool {
+                    deadline: Some(Timestamp::from_seconds(100)),
+                },
+            ),
+            (
+                composable::ExecMsg::ExitPool {
+                    tokens_out: vec![Coin::new(1000, "uaaa")],
+                    owner: Some("owner".to_string()),
+                    to_address: Some("receiver".to_string()),
+                    deadline: None,
+                },
+                sv::ExecMsg::ExitPool {
+                    tokens_out: vec![Coin::new(1000, "uaaa")],
+                    owner: Some("owner".to_string()),
+                    to_address: Some("receiver".to_string()),
+                    deadline: None,
+                },
+            ),
+            (
+                composable::ExecMsg::SwapExactAmountIn {
+                    token_out_denom: "ubbb".to_string(),
+                    token_out_min_amount: Uint128::new(990),
+                    max_execution_price: Some(Decimal::percent(101)),
+                    allow_partial_fill: Some(true),
+                    affiliate: Some(Affiliate {
+                        address: "affiliate".to_string(),
+                        share_bps: 1000,
+                    }),
+                    owner: Some("owner".to_string()),
+                    alloyed_amount_in: Some(Uint128::new(1000)),
+                    to_address: None,
+                    ibc_forward: Some(IbcForward {
+                        channel: "channel-0".to_string(),
+                        receiver: "cosmos1receiver".to_string(),
+                        timeout_seconds: 600,
+                    }),
+                    deadline: Some(Timestamp::from_seconds(100)),
+                },
+                sv::ExecMsg::SwapExactAmountIn {
+                    token_out_denom: "ubbb".to_string(),
+                    token_out_min_amount: Uint128::new(990),
+                    max_execution_price: Some(Decimal::percent(101)),
+                    allow_partial_fill: Some(true),
+                    affiliate: Some(Affiliate {
+                        address: "affiliate".to_string(),
+                        share_bps: 1000,
+                    }),
+                    owner: Some("owner".to_string()),
+                    alloyed_amount_in: Some(Uint128::new(1000)),
+                    to_address: None,
+                    ibc_forward: Some(IbcForward {
+                        channel: "channel-0".to_string(),
+                        receiver: "cosmos1receiver".to_string(),
+                        timeout_seconds: 600,
+                    }),
+                    deadline: Some(Timestamp::from_seconds(100)),
+                },
+            ),
+            (
+                composable::ExecMsg::SwapExactAmountOut {
+                    token_out: Coin::new(1000, "ubbb"),
+                    token_in_max_amount: Uint128::new(1010),
+                    max_execution_price: None,
+                    affiliate: None,
+                    owner: None,
+                    to_address: Some("receiver".to_string()),
+                    ibc_forward: None,
+                    deadline: None,
+                },
+                sv::ExecMsg::SwapExactAmountOut {
+                    token_out: Coin::new(1000, "ubbb"),
+                    token_in_max_amount: Uint128::new(1010),
+                    max_execution_price: None,
+                    affiliate: None,
+                    owner: None,
+                    to_address: Some("receiver".to_string()),
+                    ibc_forward: None,
+                    deadline: None,
+                },
+            ),
+        ];
+        for (composable_msg, contract_msg) in exec_msgs {
+            assert_eq!(
+                from_json::<sv::ExecMsg>(to_json_binary(&composable_msg).unwrap()).unwrap(),
+                contract_msg
+            );
+        }
+
+        let query_msgs = vec![
+            (
+                composable::QueryMsg::GetShareDenom {},
+                sv::QueryMsg::GetShareDenom {},
+            ),
+            (
+                composable::QueryMsg::GetTotalShares {},
+                sv::QueryMsg::GetTotalShares {},
+            ),
+            (
+                composable::QueryMsg::GetTotalPoolLiquidity {},
+                sv::QueryMsg::GetTotalPoolLiquidity {},
+            ),
+            (
+                composable::QueryMsg::SpotPrice {
+                    base_asset_denom: "uaaa".to_string(),
+                    quote_asset_denom: "ubbb".to_string(),
+                    encoding: Some(ResponseEncoding::Protobuf),
+                },
+                sv::QueryMsg::SpotPrice {
+                    base_asset_denom: "uaaa".to_string(),
+                    quote_asset_denom: "ubbb".to_string(),
+                    encoding: Some(ResponseEncoding::Protobuf),
+                },
+            ),
+        ];
+        for (composable_msg, contract_msg) in query_msgs {
+            assert_eq!(
+                from_json::<sv::QueryMsg>(to_json_binary(&composable_msg).unwrap()).unwrap(),
+                contract_msg
+            );
+        }
+    }
+
+    #[cfg(feature = "contract")]
+    #[test]
+    fn test_limiter_rejection() {
+        use crate::ContractError;
+
+        let err = ContractError::UpperLimitExceeded {
+            denom: "uaaa".to_string(),
+            upper_limit: Decimal::percent(60),
+            value: Decimal::percent(75),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Upper limit exceeded for `uaaa`, upper limit is 0.6, but the resulted weight is 0.75; \
+             {\"limiter_rejection\":{\"denom\":\"uaaa\",\"limit\":\"0.6\",\"value\":\"0.75\"}}"
+        );
+
+        let rejection = LimiterRejection {
+            denom: "uaaa".to_string(),
+            limit: Decimal::percent(60),
+            value: Decimal::percent(75),
+        };
+        assert_eq!(
+            LimiterRejection::from_error(&err.to_string()),
+            Some(rejection.clone())
+        );
+
+        // error wrapped by the chain on its way to the caller
+        assert_eq!(
+            LimiterRejection::from_error(&format!(
+                "dispatch: submessages: {err}: execute wasm contract failed"
+            )),
+            Some(rejection)
+        );
+
+        assert_eq!(
+            LimiterRejection::from_error(&ContractError::ZeroValueOperation {}.to_string()),
+            None
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use cosmwasm_std::{ensure, Addr, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+/// Allowances of alloyed asset that operators can burn from owners' accounts
+/// when exiting pool or swapping alloyed asset on their behalf.
+pub struct Operators<'a> {
+    allowances: Map<'a, (&'a Addr, &'a Addr), Uint128>,
+}
+
+impl<'a> Operators<'a> {
+    pub const fn new(allowances_namespace: &'a str) -> Self {
+        Self {
+            allowances: Map::new(allowances_namespace),
+        }
+    }
+
+    /// Remaining allowance `owner` granted to `operator`, zero if never granted
+    pub fn allowance(
+        &self,
+        storage: &dyn Storage,
+        owner: &Addr,
+        operator: &Addr,
+    ) -> StdResult<Uint128> {
+        self.allowances
+            .may_load(storage, (owner, operator))
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Set allowance `owner` grants to `operator`, zero allowance revokes the operator
+    pub fn set_allowance(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        operator: &Addr,
+        allowance: Uint128,
+    ) -> StdResult<()> {
+        if allowance.is_zero() {
+            self.allowances.remove(storage, (owner, operator));
+            Ok(())
+        } else {
+            self.allowances.save(storage, (owner, operator), &allowance)
+        }
+    }
+
+    /// Rescale all allowances by `numerator / denominator`, rounding down
+    pub fn rescale_allowances(
+        &self,
+        storage: &mut dyn Storage,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> Result<(), ContractError> {
+        let allowances = self
+            .allowances
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((owner, operator), allowance) in allowances {
+            let allowance = allowance.checked_multiply_ratio(numerator, denominator)?;
+            self.set_allowance(storage, &owner, &operator, allowance)?;
+        }
+
+        Ok(())
+    }
+
+    /// Operators `owner` has granted allowance to, with their remaining allowances
+    pub fn allowances_of(
+        &self,
+        storage: &dyn Storage,
+        owner: &Addr,
+    ) -> StdResult<Vec<(Addr, Uint128)>> {
+        self.allowances
+            .prefix(owner)
+            .range(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Deduct `amount` from allowance `owner` granted to `operator`, returns the remaining allowance
+    pub fn spend_allowance(
+        &self,
+        storage: &mut dyn Storage,
+        owner: &Addr,
+        operator: &Addr,
+        amount: Uint128,
+    ) -> Result<Uint128, ContractError> {
+        let available = self.allowance(storage, owner, operator)?;
+
+        ensure!(
+            available >= amount,
+            ContractError::InsufficientAllowance {
+                owner: owner.clone(),
+                operator: operator.clone(),
+                required: amount,
+                available,
+            }
+        );
+
+        let remaining = available - amount;
+        self.set_allowance(storage, owner, operator, remaining)?;
+
+        Ok(remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_spend_allowance() {
+        let mut deps = mock_dependencies();
+        let operators = Operators::new("operator_allowances");
+
+        let owner = Addr::unchecked("owner");
+        let operator = Addr::unchecked("operator");
+
+        assert_eq!(
+            operators
+                .allowance(&deps.storage, &owner, &operator)
+                .unwrap(),
+            Uint128::zero()
+        );
+
+        operators
+            .set_allowance(&mut deps.storage, &owner, &operator, 1000u128.into())
+            .unwrap();
+
+        // allowance is directional
+        assert_eq!(
+            operators
+                .allowance(&deps.storage, &operator, &owner)
+                .unwrap(),
+            Uint128::zero()
+        );
+
+        operators
+            .spend_allowance(&mut deps.storage, &owner, &operator, 400u128.into())
+            .unwrap();
+        assert_eq!(
+            operators
+                .allowance(&deps.storage, &owner, &operator)
+                .unwrap(),
+            Uint128::new(600)
+        );
+
+        assert_eq!(
+            operators
+                .spend_allowance(&mut deps.storage, &owner, &operator, 601u128.into())
+                .unwrap_err(),
+            ContractError::InsufficientAllowance {
+                owner: owner.clone(),
+                operator: operator.clone(),
+                required: 601u128.into(),
+                available: 600u128.into(),
+            }
+        );
+
+        operators
+            .spend_allowance(&mut deps.storage, &owner, &operator, 600u128.into())
+            .unwrap();
+        assert_eq!(
+            operators
+                .allowance(&deps.storage, &owner, &operator)
+                .unwrap(),
+            Uint128::zero()
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Uint128};
+
+use crate::role::granted::GrantedRole;
+
+/// Key of a parameter in the parameter registry
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum ParamKey {
+    SwapFeeRate,
+    MaxAffiliateShareBps,
+    AlloyedSupplyCap,
+    CircuitBreakerMaxOutflow,
+    PermissionedSwap,
+    PermissionedJoinAndExit,
+    DetailedUnauthorizedErrors,
+    StateExport,
+    AccountActivity,
+    StateDiffEvents,
+    AnonymizedEvents,
+}
+
+impl ParamKey {
+    pub fn as_str(&self) -> &str {
+        match self {
+            ParamKey::SwapFeeRate => "swap_fee_rate",
+            ParamKey::MaxAffiliateShareBps => "max_affiliate_share_bps",
+            ParamKey::AlloyedSupplyCap => "alloyed_supply_cap",
+            ParamKey::CircuitBreakerMaxOutflow => "circuit_breaker_max_outflow",
+            ParamKey::PermissionedSwap => "permissioned_swap",
+            ParamKey::PermissionedJoinAndExit => "permissioned_join_and_exit",
+            ParamKey::DetailedUnauthorizedErrors => "detailed_unauthorized_errors",
+            ParamKey::StateExport => "state_export",
+            ParamKey::AccountActivity => "account_activity",
+            ParamKey::StateDiffEvents => "state_diff_events",
+            ParamKey::AnonymizedEvents => "anonymized_events",
+        }
+    }
+
+    /// Role that can set the parameter alongside admin, `None` if only admin can
+    pub fn granted_role(&self) -> Option<GrantedRole> {
+        match self {
+            ParamKey::SwapFeeRate | ParamKey::MaxAffiliateShareBps => Some(GrantedRole::FeeManager),
+            ParamKey::AlloyedSupplyCap | ParamKey::CircuitBreakerMaxOutflow => {
+                Some(GrantedRole::RiskManager)
+            }
+            ParamKey::PermissionedSwap
+            | ParamKey::PermissionedJoinAndExit
+            | ParamKey::DetailedUnauthorizedErrors
+            | ParamKey::StateExport
+            | ParamKey::AccountActivity
+            | ParamKey::StateDiffEvents
+            | ParamKey::AnonymizedEvents => None,
+        }
+    }
+
+    /// Whether the parameter has to be changed through the timelock while it is enabled
+    pub fn is_timelocked(&self) -> bool {
+        matches!(self, ParamKey::SwapFeeRate | ParamKey::MaxAffiliateShareBps)
+    }
+}
+
+/// Parameter in the parameter registry with its typed value.
+/// Values are validated by the module that owns the parameter when set.
+#[cw_serde]
+pub enum Param {
+    /// Base swap fee rate, must be less than 1
+    SwapFeeRate(Decimal),
+    /// Maximum share of swap fee that can be given to an affiliate, at most 10000
+    MaxAffiliateShareBps(u16),
+    /// Cap on total supply of alloyed asset, uncapped if not set
+    AlloyedSupplyCap(Option<Uint128>),
+    /// Fraction of liquidity that can flow out within a block before the pool is paused,
+    /// must be within (0, 1], disabled if not set
+    CircuitBreakerMaxOutflow(Option<Decimal>),
+    /// Only allow-listed traders can swap
+    PermissionedSwap(bool),
+    /// Only allow-listed traders can join and exit pool
+    PermissionedJoinAndExit(bool),
+    /// Unauthorized execute messages fail with the attempted action and the roles authorized
+    /// for it, instead of a bare unauthorized error
+    DetailedUnauthorizedErrors(bool),
+    /// Raw contract state can be exported page by page with `export_state` query
+    StateExport(bool),
+    /// Lifetime join, exit and swap aggregates are recorded per account
+    AccountActivity(bool),
+    /// Every execute and sudo message that changes the pool emits a state diff event
+    StateDiffEvents(bool),
+    /// Events and attributes of every execute and sudo message leave out the addresses
+    /// of the accounts involved, e.g. `sender` and `recipient`, keeping only amounts
+    AnonymizedEvents(bool),
+}
+
+impl Param {
+    pub fn key(&self) -> ParamKey {
+        match self {
+            Param::SwapFeeRate(_) => ParamKey::SwapFeeRate,
+            Param::MaxAffiliateShareBps(_) => ParamKey::MaxAffiliateShareBps,
+            Param::AlloyedSupplyCap(_) => ParamKey::AlloyedSupplyCap,
+            Param::CircuitBreakerMaxOutflow(_) => ParamKey::CircuitBreakerMaxOutflow,
+            Param::PermissionedSwap(_) => ParamKey::PermissionedSwap,
+            Param::PermissionedJoinAndExit(_) => ParamKey::PermissionedJoinAndExit,
+            Param::DetailedUnauthorizedErrors(_) => ParamKey::DetailedUnauthorizedErrors,
+            Param::StateExport(_) => ParamKey::StateExport,
+            Param::AccountActivity(_) => ParamKey::AccountActivity,
+            Param::StateDiffEvents(_) => ParamKey::StateDiffEvents,
+            Param::AnonymizedEvents(_) => ParamKey::AnonymizedEvents,
+        }
+    }
+
+    /// Value as event attribute, empty if unset
+    pub fn value_string(&self) -> String {
+        fn or_empty<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(T::to_string).unwrap_or_default()
+        }
+
+        match self {
+            Param::SwapFeeRate(rate) => rate.to_string(),
+            Param::MaxAffiliateShareBps(bps) => bps.to_string(),
+            Param::AlloyedSupplyCap(cap) => or_empty(cap),
+            Param::CircuitBreakerMaxOutflow(max_outflow) => or_empty(max_outflow),
+            Param::PermissionedSwap(enabled)
+            | Param::PermissionedJoinAndExit(enabled)
+            | Param::DetailedUnauthorizedErrors(enabled)
+            | Param::StateExport(enabled)
+            | Param::AccountActivity(enabled)
+            | Param::StateDiffEvents(enabled)
+            | Param::AnonymizedEvents(enabled) => enabled.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{from_json, to_json_binary};
+
+    #[test]
+    fn test_param_serde() {
+        let param = Param::AlloyedSupplyCap(Some(Uint128::new(1000)));
+        assert_eq!(
+            to_json_binary(&param).unwrap(),
+            br#"{"alloyed_supply_cap":"1000"}"#.as_slice()
+        );
+        assert_eq!(param.key(), ParamKey::AlloyedSupplyCap);
+        assert_eq!(param.value_string(), "1000");
+
+        let param: Param = from_json(br#"{"circuit_breaker_max_outflow":null}"#).unwrap();
+        assert_eq!(param, Param::CircuitBreakerMaxOutflow(None));
+        assert_eq!(param.key().as_str(), "circuit_breaker_max_outflow");
+        assert_eq!(param.value_string(), "");
+
+        let key: ParamKey = from_json(br#""swap_fee_rate""#).unwrap();
+        assert_eq!(key, ParamKey::SwapFeeRate);
+        assert!(key.is_timelocked());
+        assert_eq!(key.granted_role(), Some(GrantedRole::FeeManager));
+    }
+}
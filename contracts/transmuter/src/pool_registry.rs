@@ -0,0 +1,93 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, StdResult, Storage, WasmMsg};
+use cw_storage_plus::Item;
+
+/// Message the registered pool registry contract receives whenever the denoms of the pool change,
+/// keyed by the calling pool contract, so that it can maintain a denom -> pools reverse index
+#[cw_serde]
+pub enum PoolRegistryMsg {
+    RegisterPool {
+        /// Every current pool asset denom, replacing the previously registered ones
+        pool_asset_denoms: Vec<String>,
+        alloyed_denom: String,
+    },
+}
+
+/// Optional registry contract that gets notified whenever the denoms of the pool change,
+/// at instantiation and on asset changes, so that pools containing a denom can be looked up on-chain.
+pub struct PoolRegistry<'a> {
+    contract: Item<'a, Addr>,
+}
+
+impl<'a> PoolRegistry<'a> {
+    pub const fn new(contract_namespace: &'a str) -> Self {
+        Self {
+            contract: Item::new(contract_namespace),
+        }
+    }
+
+    /// Registered registry contract, `None` if there is none
+    pub fn contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.contract.may_load(storage)
+    }
+
+    /// Set registry contract, `None` to unset
+    pub fn set_contract(&self, storage: &mut dyn Storage, contract: Option<Addr>) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.contract.save(storage, &contract),
+            None => {
+                self.contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Message to call the registry contract with `msg`, `None` if there is no registry contract
+    pub fn call(&self, storage: &dyn Storage, msg: &PoolRegistryMsg) -> StdResult<Option<WasmMsg>> {
+        self.contract(storage)?
+            .map(|contract| {
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract.into_string(),
+                    msg: to_json_binary(msg)?,
+                    funds: vec![],
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_call() {
+        let mut deps = mock_dependencies();
+        let pool_registry = PoolRegistry::new("pool_registry");
+
+        let msg = PoolRegistryMsg::RegisterPool {
+            pool_asset_denoms: vec!["denoma".to_string(), "denomb".to_string()],
+            alloyed_denom: "alloyed".to_string(),
+        };
+
+        // no registry set
+        assert_eq!(pool_registry.call(&deps.storage, &msg).unwrap(), None);
+
+        pool_registry
+            .set_contract(&mut deps.storage, Some(Addr::unchecked("registry")))
+            .unwrap();
+        assert_eq!(
+            pool_registry.call(&deps.storage, &msg).unwrap(),
+            Some(WasmMsg::Execute {
+                contract_addr: "registry".to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        pool_registry.set_contract(&mut deps.storage, None).unwrap();
+        assert_eq!(pool_registry.contract(&deps.storage).unwrap(), None);
+        assert_eq!(pool_registry.call(&deps.storage, &msg).unwrap(), None);
+    }
+}
@@ -0,0 +1,217 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Coin, Uint128};
+
+use crate::{
+    asset::{convert_amount, Rounding},
+    transmuter_pool::TransmuterPool,
+    ContractError,
+};
+
+/// How tokens out are chosen when redeeming exact amount of alloyed asset
+#[cw_serde]
+#[derive(Default)]
+pub enum RedemptionStrategy {
+    /// All in `denom`
+    SingleDenom { denom: String },
+
+    /// Pro-rata to the value of each pool asset, pool weights stay the same
+    #[default]
+    Proportional,
+
+    /// From pool assets with the highest value first, leveling them down
+    /// so that the pool gets closer to balance
+    MostImbalancedFirst,
+}
+
+impl RedemptionStrategy {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RedemptionStrategy::SingleDenom { .. } => "single_denom",
+            RedemptionStrategy::Proportional => "proportional",
+            RedemptionStrategy::MostImbalancedFirst => "most_imbalanced_first",
+        }
+    }
+
+    /// Tokens out from `pool` worth at most `alloyed_amount` of alloyed asset.
+    /// Each token out is rounded down, so the alloyed amount actually required
+    /// for them may be slightly less than `alloyed_amount`. Zero amounts are excluded.
+    pub fn tokens_out(
+        &self,
+        pool: &TransmuterPool,
+        alloyed_amount: Uint128,
+        alloyed_normalization_factor: Uint128,
+    ) -> Result<Vec<Coin>, ContractError> {
+        // value of each pool asset in alloyed asset amount
+        let values = pool
+            .pool_assets
+            .iter()
+            .map(|asset| {
+                convert_amount(
+                    asset.amount(),
+                    asset.normalization_factor(),
+                    alloyed_normalization_factor,
+                    &Rounding::Down,
+                )
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+
+        let total_value = values
+            .iter()
+            .try_fold(Uint128::zero(), |acc, value| acc.checked_add(*value))?;
+
+        let value_outs = match self {
+            RedemptionStrategy::SingleDenom { denom } => {
+                pool.get_pool_asset_by_denom(denom)?;
+
+                pool.pool_assets
+                    .iter()
+                    .map(|asset| {
+                        if asset.denom() == denom {
+                            alloyed_amount
+                        } else {
+                            Uint128::zero()
+                        }
+                    })
+                    .collect()
+            }
+            RedemptionStrategy::Proportional => {
+                ensure_within_pool_value(alloyed_amount, total_value)?;
+
+                values
+                    .iter()
+                    .map(|value| value.multiply_ratio(alloyed_amount, total_value))
+                    .collect()
+            }
+            RedemptionStrategy::MostImbalancedFirst => {
+                ensure_within_pool_value(alloyed_amount, total_value)?;
+
+                let level = leveled_down_value(&values, alloyed_amount)?;
+                values
+                    .iter()
+                    .map(|value| value.saturating_sub(level))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        pool.pool_assets
+            .iter()
+            .zip(value_outs)
+            .map(|(asset, value_out)| {
+                let amount = convert_amount(
+                    value_out,
+                    alloyed_normalization_factor,
+                    asset.normalization_factor(),
+                    &Rounding::Down,
+                )?;
+
+                Ok(Coin::new(amount.u128(), asset.denom()))
+            })
+            .filter(|coin| !matches!(coin, Ok(coin) if coin.amount.is_zero()))
+            .collect()
+    }
+}
+
+fn ensure_within_pool_value(
+    alloyed_amount: Uint128,
+    total_value: Uint128,
+) -> Result<(), ContractError> {
+    ensure!(
+        alloyed_amount <= total_value,
+        ContractError::RedemptionExceedsPoolValue {
+            required: alloyed_amount,
+            available: total_value,
+        }
+    );
+
+    Ok(())
+}
+
+/// Lowest value level such that taking everything above it from `values` does not exceed `amount`
+fn leveled_down_value(values: &[Uint128], amount: Uint128) -> Result<Uint128, ContractError> {
+    let taken_above = |level: Uint128| -> Result<Uint128, ContractError> {
+        values.iter().try_fold(Uint128::zero(), |acc, value| {
+            Ok(acc.checked_add(value.saturating_sub(level))?)
+        })
+    };
+
+    // `high` is always a valid level and `low` never is, unless it's zero
+    let mut low = Uint128::zero();
+    let mut high = values.iter().max().copied().unwrap_or_default();
+
+    if taken_above(low)? <= amount {
+        return Ok(low);
+    }
+
+    while high - low > Uint128::one() {
+        let mid = low + (high - low) / Uint128::new(2);
+        if taken_above(mid)? <= amount {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    Ok(high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        RedemptionStrategy::SingleDenom { denom: "denomb".to_string() },
+        600,
+        Ok(vec![Coin::new(60000, "denomb")])
+    )]
+    #[case(
+        RedemptionStrategy::SingleDenom { denom: "denomc".to_string() },
+        600,
+        Err(ContractError::InvalidTransmuteDenom {
+            denom: "denomc".to_string(),
+            expected_denom: vec!["denoma".to_string(), "denomb".to_string()],
+        })
+    )]
+    #[case(
+        RedemptionStrategy::Proportional,
+        600,
+        Ok(vec![Coin::new(450, "denoma"), Coin::new(15000, "denomb")])
+    )]
+    #[case(
+        RedemptionStrategy::MostImbalancedFirst,
+        500,
+        Ok(vec![Coin::new(500, "denoma")])
+    )]
+    #[case(
+        RedemptionStrategy::MostImbalancedFirst,
+        1200,
+        Ok(vec![Coin::new(1100, "denoma"), Coin::new(10000, "denomb")])
+    )]
+    #[case(
+        RedemptionStrategy::MostImbalancedFirst,
+        2001,
+        Err(ContractError::RedemptionExceedsPoolValue {
+            required: 2001u128.into(),
+            available: 2000u128.into(),
+        })
+    )]
+    fn test_tokens_out(
+        #[case] strategy: RedemptionStrategy,
+        #[case] alloyed_amount: u128,
+        #[case] expected: Result<Vec<Coin>, ContractError>,
+    ) {
+        // denoma is worth 1500 and denomb is worth 500 alloyed asset
+        let pool = TransmuterPool::new(vec![
+            Asset::unchecked(1500u128.into(), "denoma", 1u128.into()),
+            Asset::unchecked(50000u128.into(), "denomb", 100u128.into()),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            strategy.tokens_out(&pool, alloyed_amount.into(), 1u128.into()),
+            expected
+        );
+    }
+}
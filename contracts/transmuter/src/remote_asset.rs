@@ -0,0 +1,255 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, to_json_binary, Addr, Order, StdResult, Storage, Timestamp, WasmMsg};
+use cw_storage_plus::{Item, Map};
+
+use crate::{asset::AssetConfig, ContractError};
+
+/// Message the registered interchain query contract receives to query a denom on its origin chain
+#[cw_serde]
+pub enum IcqMsg {
+    /// Query total supply and bank metadata of `denom` on the chain at the other end of
+    /// `connection_id`, then call back `{"finalize_remote_asset": {"query_id": .., ..}}`
+    QueryDenomSupply {
+        query_id: u64,
+        connection_id: String,
+        denom: String,
+    },
+}
+
+/// Pool asset waiting for its origin chain data before being added to the pool
+#[cw_serde]
+pub struct PendingRemoteAsset {
+    pub query_id: u64,
+    /// Pool asset to add, with the local denom, e.g. an ibc denom
+    pub asset_config: AssetConfig,
+    pub connection_id: String,
+    /// Denom of the asset on its origin chain
+    pub origin_denom: String,
+    pub requested_at: Timestamp,
+}
+
+/// Pool assets whose canonical supply and metadata live on another chain. They are validated
+/// via interchain query through the registered contract, which calls back with the result.
+pub struct RemoteAssets<'a> {
+    icq_contract: Item<'a, Addr>,
+    next_query_id: Item<'a, u64>,
+    pending: Map<'a, u64, PendingRemoteAsset>,
+}
+
+impl<'a> RemoteAssets<'a> {
+    pub const fn new(
+        icq_contract_namespace: &'a str,
+        next_query_id_namespace: &'a str,
+        pending_namespace: &'a str,
+    ) -> Self {
+        Self {
+            icq_contract: Item::new(icq_contract_namespace),
+            next_query_id: Item::new(next_query_id_namespace),
+            pending: Map::new(pending_namespace),
+        }
+    }
+
+    /// Registered interchain query contract, `None` if there is none
+    pub fn icq_contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.icq_contract.may_load(storage)
+    }
+
+    /// Register interchain query contract, `None` to unregister
+    pub fn set_icq_contract(
+        &self,
+        storage: &mut dyn Storage,
+        contract: Option<Addr>,
+    ) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.icq_contract.save(storage, &contract),
+            None => {
+                self.icq_contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Record `asset_config` as pending and return it with the message that queries
+    /// `origin_denom` through the interchain query contract
+    pub fn request(
+        &self,
+        storage: &mut dyn Storage,
+        asset_config: AssetConfig,
+        connection_id: String,
+        origin_denom: String,
+        now: Timestamp,
+    ) -> Result<(PendingRemoteAsset, WasmMsg), ContractError> {
+        let icq_contract = self
+            .icq_contract(storage)?
+            .ok_or(ContractError::IcqContractNotSet {})?;
+
+        for pending in self.list(storage)? {
+            ensure!(
+                pending.asset_config.denom != asset_config.denom,
+                ContractError::RemoteAssetAlreadyPending {
+                    denom: asset_config.denom
+                }
+            );
+        }
+
+        let query_id = self.next_query_id.may_load(storage)?.unwrap_or_default();
+        self.next_query_id.save(storage, &(query_id + 1))?;
+
+        let pending = PendingRemoteAsset {
+            query_id,
+            asset_config,
+            connection_id,
+            origin_denom,
+            requested_at: now,
+        };
+        self.pending.save(storage, query_id, &pending)?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: icq_contract.into_string(),
+            msg: to_json_binary(&IcqMsg::QueryDenomSupply {
+                query_id,
+                connection_id: pending.connection_id.clone(),
+                denom: pending.origin_denom.clone(),
+            })?,
+            funds: vec![],
+        };
+
+        Ok((pending, msg))
+    }
+
+    /// Remove and return pending remote asset of `query_id` for finalizing,
+    /// which only the interchain query contract can do
+    pub fn take_for_finalize(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        query_id: u64,
+    ) -> Result<PendingRemoteAsset, ContractError> {
+        ensure!(
+            self.icq_contract(storage)?.as_ref() == Some(sender),
+            ContractError::Unauthorized {}
+        );
+
+        self.remove(storage, query_id)
+    }
+
+    /// Remove and return pending remote asset of `query_id`
+    pub fn remove(
+        &self,
+        storage: &mut dyn Storage,
+        query_id: u64,
+    ) -> Result<PendingRemoteAsset, ContractError> {
+        let pending = self
+            .pending
+            .may_load(storage, query_id)?
+            .ok_or(ContractError::RemoteAssetNotFound { query_id })?;
+        self.pending.remove(storage, query_id);
+
+        Ok(pending)
+    }
+
+    /// All pending remote assets, in query id order
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<PendingRemoteAsset>> {
+        self.pending
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, pending)| pending))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_request_and_finalize() {
+        let mut deps = mock_dependencies();
+        let remote_assets = RemoteAssets::new("icq_contract", "next_query_id", "pending");
+        let icq = Addr::unchecked("icq");
+        let now = Timestamp::from_seconds(1000);
+
+        let request = |storage: &mut dyn Storage, denom: &str| {
+            remote_assets.request(
+                storage,
+                AssetConfig::from_denom_str(denom),
+                "connection-0".to_string(),
+                "uatom".to_string(),
+                now,
+            )
+        };
+
+        // no interchain query contract registered
+        assert_eq!(
+            request(&mut deps.storage, "ibc/atom").unwrap_err(),
+            ContractError::IcqContractNotSet {}
+        );
+
+        remote_assets
+            .set_icq_contract(&mut deps.storage, Some(icq.clone()))
+            .unwrap();
+
+        let (pending, msg) = request(&mut deps.storage, "ibc/atom").unwrap();
+        assert_eq!(
+            pending,
+            PendingRemoteAsset {
+                query_id: 0,
+                asset_config: AssetConfig::from_denom_str("ibc/atom"),
+                connection_id: "connection-0".to_string(),
+                origin_denom: "uatom".to_string(),
+                requested_at: now,
+            }
+        );
+        assert_eq!(
+            msg,
+            WasmMsg::Execute {
+                contract_addr: "icq".to_string(),
+                msg: to_json_binary(&IcqMsg::QueryDenomSupply {
+                    query_id: 0,
+                    connection_id: "connection-0".to_string(),
+                    denom: "uatom".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+        );
+
+        // same denom can't be pending twice
+        assert_eq!(
+            request(&mut deps.storage, "ibc/atom").unwrap_err(),
+            ContractError::RemoteAssetAlreadyPending {
+                denom: "ibc/atom".to_string()
+            }
+        );
+
+        let (other, _) = request(&mut deps.storage, "ibc/other").unwrap();
+        assert_eq!(other.query_id, 1);
+        assert_eq!(
+            remote_assets.list(&deps.storage).unwrap(),
+            vec![pending.clone(), other.clone()]
+        );
+
+        // only interchain query contract can finalize
+        assert_eq!(
+            remote_assets
+                .take_for_finalize(&mut deps.storage, &Addr::unchecked("someone"), 0)
+                .unwrap_err(),
+            ContractError::Unauthorized {}
+        );
+        assert_eq!(
+            remote_assets
+                .take_for_finalize(&mut deps.storage, &icq, 0)
+                .unwrap(),
+            pending
+        );
+        assert_eq!(
+            remote_assets
+                .take_for_finalize(&mut deps.storage, &icq, 0)
+                .unwrap_err(),
+            ContractError::RemoteAssetNotFound { query_id: 0 }
+        );
+
+        assert_eq!(remote_assets.remove(&mut deps.storage, 1).unwrap(), other);
+        assert_eq!(remote_assets.list(&deps.storage).unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,47 @@
+use cosmwasm_std::Binary;
+
+pub use crate::msg::ResponseEncoding;
+
+/// Encode `value`, a protobuf encoded message of `type_url`, as protobuf `Any`
+pub fn encode_any(type_url: &str, value: &[u8]) -> Binary {
+    let mut buf = vec![];
+
+    // field 1: type_url, length delimited
+    buf.push(0x0a);
+    encode_varint(type_url.len() as u64, &mut buf);
+    buf.extend_from_slice(type_url.as_bytes());
+
+    // field 2: value, length delimited
+    buf.push(0x12);
+    encode_varint(value.len() as u64, &mut buf);
+    buf.extend_from_slice(value);
+
+    buf.into()
+}
+
+fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_any() {
+        assert_eq!(
+            encode_any("/a.B", &[1, 2, 3]).to_vec(),
+            vec![0x0a, 4, b'/', b'a', b'.', b'B', 0x12, 3, 1, 2, 3]
+        );
+
+        // lengths over 127 bytes take multiple bytes
+        let value = vec![7u8; 300];
+        let encoded = encode_any("/a.B", &value).to_vec();
+        assert_eq!(encoded[6..9], [0x12, 0xac, 0x02]);
+        assert_eq!(encoded[9..], value[..]);
+    }
+}
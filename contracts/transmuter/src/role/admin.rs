@@ -1,6 +1,10 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Addr, Deps, DepsMut, StdError, Storage};
+use cosmwasm_std::{
+    ensure, Addr, Api, CanonicalAddr, Deps, DepsMut, HexBinary, StdError, StdResult, Storage,
+    Timestamp,
+};
 use cw_storage_plus::Item;
+use sha2::{Digest, Sha256};
 
 use crate::ContractError;
 
@@ -8,11 +12,37 @@ pub struct Admin<'a> {
     state: Item<'a, AdminState>,
 }
 
+/// Address of the chain's x/gov module account, which executes messages of passed proposals
+pub fn gov_module_address(api: &dyn Api) -> StdResult<Addr> {
+    // module account address is the first 20 bytes of sha256 of the module name
+    let canonical = CanonicalAddr::from(&Sha256::digest(b"gov")[..20]);
+    api.addr_humanize(&canonical)
+}
+
+fn challenge_hash(challenge: &str) -> HexBinary {
+    HexBinary::from(Sha256::digest(challenge.as_bytes()).as_slice())
+}
+
 /// State of the admin to be stored in the contract storage
 #[cw_serde]
 pub enum AdminState {
     Claimed(Addr),
-    Transferring { current: Addr, candidate: Addr },
+    Transferring {
+        current: Addr,
+        candidate: Addr,
+        /// Transfer can no longer be claimed from this time, never expires if not set
+        #[serde(default)]
+        expires_at: Option<Timestamp>,
+        /// sha256 of the challenge the candidate has to echo to claim, not required if not set
+        #[serde(default)]
+        challenge_hash: Option<HexBinary>,
+    },
+    /// Admin asked to renounce and has yet to confirm it
+    Renouncing {
+        current: Addr,
+    },
+    /// Admin has been permanently renounced, no one can act as admin anymore
+    Renounced,
 }
 
 impl<'a> Admin<'a> {
@@ -29,19 +59,27 @@ impl<'a> Admin<'a> {
             .map_err(Into::into)
     }
 
-    /// Get current admin address
+    /// Get current admin address, errors if admin has been renounced
     pub fn current(&self, deps: Deps) -> Result<Addr, ContractError> {
-        let admin = self
-            .state
-            .may_load(deps.storage)?
-            .ok_or(StdError::not_found("admin"))?;
+        self.get(deps)?.ok_or(ContractError::AdminRenounced {})
+    }
 
-        match admin {
-            AdminState::Claimed(address) => Ok(address),
-            AdminState::Transferring { current, .. } => Ok(current),
+    /// Get current admin address. Returns None if admin has been renounced.
+    pub fn get(&self, deps: Deps) -> Result<Option<Addr>, ContractError> {
+        match self.state(deps)? {
+            AdminState::Claimed(address) => Ok(Some(address)),
+            AdminState::Transferring { current, .. } | AdminState::Renouncing { current } => {
+                Ok(Some(current))
+            }
+            AdminState::Renounced => Ok(None),
         }
     }
 
+    /// Whether `address` is the current admin, never true once admin has been renounced
+    pub fn is_current(&self, deps: Deps, address: &Addr) -> Result<bool, ContractError> {
+        Ok(self.get(deps)?.as_ref() == Some(address))
+    }
+
     /// Get candidate admin address. Returns None if there is no candidate.
     pub fn candidate(&self, deps: Deps) -> Result<Option<Addr>, ContractError> {
         let admin = self
@@ -50,54 +88,124 @@ impl<'a> Admin<'a> {
             .ok_or(StdError::not_found("admin"))?;
 
         match admin {
-            AdminState::Claimed(_) => Ok(None),
             AdminState::Transferring { candidate, .. } => Ok(Some(candidate)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Get candidate admin address and when the transfer expires, if it has not expired at `now`.
+    /// Returns None if there is no claimable transfer.
+    pub fn pending_transfer(
+        &self,
+        deps: Deps,
+        now: Timestamp,
+    ) -> Result<Option<(Addr, Option<Timestamp>)>, ContractError> {
+        match self.state(deps)? {
+            AdminState::Transferring {
+                candidate,
+                expires_at,
+                ..
+            } => Ok(expires_at
+                .map_or(true, |expires_at| now < expires_at)
+                .then_some((candidate, expires_at))),
+            _ => Ok(None),
         }
     }
 
-    /// Transfer admin rights to a new candidate
+    /// Whether claiming the pending transfer requires echoing a challenge
+    pub fn requires_challenge(&self, deps: Deps) -> Result<bool, ContractError> {
+        Ok(matches!(
+            self.state(deps)?,
+            AdminState::Transferring {
+                challenge_hash: Some(_),
+                ..
+            }
+        ))
+    }
+
+    /// Transfer admin rights to a new candidate, which can be claimed until `expires_at` if set.
+    /// If `challenge` is set, the candidate has to echo it to claim.
     pub fn transfer(
         &self,
         deps: DepsMut,
         sender: Addr,
         candidate: Addr,
+        expires_at: Option<Timestamp>,
+        challenge: Option<String>,
     ) -> Result<(), ContractError> {
         // Make sure that the sender is the current admin
-        let current_admin = self.current(deps.as_ref())?;
-        ensure!(sender == current_admin, ContractError::Unauthorized {});
+        ensure!(
+            self.is_current(deps.as_ref(), &sender)?,
+            ContractError::Unauthorized {}
+        );
 
         // Set the candidate admin address
         self.state
             .save(
                 deps.storage,
                 &AdminState::Transferring {
-                    current: current_admin,
+                    current: sender,
                     candidate,
+                    expires_at,
+                    challenge_hash: challenge.map(|challenge| challenge_hash(&challenge)),
                 },
             )
             .map_err(Into::into)
     }
 
-    /// Claim admin rights
-    pub fn claim(&self, deps: DepsMut, sender: Addr) -> Result<(), ContractError> {
-        // Make sure that the sender is the candidate
-        let candidate = self
-            .candidate(deps.as_ref())?
-            .ok_or(ContractError::Unauthorized {})?;
+    /// Claim admin rights, transfer must not have expired at `now`
+    /// and `challenge` must match the one set by the current admin, if any
+    pub fn claim(
+        &self,
+        deps: DepsMut,
+        sender: Addr,
+        now: Timestamp,
+        challenge: Option<String>,
+    ) -> Result<(), ContractError> {
+        let AdminState::Transferring {
+            candidate,
+            expires_at,
+            challenge_hash: expected_challenge_hash,
+            ..
+        } = self.state(deps.as_ref())?
+        else {
+            return Err(ContractError::Unauthorized {});
+        };
 
+        // Make sure that the sender is the candidate
         ensure!(candidate == sender, ContractError::Unauthorized {});
 
+        // Make sure that the transfer has not expired
+        if let Some(expires_at) = expires_at {
+            ensure!(
+                now < expires_at,
+                ContractError::AdminTransferExpired {
+                    expired_at: expires_at
+                }
+            );
+        }
+
+        // Make sure that the candidate echoes the challenge, so that it is claimed deliberately
+        if let Some(expected_challenge_hash) = expected_challenge_hash {
+            ensure!(
+                challenge.map(|challenge| challenge_hash(&challenge))
+                    == Some(expected_challenge_hash),
+                ContractError::AdminTransferChallengeMismatch {}
+            );
+        }
+
         // Set the current admin to the candidate
         self.state
             .save(deps.storage, &AdminState::Claimed(sender))
             .map_err(Into::into)
     }
 
-    /// Cancel admin transfer
+    /// Cancel admin transfer, or pending renouncement
     pub fn cancel_transfer(&self, deps: DepsMut, sender: Addr) -> Result<(), ContractError> {
         match self.state(deps.as_ref())? {
             AdminState::Claimed(_) => Err(ContractError::InoperableAdminTransferringState {}),
-            AdminState::Transferring { current, .. } => {
+            AdminState::Renounced => Err(ContractError::Unauthorized {}),
+            AdminState::Transferring { current, .. } | AdminState::Renouncing { current } => {
                 // Make sure that the sender is the current admin
                 ensure!(sender == current, ContractError::Unauthorized {});
 
@@ -112,8 +220,12 @@ impl<'a> Admin<'a> {
     /// Reject admin transfer
     pub fn reject_transfer(&self, deps: DepsMut, sender: Addr) -> Result<(), ContractError> {
         match self.state(deps.as_ref())? {
-            AdminState::Claimed(_) => Err(ContractError::InoperableAdminTransferringState {}),
-            AdminState::Transferring { current, candidate } => {
+            AdminState::Claimed(_) | AdminState::Renouncing { .. } | AdminState::Renounced => {
+                Err(ContractError::InoperableAdminTransferringState {})
+            }
+            AdminState::Transferring {
+                current, candidate, ..
+            } => {
                 // Make sure that the sender is the candidate
                 ensure!(candidate == sender, ContractError::Unauthorized {});
 
@@ -125,6 +237,59 @@ impl<'a> Admin<'a> {
         }
     }
 
+    /// Drop admin transfer that has expired at `now`, returning its candidate.
+    /// Returns None if there is no expired transfer.
+    pub fn expire_transfer(
+        &self,
+        deps: DepsMut,
+        now: Timestamp,
+    ) -> Result<Option<Addr>, ContractError> {
+        match self.state(deps.as_ref())? {
+            AdminState::Transferring {
+                current,
+                candidate,
+                expires_at: Some(expires_at),
+                ..
+            } if now >= expires_at => {
+                self.state
+                    .save(deps.storage, &AdminState::Claimed(current))?;
+
+                Ok(Some(candidate))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Ask to renounce admin rights, which only takes effect once confirmed.
+    /// Pending admin transfer, if any, is dropped.
+    pub fn renounce(&self, deps: DepsMut, sender: Addr) -> Result<(), ContractError> {
+        // Make sure that the sender is the current admin
+        ensure!(
+            self.is_current(deps.as_ref(), &sender)?,
+            ContractError::Unauthorized {}
+        );
+
+        self.state
+            .save(deps.storage, &AdminState::Renouncing { current: sender })
+            .map_err(Into::into)
+    }
+
+    /// Confirm pending renouncement, after which admin rights are gone forever
+    pub fn confirm_renounce(&self, deps: DepsMut, sender: Addr) -> Result<(), ContractError> {
+        match self.state(deps.as_ref())? {
+            AdminState::Renouncing { current } => {
+                // Make sure that the sender is the current admin
+                ensure!(sender == current, ContractError::Unauthorized {});
+
+                self.state
+                    .save(deps.storage, &AdminState::Renounced)
+                    .map_err(Into::into)
+            }
+            AdminState::Renounced => Err(ContractError::Unauthorized {}),
+            _ => Err(ContractError::InoperableAdminTransferringState {}),
+        }
+    }
+
     fn state(&self, deps: Deps) -> Result<AdminState, ContractError> {
         self.state
             .may_load(deps.storage)?
@@ -143,8 +308,7 @@ impl<'a> Admin<'a> {
 #[macro_export]
 macro_rules! ensure_admin_authority {
     ($sender:expr, $admin: expr, $deps:expr) => {
-        let current_admin = $admin.current($deps)?;
-        if ($sender != current_admin) {
+        if !$admin.is_current($deps, &$sender)? {
             return Err($crate::ContractError::Unauthorized {});
         }
     };
@@ -163,6 +327,7 @@ mod tests {
         let admin_addr = Addr::unchecked("admin");
         let random_addr = Addr::unchecked("random");
         let candidate_addr = Addr::unchecked("candidate");
+        let now = Timestamp::from_seconds(1000);
 
         // Initialize admin
         assert_eq!(
@@ -179,14 +344,22 @@ mod tests {
             admin.transfer(
                 deps.as_mut(),
                 candidate_addr.clone(),
-                candidate_addr.clone()
+                candidate_addr.clone(),
+                None,
+                None,
             ),
             Err(ContractError::Unauthorized {})
         );
 
         // Transfer admin rights
         assert_eq!(
-            admin.transfer(deps.as_mut(), admin_addr.clone(), candidate_addr.clone()),
+            admin.transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                None,
+                None,
+            ),
             Ok(())
         );
 
@@ -199,17 +372,20 @@ mod tests {
 
         // Claim admin rights with unauthorized sender
         assert_eq!(
-            admin.claim(deps.as_mut(), admin_addr.clone()),
+            admin.claim(deps.as_mut(), admin_addr.clone(), now, None),
             Err(ContractError::Unauthorized {})
         );
 
         assert_eq!(
-            admin.claim(deps.as_mut(), random_addr.clone()),
+            admin.claim(deps.as_mut(), random_addr.clone(), now, None),
             Err(ContractError::Unauthorized {})
         );
 
         // Claim admin rights
-        assert_eq!(admin.claim(deps.as_mut(), candidate_addr.clone()), Ok(()));
+        assert_eq!(
+            admin.claim(deps.as_mut(), candidate_addr.clone(), now, None),
+            Ok(())
+        );
 
         // New state
         assert_eq!(admin.current(deps.as_ref()), Ok(candidate_addr.clone()));
@@ -220,7 +396,13 @@ mod tests {
 
         // Transfer admin rights by new admin
         assert_eq!(
-            admin.transfer(deps.as_mut(), new_admin_addr.clone(), random_addr.clone()),
+            admin.transfer(
+                deps.as_mut(),
+                new_admin_addr.clone(),
+                random_addr.clone(),
+                None,
+                None,
+            ),
             Ok(())
         );
 
@@ -251,7 +433,13 @@ mod tests {
 
         // Transfer admin rights by new admin again
         assert_eq!(
-            admin.transfer(deps.as_mut(), new_admin_addr.clone(), random_addr.clone()),
+            admin.transfer(
+                deps.as_mut(),
+                new_admin_addr.clone(),
+                random_addr.clone(),
+                None,
+                None,
+            ),
             Ok(())
         );
 
@@ -277,4 +465,245 @@ mod tests {
             AdminState::Claimed(new_admin_addr.clone())
         );
     }
+
+    #[test]
+    fn test_admin_transfer_expiry() {
+        let mut deps = mock_dependencies();
+
+        let admin = Admin::new("admin");
+        let admin_addr = Addr::unchecked("admin");
+        let candidate_addr = Addr::unchecked("candidate");
+        let expires_at = Timestamp::from_seconds(1000);
+
+        admin
+            .init(deps.as_mut().storage, admin_addr.clone())
+            .unwrap();
+        assert_eq!(admin.pending_transfer(deps.as_ref(), expires_at), Ok(None));
+
+        admin
+            .transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                Some(expires_at),
+                None,
+            )
+            .unwrap();
+
+        // claimable until expiry
+        assert_eq!(
+            admin.pending_transfer(deps.as_ref(), expires_at.minus_seconds(1)),
+            Ok(Some((candidate_addr.clone(), Some(expires_at))))
+        );
+        assert_eq!(admin.pending_transfer(deps.as_ref(), expires_at), Ok(None));
+        assert_eq!(
+            admin.claim(deps.as_mut(), candidate_addr.clone(), expires_at, None),
+            Err(ContractError::AdminTransferExpired {
+                expired_at: expires_at
+            })
+        );
+        assert_eq!(admin.current(deps.as_ref()), Ok(admin_addr.clone()));
+
+        // expired transfer can still be cancelled, and re-offered
+        admin
+            .cancel_transfer(deps.as_mut(), admin_addr.clone())
+            .unwrap();
+        admin
+            .transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                Some(expires_at.plus_seconds(1000)),
+                None,
+            )
+            .unwrap();
+        admin
+            .claim(deps.as_mut(), candidate_addr.clone(), expires_at, None)
+            .unwrap();
+        assert_eq!(admin.current(deps.as_ref()), Ok(candidate_addr));
+    }
+
+    #[test]
+    fn test_expire_admin_transfer() {
+        let mut deps = mock_dependencies();
+
+        let admin = Admin::new("admin");
+        let admin_addr = Addr::unchecked("admin");
+        let candidate_addr = Addr::unchecked("candidate");
+        let expires_at = Timestamp::from_seconds(1000);
+
+        admin
+            .init(deps.as_mut().storage, admin_addr.clone())
+            .unwrap();
+        assert_eq!(admin.expire_transfer(deps.as_mut(), expires_at), Ok(None));
+
+        // transfer without expiry never expires
+        admin
+            .transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(admin.expire_transfer(deps.as_mut(), expires_at), Ok(None));
+        assert_eq!(
+            admin.candidate(deps.as_ref()),
+            Ok(Some(candidate_addr.clone()))
+        );
+
+        admin
+            .transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                Some(expires_at),
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            admin.expire_transfer(deps.as_mut(), expires_at.minus_seconds(1)),
+            Ok(None)
+        );
+        assert_eq!(
+            admin.candidate(deps.as_ref()),
+            Ok(Some(candidate_addr.clone()))
+        );
+
+        // expired transfer is dropped
+        assert_eq!(
+            admin.expire_transfer(deps.as_mut(), expires_at),
+            Ok(Some(candidate_addr))
+        );
+        assert_eq!(admin.candidate(deps.as_ref()), Ok(None));
+        assert_eq!(admin.get(deps.as_ref()), Ok(Some(admin_addr)));
+    }
+
+    #[test]
+    fn test_admin_transfer_challenge() {
+        let mut deps = mock_dependencies();
+
+        let admin = Admin::new("admin");
+        let admin_addr = Addr::unchecked("admin");
+        let candidate_addr = Addr::unchecked("candidate");
+        let now = Timestamp::from_seconds(1000);
+
+        admin
+            .init(deps.as_mut().storage, admin_addr.clone())
+            .unwrap();
+        assert_eq!(admin.requires_challenge(deps.as_ref()), Ok(false));
+
+        admin
+            .transfer(
+                deps.as_mut(),
+                admin_addr.clone(),
+                candidate_addr.clone(),
+                None,
+                Some("challenge".to_string()),
+            )
+            .unwrap();
+        assert_eq!(admin.requires_challenge(deps.as_ref()), Ok(true));
+
+        // challenge must be echoed exactly
+        assert_eq!(
+            admin.claim(deps.as_mut(), candidate_addr.clone(), now, None),
+            Err(ContractError::AdminTransferChallengeMismatch {})
+        );
+        assert_eq!(
+            admin.claim(
+                deps.as_mut(),
+                candidate_addr.clone(),
+                now,
+                Some("Challenge".to_string())
+            ),
+            Err(ContractError::AdminTransferChallengeMismatch {})
+        );
+        assert_eq!(admin.current(deps.as_ref()), Ok(admin_addr));
+
+        admin
+            .claim(
+                deps.as_mut(),
+                candidate_addr.clone(),
+                now,
+                Some("challenge".to_string()),
+            )
+            .unwrap();
+        assert_eq!(admin.current(deps.as_ref()), Ok(candidate_addr));
+        assert_eq!(admin.requires_challenge(deps.as_ref()), Ok(false));
+    }
+
+    #[test]
+    fn test_renounce() {
+        let mut deps = mock_dependencies();
+
+        let admin = Admin::new("admin");
+        let admin_addr = Addr::unchecked("admin");
+        let random_addr = Addr::unchecked("random");
+
+        admin
+            .init(deps.as_mut().storage, admin_addr.clone())
+            .unwrap();
+
+        // Only admin can renounce
+        assert_eq!(
+            admin.renounce(deps.as_mut(), random_addr.clone()),
+            Err(ContractError::Unauthorized {})
+        );
+
+        // Renouncement needs to be requested before confirmation
+        assert_eq!(
+            admin.confirm_renounce(deps.as_mut(), admin_addr.clone()),
+            Err(ContractError::InoperableAdminTransferringState {})
+        );
+
+        // Renouncement can be cancelled before confirmation
+        admin.renounce(deps.as_mut(), admin_addr.clone()).unwrap();
+        assert_eq!(admin.current(deps.as_ref()), Ok(admin_addr.clone()));
+        admin
+            .cancel_transfer(deps.as_mut(), admin_addr.clone())
+            .unwrap();
+        assert_eq!(
+            admin.state.load(&deps.storage).unwrap(),
+            AdminState::Claimed(admin_addr.clone())
+        );
+
+        admin.renounce(deps.as_mut(), admin_addr.clone()).unwrap();
+        assert_eq!(
+            admin.confirm_renounce(deps.as_mut(), random_addr.clone()),
+            Err(ContractError::Unauthorized {})
+        );
+        admin
+            .confirm_renounce(deps.as_mut(), admin_addr.clone())
+            .unwrap();
+
+        // No one is admin anymore
+        assert_eq!(admin.get(deps.as_ref()), Ok(None));
+        assert_eq!(
+            admin.current(deps.as_ref()),
+            Err(ContractError::AdminRenounced {})
+        );
+        assert_eq!(admin.is_current(deps.as_ref(), &admin_addr), Ok(false));
+        assert_eq!(
+            admin.transfer(deps.as_mut(), admin_addr.clone(), random_addr, None, None),
+            Err(ContractError::Unauthorized {})
+        );
+        assert_eq!(
+            admin.renounce(deps.as_mut(), admin_addr.clone()),
+            Err(ContractError::Unauthorized {})
+        );
+        assert_eq!(
+            admin.cancel_transfer(deps.as_mut(), admin_addr),
+            Err(ContractError::Unauthorized {})
+        );
+    }
+
+    #[test]
+    fn test_gov_module_address() {
+        let api = cosmwasm_std::testing::MockApi::default().with_prefix("osmo");
+        assert_eq!(
+            gov_module_address(&api).unwrap(),
+            Addr::unchecked("osmo10d07y265gmmuvt4z0w9aw880jnsr700jjeq4qp")
+        );
+    }
 }
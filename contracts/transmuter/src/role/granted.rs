@@ -0,0 +1,115 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::Map;
+
+/// Role that admin can grant to delegate part of the authority of admin or moderator,
+/// which stay authorized for the entrypoints they are already authorized for.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum GrantedRole {
+    /// Can manage limiters, circuit breaker, minimum swap amounts and alloyed supply cap, alongside admin
+    RiskManager,
+    /// Can only deactivate the pool, e.g. a monitoring bot that halts the pool on incident,
+    /// while re-activating it stays with moderator
+    Pauser,
+    /// Can manage swap fee, fee exemptions and affiliate share, alongside admin
+    FeeManager,
+    /// Can adjust limiters' boundary offset and upper limit, but only within bounds set by admin,
+    /// e.g. a risk management bot
+    LimiterTuner,
+}
+
+impl GrantedRole {
+    pub fn as_str(&self) -> &str {
+        match self {
+            GrantedRole::RiskManager => "risk_manager",
+            GrantedRole::Pauser => "pauser",
+            GrantedRole::FeeManager => "fee_manager",
+            GrantedRole::LimiterTuner => "limiter_tuner",
+        }
+    }
+}
+
+pub struct GrantedRoles<'a> {
+    roles: Map<'a, (&'a Addr, &'a str), GrantedRole>,
+}
+
+impl<'a> GrantedRoles<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            roles: Map::new(namespace),
+        }
+    }
+
+    pub fn has(&self, storage: &dyn Storage, address: &Addr, role: GrantedRole) -> bool {
+        self.roles.has(storage, (address, role.as_str()))
+    }
+
+    pub(crate) fn unchecked_grant(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        role: GrantedRole,
+    ) -> StdResult<()> {
+        self.roles.save(storage, (address, role.as_str()), &role)
+    }
+
+    pub(crate) fn unchecked_revoke(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        role: GrantedRole,
+    ) {
+        self.roles.remove(storage, (address, role.as_str()))
+    }
+
+    /// Revoke `role` from every address it is granted to
+    pub(crate) fn unchecked_revoke_all(
+        &self,
+        storage: &mut dyn Storage,
+        role: GrantedRole,
+    ) -> StdResult<()> {
+        for (address, _) in self
+            .list(storage)?
+            .into_iter()
+            .filter(|(_, granted_role)| *granted_role == role)
+        {
+            self.unchecked_revoke(storage, &address, role);
+        }
+
+        Ok(())
+    }
+
+    /// Roles granted to `address`
+    pub fn roles_of(&self, storage: &dyn Storage, address: &Addr) -> StdResult<Vec<GrantedRole>> {
+        self.roles
+            .prefix(address)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, role)| role))
+            .collect()
+    }
+
+    /// All granted roles with the address they are granted to
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<(Addr, GrantedRole)>> {
+        self.roles
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|((address, _), role)| (address, role)))
+            .collect()
+    }
+}
+
+/// Ensure that the sender is the current admin or has been granted the role
+///
+/// This macro is used to protect admin operations that can be delegated
+/// to addresses with a granted role.
+///
+/// If the `sender_address` is neither the current admin nor granted the role,
+/// the macro will return an `Err(ContractError::Unauthorized {})`.
+#[macro_export]
+macro_rules! ensure_role_authority {
+    ($sender:expr, $role:expr, $granted_role:expr, $deps:expr) => {
+        if !$role.has_authority($deps, &$sender, $granted_role)? {
+            return Err($crate::ContractError::Unauthorized {});
+        }
+    };
+}
@@ -1,23 +1,165 @@
-use cosmwasm_std::{Addr, DepsMut};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Deps, DepsMut};
 
 use crate::{ensure_admin_authority, ContractError};
 
 pub mod admin;
+pub mod granted;
 pub mod moderator;
 
+/// Role whose actions can be rate limited by admin, i.e. any role but admin
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum RateLimitedRole {
+    Moderator,
+    RiskManager,
+    Pauser,
+    FeeManager,
+    LimiterTuner,
+}
+
+impl RateLimitedRole {
+    pub const ALL: [RateLimitedRole; 5] = [
+        RateLimitedRole::Moderator,
+        RateLimitedRole::RiskManager,
+        RateLimitedRole::Pauser,
+        RateLimitedRole::FeeManager,
+        RateLimitedRole::LimiterTuner,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            RateLimitedRole::Moderator => "moderator",
+            RateLimitedRole::RiskManager => "risk_manager",
+            RateLimitedRole::Pauser => "pauser",
+            RateLimitedRole::FeeManager => "fee_manager",
+            RateLimitedRole::LimiterTuner => "limiter_tuner",
+        }
+    }
+}
+
+impl From<granted::GrantedRole> for RateLimitedRole {
+    fn from(role: granted::GrantedRole) -> Self {
+        match role {
+            granted::GrantedRole::RiskManager => RateLimitedRole::RiskManager,
+            granted::GrantedRole::Pauser => RateLimitedRole::Pauser,
+            granted::GrantedRole::FeeManager => RateLimitedRole::FeeManager,
+            granted::GrantedRole::LimiterTuner => RateLimitedRole::LimiterTuner,
+        }
+    }
+}
+
 pub struct Role<'a> {
     pub admin: admin::Admin<'a>,
     pub moderator: moderator::Moderator<'a>,
+    pub granted: granted::GrantedRoles<'a>,
 }
 
 impl<'a> Role<'a> {
-    pub const fn new(admin_namespace: &'a str, moderator_namespace: &'a str) -> Self {
+    pub const fn new(
+        admin_namespace: &'a str,
+        moderator_namespace: &'a str,
+        additional_moderators_namespace: &'a str,
+        granted_namespace: &'a str,
+    ) -> Self {
         Role {
             admin: admin::Admin::new(admin_namespace),
-            moderator: moderator::Moderator::new(moderator_namespace),
+            moderator: moderator::Moderator::new(
+                moderator_namespace,
+                additional_moderators_namespace,
+            ),
+            granted: granted::GrantedRoles::new(granted_namespace),
         }
     }
 
+    /// Only admin can grant role
+    pub fn grant_role(
+        &self,
+        sender: Addr,
+        deps: DepsMut,
+        address: Addr,
+        role: granted::GrantedRole,
+    ) -> Result<(), ContractError> {
+        // ensure that only admin can grant role
+        ensure_admin_authority!(sender, self.admin, deps.as_ref());
+
+        self.granted
+            .unchecked_grant(deps.storage, &address, role)
+            .map_err(Into::into)
+    }
+
+    /// Only admin can revoke role
+    pub fn revoke_role(
+        &self,
+        sender: Addr,
+        deps: DepsMut,
+        address: Addr,
+        role: granted::GrantedRole,
+    ) -> Result<(), ContractError> {
+        // ensure that only admin can revoke role
+        ensure_admin_authority!(sender, self.admin, deps.as_ref());
+
+        self.granted.unchecked_revoke(deps.storage, &address, role);
+
+        Ok(())
+    }
+
+    /// Whether `sender` is authorized for `role`, either by being granted it or by being the admin
+    pub fn has_authority(
+        &self,
+        deps: Deps,
+        sender: &Addr,
+        role: granted::GrantedRole,
+    ) -> Result<bool, ContractError> {
+        if self.granted.has(deps.storage, sender, role) {
+            return Ok(true);
+        }
+
+        self.admin.is_current(deps, sender)
+    }
+
+    /// Rate limited roles held by `address`, none if it is the admin since admin is never rate limited
+    pub fn rate_limited_roles_of(
+        &self,
+        deps: Deps,
+        address: &Addr,
+    ) -> Result<Vec<RateLimitedRole>, ContractError> {
+        if self.admin.is_current(deps, address)? {
+            return Ok(vec![]);
+        }
+
+        let mut roles = vec![];
+        if self.moderator.is_moderator(deps, address)? {
+            roles.push(RateLimitedRole::Moderator);
+        }
+        for role in self.granted.roles_of(deps.storage, address)? {
+            roles.push(role.into());
+        }
+
+        Ok(roles)
+    }
+
+    /// Confirm renouncing admin. Every granted role and moderator is removed as well,
+    /// so that no one can change anything admin appointed them for, pool status included.
+    pub fn confirm_renounce_admin(
+        &self,
+        sender: Addr,
+        mut deps: DepsMut,
+    ) -> Result<(), ContractError> {
+        self.admin.confirm_renounce(deps.branch(), sender)?;
+
+        for role in [
+            granted::GrantedRole::RiskManager,
+            granted::GrantedRole::Pauser,
+            granted::GrantedRole::FeeManager,
+            granted::GrantedRole::LimiterTuner,
+        ] {
+            self.granted.unchecked_revoke_all(deps.storage, role)?;
+        }
+
+        self.moderator.unchecked_clear(deps.storage)
+    }
+
     /// Only admin can assign moderator
     pub fn assign_moderator(
         &self,
@@ -30,6 +172,32 @@ impl<'a> Role<'a> {
 
         self.moderator.unchecked_set(deps, address)
     }
+
+    /// Only admin can add moderator
+    pub fn add_moderator(
+        &self,
+        sender: Addr,
+        deps: DepsMut,
+        address: Addr,
+    ) -> Result<(), ContractError> {
+        // ensure that only admin can add moderator
+        ensure_admin_authority!(sender, self.admin, deps.as_ref());
+
+        self.moderator.unchecked_add(deps, address)
+    }
+
+    /// Only admin can remove moderator
+    pub fn remove_moderator(
+        &self,
+        sender: Addr,
+        deps: DepsMut,
+        address: Addr,
+    ) -> Result<(), ContractError> {
+        // ensure that only admin can remove moderator
+        ensure_admin_authority!(sender, self.admin, deps.as_ref());
+
+        self.moderator.unchecked_remove(deps.storage, address)
+    }
 }
 
 #[cfg(test)]
@@ -45,7 +213,12 @@ mod tests {
         let moderator = Addr::unchecked("moderator");
         let non_admin = Addr::unchecked("non_admin");
 
-        let role = Role::new("admin", "moderator");
+        let role = Role::new(
+            "admin",
+            "moderator",
+            "additional_moderators",
+            "granted_roles",
+        );
 
         role.admin.init(&mut deps.storage, admin.clone()).unwrap();
 
@@ -62,4 +235,168 @@ mod tests {
 
         assert_eq!(err, ContractError::Unauthorized {});
     }
+
+    #[test]
+    fn test_add_remove_moderator() {
+        let mut deps = mock_dependencies();
+        let admin = Addr::unchecked("admin");
+        let moderator = Addr::unchecked("moderator");
+        let on_call = Addr::unchecked("on_call");
+
+        let role = Role::new(
+            "admin",
+            "moderator",
+            "additional_moderators",
+            "granted_roles",
+        );
+
+        role.admin.init(&mut deps.storage, admin.clone()).unwrap();
+        role.moderator
+            .init(&mut deps.storage, moderator.clone())
+            .unwrap();
+
+        // only admin can add moderator
+        let err = role
+            .add_moderator(moderator.clone(), deps.as_mut(), on_call.clone())
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        role.add_moderator(admin.clone(), deps.as_mut(), on_call.clone())
+            .unwrap();
+        assert!(role
+            .moderator
+            .is_moderator(deps.as_ref(), &on_call)
+            .unwrap());
+        assert!(role
+            .moderator
+            .is_moderator(deps.as_ref(), &moderator)
+            .unwrap());
+        assert_eq!(
+            role.moderator.list(deps.as_ref()).unwrap(),
+            vec![moderator.clone(), on_call.clone()]
+        );
+
+        let err = role
+            .add_moderator(admin.clone(), deps.as_mut(), moderator.clone())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ModeratorAlreadyExists {
+                address: moderator.clone()
+            }
+        );
+
+        // assigned moderator can only be replaced, not removed
+        let err = role
+            .remove_moderator(admin.clone(), deps.as_mut(), moderator.clone())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::ModeratorNotFound {
+                address: moderator.clone()
+            }
+        );
+
+        // assigning an additional moderator doesn't list it twice
+        role.assign_moderator(admin.clone(), deps.as_mut(), on_call.clone())
+            .unwrap();
+        assert_eq!(
+            role.moderator.list(deps.as_ref()).unwrap(),
+            vec![on_call.clone()]
+        );
+        assert!(!role
+            .moderator
+            .is_moderator(deps.as_ref(), &moderator)
+            .unwrap());
+
+        role.add_moderator(admin.clone(), deps.as_mut(), moderator.clone())
+            .unwrap();
+        role.remove_moderator(admin, deps.as_mut(), moderator.clone())
+            .unwrap();
+        assert_eq!(role.moderator.list(deps.as_ref()).unwrap(), vec![on_call]);
+    }
+
+    #[test]
+    fn test_grant_revoke_role() {
+        use granted::GrantedRole;
+
+        let mut deps = mock_dependencies();
+        let admin = Addr::unchecked("admin");
+        let risk_manager = Addr::unchecked("risk_manager");
+
+        let role = Role::new(
+            "admin",
+            "moderator",
+            "additional_moderators",
+            "granted_roles",
+        );
+
+        role.admin.init(&mut deps.storage, admin.clone()).unwrap();
+
+        // admin is authorized for every role
+        for granted_role in [
+            GrantedRole::RiskManager,
+            GrantedRole::Pauser,
+            GrantedRole::FeeManager,
+        ] {
+            assert!(role
+                .has_authority(deps.as_ref(), &admin, granted_role)
+                .unwrap());
+        }
+        assert!(!role
+            .has_authority(deps.as_ref(), &risk_manager, GrantedRole::RiskManager)
+            .unwrap());
+
+        // only admin can grant role
+        let err = role
+            .grant_role(
+                risk_manager.clone(),
+                deps.as_mut(),
+                risk_manager.clone(),
+                GrantedRole::RiskManager,
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        role.grant_role(
+            admin.clone(),
+            deps.as_mut(),
+            risk_manager.clone(),
+            GrantedRole::RiskManager,
+        )
+        .unwrap();
+        assert!(role
+            .has_authority(deps.as_ref(), &risk_manager, GrantedRole::RiskManager)
+            .unwrap());
+        assert!(!role
+            .has_authority(deps.as_ref(), &risk_manager, GrantedRole::FeeManager)
+            .unwrap());
+        assert_eq!(
+            role.granted.roles_of(&deps.storage, &risk_manager).unwrap(),
+            vec![GrantedRole::RiskManager]
+        );
+
+        // only admin can revoke role
+        let err = role
+            .revoke_role(
+                risk_manager.clone(),
+                deps.as_mut(),
+                risk_manager.clone(),
+                GrantedRole::RiskManager,
+            )
+            .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        role.revoke_role(
+            admin,
+            deps.as_mut(),
+            risk_manager.clone(),
+            GrantedRole::RiskManager,
+        )
+        .unwrap();
+        assert!(!role
+            .has_authority(deps.as_ref(), &risk_manager, GrantedRole::RiskManager)
+            .unwrap());
+        assert_eq!(role.granted.list(&deps.storage).unwrap(), vec![]);
+    }
 }
@@ -1,16 +1,23 @@
-use cosmwasm_std::{Addr, Deps, DepsMut, StdError, Storage};
-use cw_storage_plus::Item;
+use cosmwasm_std::{ensure, Addr, Deps, DepsMut, Empty, Order, StdError, Storage};
+use cw_storage_plus::{Item, Map};
 
 use crate::ContractError;
 
+/// Moderator set, consisting of the assigned moderator and any additional moderators,
+/// all of which have the same authority
 pub struct Moderator<'a> {
+    /// Assigned moderator
     moderator: Item<'a, Addr>,
+
+    /// Moderators added on top of the assigned one
+    additional: Map<'a, &'a Addr, Empty>,
 }
 
 impl<'a> Moderator<'a> {
-    pub const fn new(namespace: &'a str) -> Self {
+    pub const fn new(namespace: &'a str, additional_namespace: &'a str) -> Self {
         Self {
             moderator: Item::new(namespace),
+            additional: Map::new(additional_namespace),
         }
     }
 
@@ -18,6 +25,7 @@ impl<'a> Moderator<'a> {
         self.moderator.save(storage, &address).map_err(Into::into)
     }
 
+    /// Assigned moderator, fails if moderators have been cleared
     pub fn get(&self, deps: Deps) -> Result<Addr, ContractError> {
         self.moderator
             .may_load(deps.storage)?
@@ -25,25 +33,89 @@ impl<'a> Moderator<'a> {
             .map_err(Into::into)
     }
 
+    /// Whether `address` is in the moderator set
+    pub fn is_moderator(&self, deps: Deps, address: &Addr) -> Result<bool, ContractError> {
+        Ok(self.moderator.may_load(deps.storage)?.as_ref() == Some(address)
+            || self.additional.has(deps.storage, address))
+    }
+
+    /// All moderators, the assigned moderator first followed by additional moderators
+    pub fn list(&self, deps: Deps) -> Result<Vec<Addr>, ContractError> {
+        let mut moderators: Vec<Addr> = self.moderator.may_load(deps.storage)?.into_iter().collect();
+        for address in self
+            .additional
+            .keys(deps.storage, None, None, Order::Ascending)
+        {
+            moderators.push(address?);
+        }
+
+        Ok(moderators)
+    }
+
+    /// Replace the assigned moderator, additional moderators are kept
     pub(crate) fn unchecked_set(&self, deps: DepsMut, address: Addr) -> Result<(), ContractError> {
+        // avoid listing the same moderator twice
+        self.additional.remove(deps.storage, &address);
+
         self.moderator
             .save(deps.storage, &address)
             .map_err(Into::into)
     }
+
+    pub(crate) fn unchecked_add(&self, deps: DepsMut, address: Addr) -> Result<(), ContractError> {
+        ensure!(
+            !self.is_moderator(deps.as_ref(), &address)?,
+            ContractError::ModeratorAlreadyExists { address }
+        );
+
+        self.additional
+            .save(deps.storage, &address, &Empty {})
+            .map_err(Into::into)
+    }
+
+    /// Remove every moderator, the assigned one included, so that no one is a moderator anymore
+    pub(crate) fn unchecked_clear(&self, storage: &mut dyn Storage) -> Result<(), ContractError> {
+        let additional = self
+            .additional
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<Result<Vec<_>, _>>()?;
+        for address in additional {
+            self.additional.remove(storage, &address);
+        }
+
+        self.moderator.remove(storage);
+
+        Ok(())
+    }
+
+    /// Remove an additional moderator, the assigned moderator can only be replaced
+    pub(crate) fn unchecked_remove(
+        &self,
+        storage: &mut dyn Storage,
+        address: Addr,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            self.additional.has(storage, &address),
+            ContractError::ModeratorNotFound { address }
+        );
+
+        self.additional.remove(storage, &address);
+
+        Ok(())
+    }
 }
 
-/// Ensure that the sender is the current moderator
+/// Ensure that the sender is in the moderator set
 ///
-/// This macro ensures that the sender is the current moderator. It is used to protect
-/// sensitive operations that should only be performed by the moderator.
+/// This macro ensures that the sender is one of the current moderators. It is used to protect
+/// sensitive operations that should only be performed by a moderator.
 ///
-/// If the `sender_address` is not the current moderator, the macro will
+/// If the `sender_address` is not a current moderator, the macro will
 /// return an `Err(ContractError::Unauthorized {})`.
 #[macro_export]
 macro_rules! ensure_moderator_authority {
     ($sender:expr, $moderator: expr, $deps:expr) => {
-        let current_moderator = $moderator.get($deps)?;
-        if ($sender != current_moderator) {
+        if !$moderator.is_moderator($deps, &$sender)? {
             return Err($crate::ContractError::Unauthorized {});
         }
     };
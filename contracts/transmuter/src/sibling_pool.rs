@@ -0,0 +1,220 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    to_json_binary, Addr, Coin, Order, QuerierWrapper, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw_storage_plus::Map;
+
+use crate::ContractError;
+
+/// Subset of sibling transmuter's execute messages used for routing
+#[cw_serde]
+pub enum SiblingExecMsg {
+    SwapExactAmountIn {
+        token_out_denom: String,
+        token_out_min_amount: Uint128,
+        to_address: Option<String>,
+    },
+}
+
+/// Subset of sibling transmuter's query messages used for registration
+#[cw_serde]
+pub enum SiblingQueryMsg {
+    GetShareDenom {},
+}
+
+#[cw_serde]
+pub struct SiblingShareDenomResponse {
+    pub share_denom: String,
+}
+
+/// Transmuter pool whose alloyed asset can be reached from this pool
+#[cw_serde]
+pub struct SiblingPool {
+    /// Alloyed denom of the sibling pool
+    pub alloyed_denom: String,
+    pub contract_addr: Addr,
+    /// Denom swapped to in this pool and then into the sibling pool,
+    /// a pool asset of the sibling pool
+    pub via_denom: String,
+}
+
+/// Sibling transmuter pools by their alloyed denom, for routing swaps in two hops
+/// when token out is the alloyed asset of another pool instead of an asset of this one.
+pub struct SiblingPools<'a> {
+    pools: Map<'a, &'a str, SiblingPool>,
+}
+
+impl<'a> SiblingPools<'a> {
+    pub const fn new(pools_namespace: &'a str) -> Self {
+        Self {
+            pools: Map::new(pools_namespace),
+        }
+    }
+
+    /// Register `pool`, replacing the sibling pool of the same alloyed denom if any
+    pub fn register(&self, storage: &mut dyn Storage, pool: &SiblingPool) -> StdResult<()> {
+        self.pools.save(storage, &pool.alloyed_denom, pool)
+    }
+
+    /// Deregister sibling pool of `alloyed_denom`
+    pub fn deregister(
+        &self,
+        storage: &mut dyn Storage,
+        alloyed_denom: &str,
+    ) -> Result<SiblingPool, ContractError> {
+        let pool = self.get(storage, alloyed_denom)?;
+        self.pools.remove(storage, alloyed_denom);
+
+        Ok(pool)
+    }
+
+    /// Sibling pool of `alloyed_denom`
+    pub fn get(
+        &self,
+        storage: &dyn Storage,
+        alloyed_denom: &str,
+    ) -> Result<SiblingPool, ContractError> {
+        self.pools.may_load(storage, alloyed_denom)?.ok_or_else(|| {
+            ContractError::SiblingPoolNotFound {
+                denom: alloyed_denom.to_string(),
+            }
+        })
+    }
+
+    pub fn any_registered(&self, storage: &dyn Storage) -> bool {
+        self.pools
+            .keys(storage, None, None, Order::Ascending)
+            .next()
+            .is_some()
+    }
+
+    /// All sibling pools, in alloyed denom order
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<SiblingPool>> {
+        self.pools
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, pool)| pool))
+            .collect()
+    }
+}
+
+impl SiblingPool {
+    /// Sibling pool of transmuter `contract_addr` reached via `via_denom`,
+    /// with the alloyed denom the contract reports
+    pub fn query(
+        querier: QuerierWrapper,
+        contract_addr: Addr,
+        via_denom: String,
+    ) -> StdResult<Self> {
+        let SiblingShareDenomResponse { share_denom } =
+            querier.query_wasm_smart(&contract_addr, &SiblingQueryMsg::GetShareDenom {})?;
+
+        Ok(Self {
+            alloyed_denom: share_denom,
+            contract_addr,
+            via_denom,
+        })
+    }
+
+    /// Message that swaps `via` into the sibling pool's alloyed asset,
+    /// sent to `recipient` and reverting below `token_out_min_amount`
+    pub fn swap_msg(
+        &self,
+        via: Coin,
+        token_out_min_amount: Uint128,
+        recipient: &Addr,
+    ) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.contract_addr.to_string(),
+            msg: to_json_binary(&SiblingExecMsg::SwapExactAmountIn {
+                token_out_denom: self.alloyed_denom.clone(),
+                token_out_min_amount,
+                to_address: Some(recipient.to_string()),
+            })?,
+            funds: vec![via],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{testing::mock_dependencies, ContractResult, SystemResult, WasmQuery};
+
+    #[test]
+    fn test_query_register_and_deregister() {
+        let mut deps = mock_dependencies();
+        let sibling_pools = SiblingPools::new("sibling_pools");
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } => SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&SiblingShareDenomResponse {
+                    share_denom: format!("alloyed/{contract_addr}"),
+                })
+                .unwrap(),
+            )),
+            _ => unimplemented!(),
+        });
+
+        assert!(!sibling_pools.any_registered(&deps.storage));
+
+        let pool = SiblingPool::query(
+            QuerierWrapper::new(&deps.querier),
+            Addr::unchecked("sibling"),
+            "uusdc".to_string(),
+        )
+        .unwrap();
+        sibling_pools.register(&mut deps.storage, &pool).unwrap();
+
+        let expected_pool = SiblingPool {
+            alloyed_denom: "alloyed/sibling".to_string(),
+            contract_addr: Addr::unchecked("sibling"),
+            via_denom: "uusdc".to_string(),
+        };
+        assert_eq!(pool, expected_pool);
+        assert!(sibling_pools.any_registered(&deps.storage));
+        assert_eq!(
+            sibling_pools.get(&deps.storage, "alloyed/sibling").unwrap(),
+            expected_pool
+        );
+        assert_eq!(
+            sibling_pools.list(&deps.storage).unwrap(),
+            vec![expected_pool.clone()]
+        );
+
+        assert_eq!(
+            expected_pool
+                .swap_msg(
+                    Coin::new(1000, "uusdc"),
+                    Uint128::new(990),
+                    &Addr::unchecked("user")
+                )
+                .unwrap(),
+            WasmMsg::Execute {
+                contract_addr: "sibling".to_string(),
+                msg: to_json_binary(&SiblingExecMsg::SwapExactAmountIn {
+                    token_out_denom: "alloyed/sibling".to_string(),
+                    token_out_min_amount: Uint128::new(990),
+                    to_address: Some("user".to_string()),
+                })
+                .unwrap(),
+                funds: vec![Coin::new(1000, "uusdc")],
+            }
+        );
+
+        assert_eq!(
+            sibling_pools
+                .deregister(&mut deps.storage, "alloyed/sibling")
+                .unwrap(),
+            expected_pool
+        );
+        assert_eq!(
+            sibling_pools
+                .get(&deps.storage, "alloyed/sibling")
+                .unwrap_err(),
+            ContractError::SiblingPoolNotFound {
+                denom: "alloyed/sibling".to_string()
+            }
+        );
+        assert!(!sibling_pools.any_registered(&deps.storage));
+    }
+}
@@ -0,0 +1,123 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Binary, Order, Storage};
+
+/// Number of entries returned per page, if not specified
+pub const DEFAULT_STATE_EXPORT_PAGE_LIMIT: u32 = 30;
+
+/// Maximum number of entries returned per page
+pub const MAX_STATE_EXPORT_PAGE_LIMIT: u32 = 100;
+
+/// Raw key/value pair in contract storage
+#[cw_serde]
+pub struct StateEntry {
+    pub key: Binary,
+    pub value: Binary,
+}
+
+/// Up to `limit` raw entries of storage `namespace` after raw key `start_after`, in key order.
+///
+/// Entries of a map are stored under its length-prefixed namespace, while an item is stored
+/// under the namespace itself, so both are exported and map entries come first.
+pub fn export(
+    storage: &dyn Storage,
+    namespace: &str,
+    start_after: Option<&[u8]>,
+    limit: u32,
+) -> Vec<StateEntry> {
+    let limit = limit.min(MAX_STATE_EXPORT_PAGE_LIMIT) as usize;
+
+    let mut map_prefix = (namespace.len() as u16).to_be_bytes().to_vec();
+    map_prefix.extend_from_slice(namespace.as_bytes());
+
+    // range start is inclusive, so start right after `start_after`
+    let start = match start_after {
+        Some(start_after) => [start_after, &[0u8][..]].concat().max(map_prefix.clone()),
+        None => map_prefix.clone(),
+    };
+
+    let mut entries: Vec<StateEntry> = storage
+        .range(Some(&start), None, Order::Ascending)
+        .take_while(|(key, _)| key.starts_with(&map_prefix))
+        .take(limit)
+        .map(|(key, value)| StateEntry {
+            key: key.into(),
+            value: value.into(),
+        })
+        .collect();
+
+    let item_key = namespace.as_bytes();
+    let item_after_start = start_after.map_or(true, |start_after| item_key > start_after);
+    if entries.len() < limit && item_after_start {
+        if let Some(value) = storage.get(item_key) {
+            entries.push(StateEntry {
+                key: item_key.into(),
+                value: value.into(),
+            });
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{testing::mock_dependencies, to_json_vec};
+    use cw_storage_plus::{Item, Map};
+
+    #[test]
+    fn test_export() {
+        let mut deps = mock_dependencies();
+
+        let map: Map<&str, u64> = Map::new("limiters");
+        let item: Item<u64> = Item::new("limiters");
+        let other: Map<&str, u64> = Map::new("limiterz");
+
+        for (key, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            map.save(&mut deps.storage, key, &value).unwrap();
+            other.save(&mut deps.storage, key, &value).unwrap();
+        }
+        item.save(&mut deps.storage, &4).unwrap();
+
+        let map_entry = |key: &str, value: u64| StateEntry {
+            key: [b"\x00\x08limiters".as_slice(), key.as_bytes()]
+                .concat()
+                .into(),
+            value: to_json_vec(&value).unwrap().into(),
+        };
+        let item_entry = StateEntry {
+            key: b"limiters".to_vec().into(),
+            value: to_json_vec(&4u64).unwrap().into(),
+        };
+
+        // other namespaces are not exported
+        assert_eq!(
+            export(&deps.storage, "limiters", None, 10),
+            vec![
+                map_entry("a", 1),
+                map_entry("b", 2),
+                map_entry("c", 3),
+                item_entry.clone(),
+            ]
+        );
+
+        // paginated by raw key
+        let page = export(&deps.storage, "limiters", None, 2);
+        assert_eq!(page, vec![map_entry("a", 1), map_entry("b", 2)]);
+        assert_eq!(
+            export(&deps.storage, "limiters", Some(page[1].key.as_slice()), 2),
+            vec![map_entry("c", 3), item_entry.clone()]
+        );
+        assert_eq!(
+            export(
+                &deps.storage,
+                "limiters",
+                Some(item_entry.key.as_slice()),
+                2
+            ),
+            vec![]
+        );
+
+        assert_eq!(export(&deps.storage, "unknown", None, 10), vec![]);
+    }
+}
@@ -0,0 +1,118 @@
+use cosmwasm_std::{Addr, Event};
+
+use crate::contract::{authorized_actions, CONTRACT_VERSION};
+
+/// Event emitted on every execute and sudo message, as `wasm-transmuter`
+pub const SUBSCRIPTION_EVENT_TYPE: &str = "transmuter";
+
+/// Sudo messages only the chain, i.e. governance, can send that change the pool
+const GOVERNANCE_SUDO_METHODS: &[&str] = &[
+    "set_active",
+    "change_alloyed_normalization_factor",
+    "override_param",
+    "end_epoch",
+];
+
+const SWAP_METHODS: &[&str] = &[
+    "swap_exact_amount_in",
+    "swap_exact_amount_out",
+    "swap_via_sibling",
+    "batch_swap",
+    "commit_swap",
+    "reveal_swap",
+    "submit_drip_order",
+    "crank_drip_orders",
+    "cancel_drip_order",
+];
+
+const LIQUIDITY_METHODS: &[&str] = &[
+    "join_pool",
+    "join_pool_for",
+    "join_pool_exact_out",
+    "exit_pool",
+    "exit_pool_exact_in",
+    "execute_meta_tx",
+];
+
+const LIMITER_METHODS: &[&str] = &[
+    "register_limiter",
+    "deregister_limiter",
+    "set_change_limiter_boundary_offset",
+    "set_static_limiter_upper_limit",
+    "set_limiter_tuning_bounds",
+    "set_circuit_breaker",
+];
+
+/// Key of subscription event attribute `name`, prefixed with the contract's major version,
+/// e.g. `v3_action`. Keys only change with the major version, so that subscription filters
+/// like `wasm-transmuter.v3_category='swap'` keep working across minor upgrades and stop
+/// matching, instead of misreading, once the event shape changes.
+pub fn attribute_key(name: &str) -> String {
+    let major = CONTRACT_VERSION
+        .split('.')
+        .next()
+        .unwrap_or(CONTRACT_VERSION);
+    format!("v{major}_{name}")
+}
+
+/// Category of execute or sudo `method`: `swap`, `liquidity`, `limiter`, `admin` or `other`
+pub fn category(method: &str) -> &'static str {
+    if SWAP_METHODS.contains(&method) {
+        "swap"
+    } else if LIQUIDITY_METHODS.contains(&method) {
+        "liquidity"
+    } else if LIMITER_METHODS.contains(&method) {
+        "limiter"
+    } else if GOVERNANCE_SUDO_METHODS.contains(&method)
+        || !authorized_actions::roles_for(method).is_empty()
+    {
+        "admin"
+    } else {
+        "other"
+    }
+}
+
+/// Subscription event of `method`, with `sender` if it is an execute message
+pub fn subscription_event(method: &str, sender: Option<&Addr>) -> Event {
+    let event = Event::new(SUBSCRIPTION_EVENT_TYPE)
+        .add_attribute(attribute_key("action"), method)
+        .add_attribute(attribute_key("category"), category(method));
+
+    match sender {
+        Some(sender) => event.add_attribute(attribute_key("sender"), sender),
+        None => event,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription_event() {
+        assert_eq!(attribute_key("action"), "v3_action");
+
+        assert_eq!(category("swap_exact_amount_in"), "swap");
+        assert_eq!(category("crank_drip_orders"), "swap");
+        assert_eq!(category("exit_pool"), "liquidity");
+        assert_eq!(category("register_limiter"), "limiter");
+        assert_eq!(category("set_swap_fee"), "admin");
+        assert_eq!(category("claim_admin"), "admin");
+        assert_eq!(category("override_param"), "admin");
+        assert_eq!(category("cache_pool_asset_metadata"), "other");
+
+        assert_eq!(
+            subscription_event("join_pool", Some(&Addr::unchecked("user"))),
+            Event::new("transmuter")
+                .add_attribute("v3_action", "join_pool")
+                .add_attribute("v3_category", "liquidity")
+                .add_attribute("v3_sender", "user")
+        );
+        assert_eq!(
+            subscription_event("end_epoch", None),
+            Event::new("transmuter")
+                .add_attribute("v3_action", "end_epoch")
+                .add_attribute("v3_category", "admin")
+        );
+    }
+}
@@ -1,18 +1,19 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{ensure, Coin, Decimal, DepsMut, Env, Response, Uint128};
+use cosmwasm_std::{ensure, Coin, Decimal, DepsMut, Env, Response, Timestamp, Uint128};
 
 use crate::{
-    contract::Transmuter,
-    swap::{
-        BurnTarget, Entrypoint, SwapFromAlloyedConstraint, SwapToAlloyedConstraint, SwapVariant,
-    },
+    active_status::InactiveReason, contract::Transmuter, param::Param, swap::Entrypoint,
     ContractError,
 };
 
 #[cw_serde]
 pub enum SudoMsg {
+    /// Set active status of the pool. Deactivation can carry a machine-readable `reason`,
+    /// and `reactivate_at` after which the pool is active again without another sudo call.
     SetActive {
         is_active: bool,
+        reason: Option<InactiveReason>,
+        reactivate_at: Option<Timestamp>,
     },
     /// SwapExactAmountIn swaps an exact amount of tokens in for as many tokens out as possible.
     /// The amount of tokens out is determined by the current exchange rate and the swap fee.
@@ -36,6 +37,30 @@ pub enum SudoMsg {
         token_out: Coin,
         swap_fee: Decimal,
     },
+    /// Called by tokenfactory before each transfer of the alloyed asset,
+    /// rejects the transfer if it is restricted. See `set_alloyed_transfer_restriction_mode`.
+    BlockBeforeSend {
+        from: String,
+        to: String,
+        amount: Coin,
+    },
+    /// Called by tokenfactory before each transfer of the alloyed asset, nothing is tracked.
+    TrackBeforeSend {
+        from: String,
+        to: String,
+        amount: Coin,
+    },
+    /// Change alloyed asset normalization factor, which changes the exchange rate between
+    /// alloyed asset and the underlying assets. Pool must be inactive.
+    /// See `Transmuter::change_alloyed_normalization_factor`.
+    ChangeAlloyedNormalizationFactor { normalization_factor: Uint128 },
+    /// Override a parameter in the parameter registry directly, bypassing role authority
+    /// and timelock, so that chain governance can intervene even if the admin key is lost.
+    /// Can be sent while the pool is inactive. See `Transmuter::set_param`.
+    OverrideParam { param: Param },
+    /// Called by the chain at the end of each epoch of `identifier` to run maintenance.
+    /// Can be sent while the pool is inactive. See `Transmuter::end_epoch`.
+    EndEpoch { identifier: String },
 }
 
 impl SudoMsg {
@@ -45,9 +70,19 @@ impl SudoMsg {
         ctx: (DepsMut, Env),
     ) -> Result<Response, ContractError> {
         match self {
-            SudoMsg::SetActive { is_active } => {
-                let (deps, _env) = ctx;
-                transmuter.checked_set_active_status(deps.storage, is_active)?;
+            SudoMsg::SetActive {
+                is_active,
+                reason,
+                reactivate_at,
+            } => {
+                let (deps, env) = ctx;
+                transmuter.active_status.checked_set(
+                    deps.storage,
+                    env.block.time,
+                    is_active,
+                    reason,
+                    reactivate_at,
+                )?;
 
                 Ok(Response::new().add_attribute("method", "set_active"))
             }
@@ -64,47 +99,24 @@ impl SudoMsg {
                     ContractError::ZeroValueOperation {}
                 );
 
-                transmuter.ensure_valid_swap_fee(swap_fee)?;
-
                 let (deps, env) = ctx;
+                transmuter.ensure_valid_swap_fee(deps.storage, swap_fee)?;
                 let sender = deps.api.addr_validate(&sender)?;
 
-                let swap_variant =
-                    transmuter.swap_variant(&token_in.denom, &token_out_denom, deps.as_ref())?;
-
-                match swap_variant {
-                    SwapVariant::TokenToAlloyed => transmuter.swap_tokens_to_alloyed_asset(
+                transmuter
+                    .dispatch_swap_exact_amount_in(
                         Entrypoint::Sudo,
-                        SwapToAlloyedConstraint::ExactIn {
-                            tokens_in: &[token_in],
-                            token_out_min_amount,
-                        },
-                        sender,
-                        deps,
-                        env,
-                    ),
-                    SwapVariant::AlloyedToToken => transmuter.swap_alloyed_asset_to_tokens(
-                        Entrypoint::Sudo,
-                        SwapFromAlloyedConstraint::ExactIn {
-                            token_in_amount: token_in.amount,
-                            token_out_denom: &token_out_denom,
-                            token_out_min_amount,
-                        },
-                        BurnTarget::SentFunds,
-                        sender,
-                        deps,
-                        env,
-                    ),
-                    SwapVariant::TokenToToken => transmuter.swap_non_alloyed_exact_amount_in(
                         token_in,
-                        token_out_denom.as_str(),
+                        &token_out_denom,
                         token_out_min_amount,
+                        None,
+                        None,
+                        sender.clone(),
                         sender,
                         deps,
                         env,
-                    ),
-                }
-                .map(|res| res.add_attribute("method", "swap_exact_amount_in"))
+                    )
+                    .map(|res| res.add_attribute("method", "swap_exact_amount_in"))
             }
             SudoMsg::SwapExactAmountOut {
                 sender,
@@ -119,48 +131,63 @@ impl SudoMsg {
                     ContractError::ZeroValueOperation {}
                 );
 
-                transmuter.ensure_valid_swap_fee(swap_fee)?;
-
                 let (deps, env) = ctx;
+                transmuter.ensure_valid_swap_fee(deps.storage, swap_fee)?;
 
                 let sender = deps.api.addr_validate(&sender)?;
 
-                let swap_variant =
-                    transmuter.swap_variant(&token_in_denom, &token_out.denom, deps.as_ref())?;
-
-                match swap_variant {
-                    SwapVariant::TokenToAlloyed => transmuter.swap_tokens_to_alloyed_asset(
-                        Entrypoint::Sudo,
-                        SwapToAlloyedConstraint::ExactOut {
-                            token_in_denom: &token_in_denom,
-                            token_in_max_amount,
-                            token_out_amount: token_out.amount,
-                        },
-                        sender,
-                        deps,
-                        env,
-                    ),
-                    SwapVariant::AlloyedToToken => transmuter.swap_alloyed_asset_to_tokens(
+                transmuter
+                    .dispatch_swap_exact_amount_out(
                         Entrypoint::Sudo,
-                        SwapFromAlloyedConstraint::ExactOut {
-                            tokens_out: &[token_out],
-                            token_in_max_amount,
-                        },
-                        BurnTarget::SentFunds,
-                        sender,
-                        deps,
-                        env,
-                    ),
-                    SwapVariant::TokenToToken => transmuter.swap_non_alloyed_exact_amount_out(
-                        token_in_denom.as_str(),
+                        &token_in_denom,
                         token_in_max_amount,
                         token_out,
+                        None,
+                        None,
+                        sender.clone(),
                         sender,
                         deps,
                         env,
-                    ),
+                    )
+                    .map(|res| res.add_attribute("method", "swap_exact_amount_out"))
+            }
+            SudoMsg::BlockBeforeSend { from, to, amount } => {
+                let (deps, env) = ctx;
+
+                if amount.denom == transmuter.alloyed_asset.get_alloyed_denom(deps.storage)? {
+                    transmuter
+                        .alloyed_transfer_restriction
+                        .ensure_transfer_allowed(
+                            deps.storage,
+                            &deps.api.addr_validate(&from)?,
+                            &deps.api.addr_validate(&to)?,
+                            &env.contract.address,
+                        )?;
                 }
-                .map(|res| res.add_attribute("method", "swap_exact_amount_out"))
+
+                Ok(Response::new().add_attribute("method", "block_before_send"))
+            }
+            SudoMsg::TrackBeforeSend { .. } => {
+                Ok(Response::new().add_attribute("method", "track_before_send"))
+            }
+            SudoMsg::ChangeAlloyedNormalizationFactor {
+                normalization_factor,
+            } => {
+                let (deps, env) = ctx;
+
+                transmuter
+                    .change_alloyed_normalization_factor(deps, env, normalization_factor)
+                    .map(|res| res.add_attribute("method", "change_alloyed_normalization_factor"))
+            }
+            SudoMsg::OverrideParam { param } => {
+                let (deps, _env) = ctx;
+
+                transmuter.override_param(deps, param)
+            }
+            SudoMsg::EndEpoch { identifier } => {
+                let (deps, env) = ctx;
+
+                transmuter.end_epoch(deps, env, identifier)
             }
         }
     }
@@ -169,6 +196,7 @@ impl SudoMsg {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::subscription::subscription_event;
     use crate::{
         asset::AssetConfig,
         contract::sv::{ContractExecMsg, ExecMsg, InstantiateMsg},
@@ -177,12 +205,50 @@ mod tests {
     };
     use cosmwasm_std::{
         testing::{mock_dependencies, mock_env, mock_info, MOCK_CONTRACT_ADDR},
-        to_json_binary, BankMsg, Reply, SubMsgResponse, SubMsgResult,
+        to_json_binary, BankMsg, Event, Reply, SubMsgResponse, SubMsgResult,
     };
     use osmosis_std::types::osmosis::tokenfactory::v1beta1::{
         MsgBurn, MsgCreateDenomResponse, MsgMint,
     };
 
+    fn swap_event(
+        sender: &str,
+        token_in: &str,
+        token_out: &str,
+        fee: &str,
+        weights: String,
+    ) -> Event {
+        Event::new("transmuter-swap")
+            .add_attribute("event_version", "1")
+            .add_attribute("sender", sender)
+            .add_attribute("token_in", token_in)
+            .add_attribute("token_out", token_out)
+            .add_attribute("fee", fee)
+            .add_attribute("weights", weights)
+    }
+
+    fn balance_change_event(denom: &str, change: &str, balance: &str) -> Event {
+        Event::new("transmuter-balance_change")
+            .add_attribute("event_version", "1")
+            .add_attribute("denom", denom)
+            .add_attribute("change", change)
+            .add_attribute("balance", balance)
+    }
+
+    fn weight_change_event(
+        denom: &str,
+        old_weight: Decimal,
+        new_weight: Decimal,
+        operation: &str,
+    ) -> Event {
+        Event::new("transmuter-weight_change")
+            .add_attribute("event_version", "1")
+            .add_attribute("denom", denom)
+            .add_attribute("old_weight", old_weight.to_string())
+            .add_attribute("new_weight", new_weight.to_string())
+            .add_attribute("operation", operation)
+    }
+
     #[test]
     fn test_swap_exact_amount_in() {
         let mut deps = mock_dependencies();
@@ -204,6 +270,11 @@ mod tests {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
             moderator: "moderator".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         };
         let env = mock_env();
         let info = mock_info(admin, &[]);
@@ -232,7 +303,7 @@ mod tests {
         )
         .unwrap();
 
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool { deadline: None });
         execute(
             deps.as_mut(),
             env.clone(),
@@ -279,9 +350,36 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountInResponseData {
                     token_out_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500axlusdc",
+                "500whusdc",
+                "0axlusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                    Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128)
+                ),
+            ))
+            .add_event(balance_change_event("axlusdc", "500", "1000000000500"))
+            .add_event(balance_change_event("whusdc", "-500", "999999999500"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::percent(50),
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::percent(50),
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(subscription_event("swap_exact_amount_in", None));
 
         assert_eq!(res, expected);
 
@@ -312,9 +410,35 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountInResponseData {
                     token_out_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500uusdc",
+                "500whusdc",
+                "0uusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                    Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128)
+                ),
+            ))
+            .add_event(balance_change_event("whusdc", "-500", "999999999000"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(subscription_event("swap_exact_amount_in", None));
 
         assert_eq!(res, expected);
 
@@ -339,9 +463,35 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountInResponseData {
                     token_out_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500whusdc",
+                "500uusdc",
+                "0whusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                    Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128)
+                ),
+            ))
+            .add_event(balance_change_event("whusdc", "500", "999999999500"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128),
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_in",
+            ))
+            .add_event(subscription_event("swap_exact_amount_in", None));
 
         assert_eq!(res, expected);
 
@@ -424,6 +574,11 @@ mod tests {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.to_string()),
             moderator: "moderator".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         };
         let env = mock_env();
         let info = mock_info(admin, &[]);
@@ -452,7 +607,7 @@ mod tests {
         )
         .unwrap();
 
-        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool {});
+        let join_pool_msg = ContractExecMsg::Transmuter(ExecMsg::JoinPool { deadline: None });
         execute(
             deps.as_mut(),
             env.clone(),
@@ -499,9 +654,36 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountOutResponseData {
                     token_in_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500axlusdc",
+                "500whusdc",
+                "0axlusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                    Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128)
+                ),
+            ))
+            .add_event(balance_change_event("axlusdc", "500", "1000000000500"))
+            .add_event(balance_change_event("whusdc", "-500", "999999999500"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::percent(50),
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::percent(50),
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(subscription_event("swap_exact_amount_out", None));
 
         assert_eq!(res, expected);
 
@@ -533,9 +715,35 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountOutResponseData {
                     token_in_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500uusdc",
+                "500whusdc",
+                "0uusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                    Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128)
+                ),
+            ))
+            .add_event(balance_change_event("whusdc", "-500", "999999999000"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(subscription_event("swap_exact_amount_out", None));
 
         assert_eq!(res, expected);
 
@@ -560,9 +768,35 @@ mod tests {
             .set_data(
                 to_json_binary(&SwapExactAmountOutResponseData {
                     token_in_amount: Uint128::from(500u128),
+                    swap_fee_amount: Uint128::zero(),
                 })
                 .unwrap(),
-            );
+            )
+            .add_event(swap_event(
+                user,
+                "500whusdc",
+                "500uusdc",
+                "0whusdc",
+                format!(
+                    "axlusdc:{},whusdc:{}",
+                    Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                    Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128)
+                ),
+            ))
+            .add_event(balance_change_event("whusdc", "500", "999999999500"))
+            .add_event(weight_change_event(
+                "axlusdc",
+                Decimal::from_ratio(1_000_000_000_500u128, 1_999_999_999_500u128),
+                Decimal::from_ratio(1_000_000_000_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(weight_change_event(
+                "whusdc",
+                Decimal::from_ratio(999_999_999_000u128, 1_999_999_999_500u128),
+                Decimal::from_ratio(999_999_999_500u128, 2_000_000_000_000u128),
+                "swap_exact_amount_out",
+            ))
+            .add_event(subscription_event("swap_exact_amount_out", None));
 
         assert_eq!(res, expected);
 
@@ -0,0 +1,238 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::Item;
+
+use crate::ContractError;
+
+/// Cap that takes effect once block time reaches `time`
+#[cw_serde]
+pub struct SupplyCapStep {
+    pub time: Timestamp,
+    pub cap: Uint128,
+}
+
+/// Maximum total supply of alloyed asset, so that exposure to a newly rolled out
+/// alloyed asset can be capped and raised in stages.
+pub struct SupplyCap<'a> {
+    cap: Item<'a, Uint128>,
+    /// Steps sorted by time, the latest reached step overrides `cap`
+    schedule: Item<'a, Vec<SupplyCapStep>>,
+}
+
+impl<'a> SupplyCap<'a> {
+    pub const fn new(cap_namespace: &'a str, schedule_namespace: &'a str) -> Self {
+        Self {
+            cap: Item::new(cap_namespace),
+            schedule: Item::new(schedule_namespace),
+        }
+    }
+
+    /// Cap set without schedule, `None` if supply is uncapped
+    pub fn cap(&self, storage: &dyn Storage) -> StdResult<Option<Uint128>> {
+        self.cap.may_load(storage)
+    }
+
+    pub fn schedule(&self, storage: &dyn Storage) -> StdResult<Vec<SupplyCapStep>> {
+        self.schedule
+            .may_load(storage)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Set schedule of caps that take effect over time, steps must be in strictly increasing time.
+    /// Empty schedule removes it.
+    pub fn set_schedule(
+        &self,
+        storage: &mut dyn Storage,
+        schedule: Vec<SupplyCapStep>,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            schedule
+                .windows(2)
+                .all(|steps| steps[0].time < steps[1].time),
+            ContractError::InvalidSupplyCapSchedule {}
+        );
+
+        if schedule.is_empty() {
+            self.schedule.remove(storage);
+        } else {
+            self.schedule.save(storage, &schedule)?;
+        }
+
+        Ok(())
+    }
+
+    /// Cap in effect at `now`, which is the cap of the latest step reached by `now`,
+    /// or the cap set without schedule if no step has been reached yet
+    pub fn current_cap(&self, storage: &dyn Storage, now: Timestamp) -> StdResult<Option<Uint128>> {
+        let reached_step = self
+            .schedule(storage)?
+            .into_iter()
+            .take_while(|step| step.time <= now)
+            .last();
+
+        match reached_step {
+            Some(step) => Ok(Some(step.cap)),
+            None => self.cap(storage),
+        }
+    }
+
+    /// Set cap, `None` to uncap
+    pub fn set_cap(&self, storage: &mut dyn Storage, cap: Option<Uint128>) -> StdResult<()> {
+        match cap {
+            Some(cap) => self.cap.save(storage, &cap),
+            None => {
+                self.cap.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Rescale cap and all scheduled caps by `numerator / denominator`, rounding down
+    pub fn rescale(
+        &self,
+        storage: &mut dyn Storage,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> Result<(), ContractError> {
+        if let Some(cap) = self.cap(storage)? {
+            let cap = cap.checked_multiply_ratio(numerator, denominator)?;
+            self.set_cap(storage, Some(cap))?;
+        }
+
+        let schedule = self
+            .schedule(storage)?
+            .into_iter()
+            .map(|step| {
+                Ok(SupplyCapStep {
+                    cap: step.cap.checked_multiply_ratio(numerator, denominator)?,
+                    ..step
+                })
+            })
+            .collect::<Result<Vec<_>, ContractError>>()?;
+        self.set_schedule(storage, schedule)
+    }
+
+    /// Ensure minting `mint_amount` on top of `supply` does not exceed the cap in effect at `now`
+    pub fn ensure_can_mint(
+        &self,
+        storage: &dyn Storage,
+        now: Timestamp,
+        supply: Uint128,
+        mint_amount: Uint128,
+    ) -> Result<(), ContractError> {
+        if let Some(cap) = self.current_cap(storage, now)? {
+            let supply_after_mint = supply.checked_add(mint_amount)?;
+            ensure!(
+                supply_after_mint <= cap,
+                ContractError::AlloyedSupplyCapExceeded {
+                    cap,
+                    supply_after_mint
+                }
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_ensure_can_mint() {
+        let mut deps = mock_dependencies();
+        let supply_cap = SupplyCap::new("supply_cap", "supply_cap_schedule");
+        let now = Timestamp::from_seconds(1000);
+
+        // uncapped by default
+        assert_eq!(supply_cap.cap(&deps.storage).unwrap(), None);
+        supply_cap
+            .ensure_can_mint(
+                &deps.storage,
+                now,
+                Uint128::new(1000),
+                Uint128::MAX - Uint128::new(1000),
+            )
+            .unwrap();
+
+        supply_cap
+            .set_cap(&mut deps.storage, Some(Uint128::new(1000)))
+            .unwrap();
+        supply_cap
+            .ensure_can_mint(&deps.storage, now, Uint128::new(600), Uint128::new(400))
+            .unwrap();
+        assert_eq!(
+            supply_cap
+                .ensure_can_mint(&deps.storage, now, Uint128::new(600), Uint128::new(401))
+                .unwrap_err(),
+            ContractError::AlloyedSupplyCapExceeded {
+                cap: Uint128::new(1000),
+                supply_after_mint: Uint128::new(1001)
+            }
+        );
+
+        // supply already above the cap, e.g. after lowering the cap, blocks any further minting
+        supply_cap
+            .set_cap(&mut deps.storage, Some(Uint128::new(500)))
+            .unwrap();
+        supply_cap
+            .ensure_can_mint(&deps.storage, now, Uint128::new(600), Uint128::one())
+            .unwrap_err();
+
+        supply_cap.set_cap(&mut deps.storage, None).unwrap();
+        supply_cap
+            .ensure_can_mint(&deps.storage, now, Uint128::new(600), Uint128::new(401))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_current_cap() {
+        let mut deps = mock_dependencies();
+        let supply_cap = SupplyCap::new("supply_cap", "supply_cap_schedule");
+
+        supply_cap
+            .set_cap(&mut deps.storage, Some(Uint128::new(1000)))
+            .unwrap();
+
+        // steps must be in strictly increasing time
+        let step = |time: u64, cap: u128| SupplyCapStep {
+            time: Timestamp::from_seconds(time),
+            cap: Uint128::new(cap),
+        };
+        assert_eq!(
+            supply_cap
+                .set_schedule(&mut deps.storage, vec![step(200, 3000), step(200, 4000)])
+                .unwrap_err(),
+            ContractError::InvalidSupplyCapSchedule {}
+        );
+
+        supply_cap
+            .set_schedule(&mut deps.storage, vec![step(100, 2000), step(200, 3000)])
+            .unwrap();
+
+        let current_cap = |time: u64| {
+            supply_cap
+                .current_cap(&deps.storage, Timestamp::from_seconds(time))
+                .unwrap()
+        };
+        assert_eq!(current_cap(99), Some(Uint128::new(1000)));
+        assert_eq!(current_cap(100), Some(Uint128::new(2000)));
+        assert_eq!(current_cap(199), Some(Uint128::new(2000)));
+        assert_eq!(current_cap(200), Some(Uint128::new(3000)));
+        assert_eq!(
+            current_cap(u64::MAX / 1_000_000_000),
+            Some(Uint128::new(3000))
+        );
+
+        // schedule can ramp from uncapped
+        supply_cap.set_cap(&mut deps.storage, None).unwrap();
+        assert_eq!(current_cap(99), None);
+        assert_eq!(current_cap(100), Some(Uint128::new(2000)));
+
+        supply_cap.set_schedule(&mut deps.storage, vec![]).unwrap();
+        assert_eq!(supply_cap.schedule(&deps.storage).unwrap(), vec![]);
+        assert_eq!(current_cap(200), None);
+    }
+}
@@ -0,0 +1,98 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, StdResult, Storage, Uint128, WasmMsg};
+use cw_storage_plus::Item;
+
+/// Message the registered supply hook contract receives whenever alloyed asset is minted or burned
+#[cw_serde]
+pub enum SupplyHookMsg {
+    AlloyedSupplyChanged {
+        /// Address alloyed asset is minted to, `None` if burned
+        minter: Option<String>,
+        /// Address alloyed asset is burned from, `None` if minted
+        burner: Option<String>,
+        amount: Uint128,
+        /// Total supply of alloyed asset after the mint or burn
+        new_supply: Uint128,
+    },
+}
+
+/// Optional contract that gets notified whenever alloyed supply changes,
+/// so that staking or incentive programs can be keyed to alloyed supply.
+pub struct SupplyHook<'a> {
+    contract: Item<'a, Addr>,
+}
+
+impl<'a> SupplyHook<'a> {
+    pub const fn new(contract_namespace: &'a str) -> Self {
+        Self {
+            contract: Item::new(contract_namespace),
+        }
+    }
+
+    /// Registered hook contract, `None` if there is none
+    pub fn contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.contract.may_load(storage)
+    }
+
+    /// Register hook contract, `None` to unregister
+    pub fn set_contract(&self, storage: &mut dyn Storage, contract: Option<Addr>) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.contract.save(storage, &contract),
+            None => {
+                self.contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Message to call the hook contract with `msg`, `None` if there is no hook contract
+    pub fn call(&self, storage: &dyn Storage, msg: &SupplyHookMsg) -> StdResult<Option<WasmMsg>> {
+        self.contract(storage)?
+            .map(|contract| {
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract.into_string(),
+                    msg: to_json_binary(msg)?,
+                    funds: vec![],
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_call() {
+        let mut deps = mock_dependencies();
+        let supply_hook = SupplyHook::new("supply_hook");
+
+        let msg = SupplyHookMsg::AlloyedSupplyChanged {
+            minter: Some("minter".to_string()),
+            burner: None,
+            amount: Uint128::new(1000),
+            new_supply: Uint128::new(5000),
+        };
+
+        // no hook registered
+        assert_eq!(supply_hook.call(&deps.storage, &msg).unwrap(), None);
+
+        supply_hook
+            .set_contract(&mut deps.storage, Some(Addr::unchecked("hook")))
+            .unwrap();
+        assert_eq!(
+            supply_hook.call(&deps.storage, &msg).unwrap(),
+            Some(WasmMsg::Execute {
+                contract_addr: "hook".to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        supply_hook.set_contract(&mut deps.storage, None).unwrap();
+        assert_eq!(supply_hook.contract(&deps.storage).unwrap(), None);
+        assert_eq!(supply_hook.call(&deps.storage, &msg).unwrap(), None);
+    }
+}
@@ -1,22 +1,36 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt::Display};
 
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{
-    ensure, ensure_eq, to_json_binary, Addr, BankMsg, Coin, Decimal, Deps, DepsMut, Env, Response,
-    StdError, Storage, Uint128,
+    ensure, ensure_eq, from_json, to_json_binary, Addr, Api, BankMsg, Coin, Decimal, Deps, DepsMut,
+    Env, Event, IbcMsg, IbcTimeout, Response, StdError, StdResult, Storage, SubMsg, Timestamp,
+    Uint128,
 };
-use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
 use serde::Serialize;
 
 use crate::{
+    account_activity::ActivityKind,
+    active_status::InactiveReason,
     alloyed_asset::{swap_from_alloyed, swap_to_alloyed},
-    contract::Transmuter,
+    asset::{convert_amount, Rounding},
+    contract::{Transmuter, SUPPLY_HOOK_REPLY_ID, SWAP_HOOK_REPLY_ID},
+    debug,
+    events::{fee_accrual_events, fee_events, fee_rebate_event},
+    flow_quota::FlowQuotaMsg,
+    limiter::LimiterVerdict,
+    supply_hook::SupplyHookMsg,
+    swap_fee::{
+        fee_on_exact_in, token_in_amount_with_fee, Affiliate, FeeDestination, SwapFeeConfig,
+    },
+    swap_hook::SwapHookMsg,
     transmuter_pool::{AmountConstraint, TransmuterPool},
     ContractError,
 };
 
-/// Swap fee is hardcoded to zero intentionally.
-pub const SWAP_FEE: Decimal = Decimal::zero();
+pub use crate::msg::IbcForward;
+
+/// Type of the event emitted by every swap, join and exit pool
+pub const SWAP_EVENT_TYPE: &str = "transmuter-swap";
 
 impl Transmuter<'_> {
     /// Getting the [SwapVariant] of the swap operation
@@ -48,10 +62,721 @@ impl Transmuter<'_> {
         Ok(SwapVariant::TokenToToken)
     }
 
+    /// Swap exact amount of `token_in` for as many `token_out_denom` as possible,
+    /// routing to the appropriate swap implementation based on [SwapVariant].
+    /// Swap fee is deducted from `token_in` before swapping.
+    /// If `owner` is set, the sender is an operator swapping the owner's alloyed asset,
+    /// which is burned from the owner's account instead of the sent funds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dispatch_swap_exact_amount_in(
+        &self,
+        entrypoint: Entrypoint,
+        token_in: Coin,
+        token_out_denom: &str,
+        token_out_min_amount: Uint128,
+        affiliate: Option<Affiliate>,
+        owner: Option<Addr>,
+        sender: Addr,
+        recipient: Addr,
+        mut deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        self.trader_allow_list
+            .ensure_can_swap(deps.storage, &sender)?;
+        if let Some(owner) = &owner {
+            self.trader_allow_list
+                .ensure_can_swap(deps.storage, owner)?;
+        }
+
+        let swap_variant = self.swap_variant(&token_in.denom, token_out_denom, deps.as_ref())?;
+        self.ensure_min_swap_amount(deps.storage, &token_in)?;
+
+        let swap_fee =
+            self.swap_fee_on_exact_in(deps.as_ref(), Some(&sender), &token_in, token_out_denom)?;
+        let token_in_with_fee = token_in.clone();
+        let token_in = Coin::new(
+            token_in.amount.checked_sub(swap_fee.amount)?.u128(),
+            token_in.denom,
+        );
+
+        // reject dust swaps that would result in too little, or zero, token out
+        let (_pool, expected_token_out) = self.out_amt_given_in(
+            deps.as_ref(),
+            self.pool.load(deps.storage)?,
+            token_in.clone(),
+            token_out_denom,
+        )?;
+        self.ensure_min_swap_amount(deps.storage, &expected_token_out)?;
+
+        let fee_joining_pool =
+            self.swap_fee_joining_pool(deps.storage, &swap_fee, affiliate.as_ref())?;
+
+        let response = match swap_variant {
+            SwapVariant::TokenToAlloyed => self.swap_tokens_to_alloyed_asset(
+                entrypoint,
+                SwapToAlloyedConstraint::ExactIn {
+                    tokens_in: &[token_in],
+                    token_out_min_amount,
+                },
+                &fee_joining_pool,
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+            SwapVariant::AlloyedToToken => self.swap_alloyed_asset_to_tokens(
+                entrypoint,
+                SwapFromAlloyedConstraint::ExactIn {
+                    token_in_amount: token_in.amount,
+                    token_out_denom,
+                    token_out_min_amount,
+                },
+                burn_target(owner.clone()),
+                sender.clone(),
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+            SwapVariant::TokenToToken => self.swap_non_alloyed_exact_amount_in(
+                token_in,
+                token_out_denom,
+                token_out_min_amount,
+                &fee_joining_pool,
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+        }?;
+
+        // swap fee is reported apart from the token amounts, so that poolmanager can tell it
+        // apart from the taker fee it charges
+        let response = match &response.data {
+            Some(data) => {
+                let data = from_json::<SwapExactAmountInResponseData>(data)?;
+                response.set_data(to_json_binary(&SwapExactAmountInResponseData {
+                    swap_fee_amount: swap_fee.amount,
+                    ..data
+                })?)
+            }
+            None => response,
+        };
+
+        let response = match &owner {
+            Some(owner) => self.move_swap_fee_from_owner(
+                response,
+                &swap_fee,
+                owner,
+                &sender,
+                deps.storage,
+                &env,
+            )?,
+            None => response,
+        };
+
+        let response =
+            self.collect_swap_fee(response, swap_fee.clone(), affiliate, deps.branch(), &env)?;
+
+        self.after_swap(
+            deps.storage,
+            env.block.time,
+            response,
+            &sender,
+            ActivityKind::Swap,
+            &[token_in_with_fee],
+            &[expected_token_out],
+            &[swap_fee],
+        )
+    }
+
+    /// Swap as little `token_in_denom` as possible for exact amount of `token_out`,
+    /// routing to the appropriate swap implementation based on [SwapVariant].
+    /// Swap fee is charged on top of the required token in.
+    /// If `owner` is set, the sender is an operator swapping the owner's alloyed asset,
+    /// which is burned from the owner's account instead of the sent funds.
+    #[allow(clippy::too_many_arguments)]
+    pub fn dispatch_swap_exact_amount_out(
+        &self,
+        entrypoint: Entrypoint,
+        token_in_denom: &str,
+        token_in_max_amount: Uint128,
+        token_out: Coin,
+        affiliate: Option<Affiliate>,
+        owner: Option<Addr>,
+        sender: Addr,
+        recipient: Addr,
+        mut deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        self.trader_allow_list
+            .ensure_can_swap(deps.storage, &sender)?;
+        if let Some(owner) = &owner {
+            self.trader_allow_list
+                .ensure_can_swap(deps.storage, owner)?;
+        }
+
+        let swap_variant = self.swap_variant(token_in_denom, &token_out.denom, deps.as_ref())?;
+        self.ensure_min_swap_amount(deps.storage, &token_out)?;
+
+        let (swap_fee, token_in, _pool) = self.simulate_swap_exact_amount_out(
+            deps.as_ref(),
+            Some(&sender),
+            token_in_denom,
+            token_out.clone(),
+        )?;
+        self.ensure_min_swap_amount(deps.storage, &token_in)?;
+
+        ensure!(
+            token_in.amount <= token_in_max_amount,
+            ContractError::ExcessiveRequiredTokenIn {
+                limit: token_in_max_amount,
+                required: token_in.amount,
+            }
+        );
+
+        let token_in_max_amount = token_in.amount.checked_sub(swap_fee.amount)?;
+
+        let fee_joining_pool =
+            self.swap_fee_joining_pool(deps.storage, &swap_fee, affiliate.as_ref())?;
+
+        let response = match swap_variant {
+            SwapVariant::TokenToAlloyed => self.swap_tokens_to_alloyed_asset(
+                entrypoint,
+                SwapToAlloyedConstraint::ExactOut {
+                    token_in_denom,
+                    token_in_max_amount,
+                    token_out_amount: token_out.amount,
+                },
+                &fee_joining_pool,
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+            SwapVariant::AlloyedToToken => self.swap_alloyed_asset_to_tokens(
+                entrypoint,
+                SwapFromAlloyedConstraint::ExactOut {
+                    tokens_out: &[token_out.clone()],
+                    token_in_max_amount,
+                },
+                burn_target(owner.clone()),
+                sender.clone(),
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+            SwapVariant::TokenToToken => self.swap_non_alloyed_exact_amount_out(
+                token_in_denom,
+                token_in_max_amount,
+                token_out.clone(),
+                &fee_joining_pool,
+                recipient,
+                deps.branch(),
+                env.clone(),
+            ),
+        }?;
+
+        // token in amount reported must include swap fee
+        let response = match response.data {
+            Some(_) => response.set_data(to_json_binary(&SwapExactAmountOutResponseData {
+                token_in_amount: token_in.amount,
+                swap_fee_amount: swap_fee.amount,
+            })?),
+            None => response,
+        };
+
+        let response = match &owner {
+            Some(owner) => self.move_swap_fee_from_owner(
+                response,
+                &swap_fee,
+                owner,
+                &sender,
+                deps.storage,
+                &env,
+            )?,
+            None => response,
+        };
+
+        let response =
+            self.collect_swap_fee(response, swap_fee.clone(), affiliate, deps.branch(), &env)?;
+
+        self.after_swap(
+            deps.storage,
+            env.block.time,
+            response,
+            &sender,
+            ActivityKind::Swap,
+            &[token_in],
+            &[token_out],
+            &[swap_fee],
+        )
+    }
+
+    /// Simulate swapping exact amount of `token_in`, swap fee is deducted from `token_in`.
+    /// Swap fee is waived if `sender` is exempted.
+    /// Returns (swap fee, token out, resulting pool).
+    pub fn simulate_swap_exact_amount_in(
+        &self,
+        deps: Deps,
+        sender: Option<&Addr>,
+        token_in: Coin,
+        token_out_denom: &str,
+    ) -> Result<(Coin, Coin, TransmuterPool), ContractError> {
+        let swap_fee = self.swap_fee_on_exact_in(deps, sender, &token_in, token_out_denom)?;
+        let token_in = Coin::new(
+            token_in.amount.checked_sub(swap_fee.amount)?.u128(),
+            token_in.denom,
+        );
+
+        let pool = self.pool.load(deps.storage)?;
+        let (pool, token_out) = self.out_amt_given_in(deps, pool, token_in, token_out_denom)?;
+        let pool = self.simulate_collect_swap_fee(deps, pool, &swap_fee)?;
+
+        Ok((swap_fee, token_out, pool))
+    }
+
+    /// Simulate swapping for exact amount of `token_out`, swap fee is charged on top of token in.
+    /// Swap fee is waived if `sender` is exempted.
+    /// Returns (swap fee, token in including swap fee, resulting pool).
+    pub fn simulate_swap_exact_amount_out(
+        &self,
+        deps: Deps,
+        sender: Option<&Addr>,
+        token_in_denom: &str,
+        token_out: Coin,
+    ) -> Result<(Coin, Coin, TransmuterPool), ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+        let (updated_pool, token_in) =
+            self.in_amt_given_out(deps, pool.clone(), token_out, token_in_denom.to_string())?;
+
+        let swap_fee_config = self.swap_fee.get_for(deps.storage, sender)?;
+        let rate = swap_fee_rate(&swap_fee_config, &pool, |_| Ok(updated_pool.clone()))?;
+        let token_in_amount_with_fee = token_in_amount_with_fee(rate, token_in.amount)?;
+        let fee_amount = token_in_amount_with_fee.checked_sub(token_in.amount)?;
+
+        let swap_fee = Coin::new(fee_amount.u128(), token_in_denom);
+        let updated_pool = self.simulate_collect_swap_fee(deps, updated_pool, &swap_fee)?;
+
+        Ok((
+            swap_fee,
+            Coin::new(token_in_amount_with_fee.u128(), token_in_denom),
+            updated_pool,
+        ))
+    }
+
+    /// Largest amount of `token_in` that `sender`, if any, can swap for `token_out_denom`
+    /// without exceeding any limiter. Since swapping in more only moves weights further
+    /// in the same direction, the amount is found by bisection.
+    pub fn max_amount_in_within_limits(
+        &self,
+        deps: Deps,
+        env: &Env,
+        sender: Option<&Addr>,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> Result<Uint128, ContractError> {
+        let within_limits = |amount: Uint128| -> Result<bool, ContractError> {
+            let (swap_fee, _token_out, updated_pool) = self.simulate_swap_exact_amount_in(
+                deps,
+                sender,
+                Coin::new(amount.u128(), token_in.denom.as_str()),
+                token_out_denom,
+            )?;
+            let simulation = self.swap_simulation(deps, env, swap_fee, updated_pool)?;
+
+            Ok(simulation
+                .limiter_verdicts
+                .iter()
+                .all(|verdict| verdict.passed))
+        };
+
+        // swapping more than the pool can pay out is not within limits either
+        if within_limits(token_in.amount).unwrap_or(false) {
+            return Ok(token_in.amount);
+        }
+
+        // `low` is always within limits and `high` never is
+        let (mut low, mut high) = (Uint128::zero(), token_in.amount);
+        while high - low > Uint128::one() {
+            let mid = low + (high - low) / Uint128::new(2);
+            if within_limits(mid).unwrap_or(false) {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+
+        Ok(low)
+    }
+
+    /// Largest amount of `token_in_denom` that `sender`, if any, can swap for `token_out_denom`
+    /// right now, bounded by the limiters and the pool balance of `token_out_denom`.
+    /// Minting alloyed asset is not bounded by any balance, so it is bounded by the size
+    /// of the whole pool instead.
+    pub fn max_amount_in(
+        &self,
+        deps: Deps,
+        env: &Env,
+        sender: Option<&Addr>,
+        token_in_denom: &str,
+        token_out_denom: &str,
+    ) -> Result<Uint128, ContractError> {
+        let pool = self.pool.load(deps.storage)?;
+
+        let bound = if token_out_denom == self.alloyed_asset.get_alloyed_denom(deps.storage)? {
+            let normalization_factor = pool
+                .get_pool_asset_by_denom(token_in_denom)?
+                .normalization_factor();
+
+            let mut bound = Uint128::zero();
+            for asset in &pool.pool_assets {
+                bound = bound.checked_add(convert_amount(
+                    asset.amount(),
+                    asset.normalization_factor(),
+                    normalization_factor,
+                    &Rounding::Down,
+                )?)?;
+            }
+            bound
+        } else {
+            let balance = pool.get_pool_asset_by_denom(token_out_denom)?.amount();
+            if balance.is_zero() {
+                return Ok(Uint128::zero());
+            }
+
+            let (_swap_fee, token_in, _pool) = self.simulate_swap_exact_amount_out(
+                deps,
+                sender,
+                token_in_denom,
+                Coin::new(balance.u128(), token_out_denom),
+            )?;
+            token_in.amount
+        };
+
+        if bound.is_zero() {
+            return Ok(Uint128::zero());
+        }
+
+        self.max_amount_in_within_limits(
+            deps,
+            env,
+            sender,
+            &Coin::new(bound.u128(), token_in_denom),
+            token_out_denom,
+        )
+    }
+
+    /// Weights of `pool` to check limiters against after it gets updated,
+    /// `None` if there is no registered limiter so that computing weights can be skipped.
+    fn weights_for_limiters(
+        &self,
+        storage: &dyn Storage,
+        pool: &TransmuterPool,
+    ) -> Result<Option<BTreeMap<String, Decimal>>, ContractError> {
+        if self.limiters.any_registered(storage)? {
+            pool.weights_map().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Check and update limiters against the change from `prev_weights` to the weights of `pool`,
+    /// only if there is any registered limiter and pool assets are not zero.
+    fn check_limits_and_update(
+        &self,
+        storage: &mut dyn Storage,
+        prev_weights: Option<BTreeMap<String, Decimal>>,
+        pool: &TransmuterPool,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let prev_weights = match prev_weights {
+            Some(prev_weights) => prev_weights,
+            None => return Ok(()),
+        };
+
+        if let Some(updated_weights) = pool.weights()? {
+            debug::trace("weights", || {
+                vec![
+                    ("prev_weights", weights_to_string(&prev_weights)),
+                    ("weights", weights_to_string(updated_weights.clone())),
+                ]
+            });
+
+            self.limiters.check_limits_and_update(
+                storage,
+                pair_weights_by_denom(prev_weights, updated_weights),
+                block_time,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Save updated `pool`, then pause the pool if net outflow of any denom within the block
+    /// exceeds the circuit breaker's max outflow. The operation that trips it still goes through.
+    /// Returns the event to be emitted if tripped.
+    fn save_pool_with_circuit_breaker(
+        &self,
+        storage: &mut dyn Storage,
+        env: &Env,
+        pool: &TransmuterPool,
+    ) -> Result<Option<Event>, ContractError> {
+        // pool in storage is still the pool before the operation
+        let prev_pool = self.pool.load(storage)?;
+        self.circuit_breaker
+            .record_block_start(storage, env.block.height, &prev_pool)?;
+        self.weight_checkpoints
+            .record(storage, &prev_pool, env.block.time)?;
+
+        self.pool.save(storage, pool)?;
+
+        match self
+            .circuit_breaker
+            .exceeded_outflow(storage, env.block.height, pool)?
+        {
+            Some((denom, outflow)) => {
+                self.active_status
+                    .deactivate(storage, InactiveReason::CircuitBreaker)?;
+
+                Ok(Some(
+                    Event::new("circuit_breaker_tripped")
+                        .add_attribute("denom", denom)
+                        .add_attribute("outflow", outflow.to_string()),
+                ))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Detailed result of a simulated swap that results in `updated_pool`,
+    /// including whether each limiter would pass or fail.
+    pub fn swap_simulation(
+        &self,
+        deps: Deps,
+        env: &Env,
+        swap_fee: Coin,
+        updated_pool: TransmuterPool,
+    ) -> Result<SwapSimulation, ContractError> {
+        let prev_weights = self.pool.load(deps.storage)?.weights_map()?;
+
+        let (weights, limiter_verdicts) = match updated_pool.weights()? {
+            Some(updated_weights) => {
+                let limiter_verdicts = self.limiters.check_limits(
+                    deps.storage,
+                    pair_weights_by_denom(prev_weights, updated_weights.clone()),
+                    env.block.time,
+                )?;
+                (updated_weights, limiter_verdicts)
+            }
+            None => (vec![], vec![]),
+        };
+
+        Ok(SwapSimulation {
+            pool_balances: updated_pool
+                .pool_assets
+                .iter()
+                .map(|asset| asset.to_coin())
+                .collect(),
+            weights,
+            swap_fee,
+            limiter_verdicts,
+        })
+    }
+
+    /// Pool after collecting `swap_fee`, only changes if the fee is kept in the pool
+    /// and is not alloyed asset, since alloyed asset fee is burned.
+    fn simulate_collect_swap_fee(
+        &self,
+        deps: Deps,
+        mut pool: TransmuterPool,
+        swap_fee: &Coin,
+    ) -> Result<TransmuterPool, ContractError> {
+        if swap_fee.amount.is_zero() {
+            return Ok(pool);
+        }
+
+        if let FeeDestination::Pool = self.swap_fee.get(deps.storage)?.destination {
+            if swap_fee.denom != self.alloyed_asset.get_alloyed_denom(deps.storage)? {
+                pool.join_pool(&[swap_fee.clone()])?;
+            }
+        }
+
+        Ok(pool)
+    }
+
+    /// Swap fee charged on exact amount of `token_in`, at the effective rate of the swap.
+    fn swap_fee_on_exact_in(
+        &self,
+        deps: Deps,
+        sender: Option<&Addr>,
+        token_in: &Coin,
+        token_out_denom: &str,
+    ) -> Result<Coin, ContractError> {
+        let swap_fee_config = self.swap_fee.get_for(deps.storage, sender)?;
+        let pool = self.pool.load(deps.storage)?;
+
+        let rate = swap_fee_rate(&swap_fee_config, &pool, |pool| {
+            self.out_amt_given_in(deps, pool, token_in.clone(), token_out_denom)
+                .map(|(updated_pool, _)| updated_pool)
+        })?;
+        let fee_amount = fee_on_exact_in(rate, token_in.amount)?;
+
+        Ok(Coin::new(fee_amount.u128(), token_in.denom.as_str()))
+    }
+
+    /// Move `swap_fee` in `owner`'s alloyed asset to the contract, so that it gets collected
+    /// as if it was sent along with the token in, spending it from the allowance of `operator`
+    fn move_swap_fee_from_owner(
+        &self,
+        response: Response,
+        swap_fee: &Coin,
+        owner: &Addr,
+        operator: &Addr,
+        storage: &mut dyn Storage,
+        env: &Env,
+    ) -> Result<Response, ContractError> {
+        if swap_fee.amount.is_zero() {
+            return Ok(response);
+        }
+
+        let remaining_allowance =
+            self.operators
+                .spend_allowance(storage, owner, operator, swap_fee.amount)?;
+
+        // total supply is unchanged, so the supply hook is not notified
+        let burn_msg =
+            self.alloyed_asset
+                .burn(storage, &env.contract.address, swap_fee.amount, owner)?;
+        let mint_msg = self.alloyed_asset.mint(
+            storage,
+            &env.contract.address,
+            swap_fee.amount,
+            &env.contract.address,
+        )?;
+
+        Ok(response
+            .add_messages(burn_msg)
+            .add_messages(mint_msg)
+            .add_event(
+                Event::new("operator_allowance_spent")
+                    .add_attribute("owner", owner)
+                    .add_attribute("operator", operator)
+                    .add_attribute("amount", swap_fee.amount)
+                    .add_attribute("remaining_allowance", remaining_allowance),
+            ))
+    }
+
+    /// Accrue collected swap fee to its destination, after paying `affiliate` its share if any.
+    /// If the destination is the pool, the fee is added to pool liquidity,
+    /// or burned in case of alloyed asset, which increases value backing each alloyed asset.
+    /// If the destination is the collector, the fee is kept until the collector withdraws it.
+    fn collect_swap_fee(
+        &self,
+        response: Response,
+        swap_fee: Coin,
+        affiliate: Option<Affiliate>,
+        deps: DepsMut,
+        env: &Env,
+    ) -> Result<Response, ContractError> {
+        let (response, swap_fee) = match affiliate {
+            Some(affiliate) => {
+                let affiliate_fee = affiliate.fee_share(&swap_fee);
+                let remaining_fee = Coin::new(
+                    swap_fee.amount.checked_sub(affiliate_fee.amount)?.u128(),
+                    swap_fee.denom,
+                );
+
+                let response = if affiliate_fee.amount.is_zero() {
+                    response
+                } else {
+                    response
+                        .add_event(fee_rebate_event(&affiliate.address, &affiliate_fee))
+                        .add_message(BankMsg::Send {
+                            to_address: affiliate.address,
+                            amount: vec![affiliate_fee],
+                        })
+                };
+
+                (response, remaining_fee)
+            }
+            None => (response, swap_fee),
+        };
+
+        if swap_fee.amount.is_zero() {
+            return Ok(response);
+        }
+
+        let destination = self.swap_fee.get(deps.storage)?.destination;
+        let response = response.add_events(fee_accrual_events(&[swap_fee.clone()], &destination));
+
+        match destination {
+            FeeDestination::Collector(_) => {
+                self.swap_fee.accrue(deps.storage, &swap_fee)?;
+                Ok(response)
+            }
+            FeeDestination::Pool => {
+                let alloyed_denom = self.alloyed_asset.get_alloyed_denom(deps.storage)?;
+
+                if swap_fee.denom == alloyed_denom {
+                    let burn_amount = swap_fee.amount;
+                    let burn_msg = self.alloyed_asset.burn(
+                        deps.storage,
+                        &env.contract.address,
+                        burn_amount,
+                        &env.contract.address,
+                    )?;
+                    let response = response.add_messages(burn_msg);
+
+                    self.after_alloyed_supply_change(
+                        deps,
+                        response,
+                        AlloyedSupplyChange::Burn {
+                            from: &env.contract.address,
+                        },
+                        burn_amount,
+                    )
+                } else {
+                    // already joined the pool along with the swap,
+                    // see [Self::swap_fee_joining_pool]
+                    Ok(response)
+                }
+            }
+        }
+    }
+
+    /// Part of `swap_fee` that joins pool liquidity once collected, net of `affiliate`'s share.
+    /// It joins the pool along with the swap, so that limiters are checked and updated once
+    /// against the swap and its fee, as in [Self::batch_swap_exact_amount_in].
+    fn swap_fee_joining_pool(
+        &self,
+        storage: &dyn Storage,
+        swap_fee: &Coin,
+        affiliate: Option<&Affiliate>,
+    ) -> Result<Vec<Coin>, ContractError> {
+        let affiliate_fee_amount = affiliate
+            .map(|affiliate| affiliate.fee_share(swap_fee).amount)
+            .unwrap_or_default();
+        let fee_amount = swap_fee.amount.checked_sub(affiliate_fee_amount)?;
+
+        if fee_amount.is_zero() {
+            return Ok(vec![]);
+        }
+
+        // alloyed asset fee is burned instead
+        match self.swap_fee.get(storage)?.destination {
+            FeeDestination::Pool
+                if swap_fee.denom != self.alloyed_asset.get_alloyed_denom(storage)? =>
+            {
+                Ok(vec![Coin::new(fee_amount.u128(), swap_fee.denom.as_str())])
+            }
+            FeeDestination::Pool | FeeDestination::Collector(_) => Ok(vec![]),
+        }
+    }
+
+    /// Swap `constraint`'s tokens in for alloyed asset minted to `mint_to_address`.
+    /// `fee_joining_pool` joins the pool along with tokens in.
     pub fn swap_tokens_to_alloyed_asset(
         &self,
         entrypoint: Entrypoint,
         constraint: SwapToAlloyedConstraint,
+        fee_joining_pool: &[Coin],
         mint_to_address: Addr,
         deps: DepsMut,
         env: Env,
@@ -78,6 +803,7 @@ impl Transmuter<'_> {
                     &entrypoint,
                     &SwapExactAmountInResponseData {
                         token_out_amount: out_amount,
+                        swap_fee_amount: Uint128::zero(),
                     },
                 )?;
 
@@ -105,11 +831,28 @@ impl Transmuter<'_> {
                     &entrypoint,
                     &SwapExactAmountOutResponseData {
                         token_in_amount: in_amount,
+                        swap_fee_amount: Uint128::zero(),
                     },
                 )?;
 
                 (tokens_in, token_out_amount, response)
             }
+
+            SwapToAlloyedConstraint::ExactInAndOut {
+                tokens_in,
+                token_out_amount,
+            } => {
+                // ensure tokens in is worth at least token out so that alloyed asset is not over minted
+                let tokens_in_with_norm_factor =
+                    pool.pair_coins_with_normalization_factor(tokens_in)?;
+                swap_to_alloyed::out_amount_via_exact_in(
+                    tokens_in_with_norm_factor,
+                    token_out_amount,
+                    self.alloyed_asset.get_normalization_factor(deps.storage)?,
+                )?;
+
+                (tokens_in.to_owned(), token_out_amount, response)
+            }
         };
 
         // ensure funds not empty
@@ -124,37 +867,45 @@ impl Transmuter<'_> {
             ContractError::ZeroValueOperation {}
         );
 
-        let prev_weights = pool.weights_map()?;
+        let prev_weights = self.weights_for_limiters(deps.storage, &pool)?;
 
         pool.join_pool(&tokens_in)?;
+        pool.join_pool(fee_joining_pool)?;
 
-        // check and update limiters only if pool assets are not zero
-        if let Some(updated_weights) = pool.weights()? {
-            self.limiters.check_limits_and_update(
-                deps.storage,
-                pair_weights_by_denom(prev_weights, updated_weights),
-                env.block.time,
-            )?;
-        }
+        self.check_limits_and_update(deps.storage, prev_weights, &pool, env.block.time)?;
 
         // no need for cleaning up drained corrupted assets here
         // since this function will only adding more underlying assets
         // rather than removing any of them
 
+        let prev_pool = self.pool.load(deps.storage)?;
+        self.weight_checkpoints
+            .record(deps.storage, &prev_pool, env.block.time)?;
         self.pool.save(deps.storage, &pool)?;
 
-        let alloyed_asset_out = Coin::new(
-            out_amount.u128(),
-            self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-        );
-
-        let response = response.add_message(MsgMint {
-            sender: env.contract.address.to_string(),
-            amount: Some(alloyed_asset_out.into()),
-            mint_to_address: mint_to_address.to_string(),
-        });
+        self.alloyed_supply_cap.ensure_can_mint(
+            deps.storage,
+            env.block.time,
+            self.alloyed_asset.get_total_supply(deps.as_ref())?,
+            out_amount,
+        )?;
 
-        Ok(response)
+        let mint_msg = self.alloyed_asset.mint(
+            deps.storage,
+            &env.contract.address,
+            out_amount,
+            &mint_to_address,
+        )?;
+        let response = response.add_messages(mint_msg);
+
+        self.after_alloyed_supply_change(
+            deps,
+            response,
+            AlloyedSupplyChange::Mint {
+                to: &mint_to_address,
+            },
+            out_amount,
+        )
     }
 
     pub fn swap_alloyed_asset_to_tokens(
@@ -163,6 +914,7 @@ impl Transmuter<'_> {
         constraint: SwapFromAlloyedConstraint,
         burn_target: BurnTarget,
         sender: Addr,
+        recipient: Addr,
         deps: DepsMut,
         env: Env,
     ) -> Result<Response, ContractError> {
@@ -191,6 +943,7 @@ impl Transmuter<'_> {
                     &entrypoint,
                     &SwapExactAmountInResponseData {
                         token_out_amount: out_amount,
+                        swap_fee_amount: Uint128::zero(),
                     },
                 )?;
 
@@ -215,6 +968,7 @@ impl Transmuter<'_> {
                     &entrypoint,
                     &SwapExactAmountOutResponseData {
                         token_in_amount: in_amount,
+                        swap_fee_amount: Uint128::zero(),
                     },
                 )?;
 
@@ -228,7 +982,8 @@ impl Transmuter<'_> {
             ContractError::ZeroValueOperation {}
         );
 
-        let burn_from_address = match burn_target {
+        let mut operator_allowance_event = None;
+        let burn_from_address = match &burn_target {
             BurnTarget::SenderAccount => {
                 // Check if the sender's shares is sufficient to burn
                 let shares = self.alloyed_asset.get_balance(deps.as_ref(), &sender)?;
@@ -243,6 +998,32 @@ impl Transmuter<'_> {
                 Ok::<&Addr, ContractError>(&sender)
             }
 
+            // Sender is an operator, it can only burn as much as the owner has allowed
+            BurnTarget::OwnerAccount { owner } => {
+                let remaining_allowance =
+                    self.operators
+                        .spend_allowance(deps.storage, owner, &sender, in_amount)?;
+                operator_allowance_event = Some(
+                    Event::new("operator_allowance_spent")
+                        .add_attribute("owner", owner)
+                        .add_attribute("operator", &sender)
+                        .add_attribute("amount", in_amount)
+                        .add_attribute("remaining_allowance", remaining_allowance),
+                );
+
+                // Check if the owner's shares is sufficient to burn
+                let shares = self.alloyed_asset.get_balance(deps.as_ref(), owner)?;
+                ensure!(
+                    shares >= in_amount,
+                    ContractError::InsufficientShares {
+                        required: in_amount,
+                        available: shares
+                    }
+                );
+
+                Ok(owner)
+            }
+
             // Burn from the sent funds, funds are guaranteed to be sent via cw-pool mechanism
             // But to defend in depth, we still check the balance of the contract.
             // Theoretically, alloyed asset balance should always remain 0 before any tx since
@@ -270,7 +1051,7 @@ impl Transmuter<'_> {
                 Ok(&env.contract.address)
             }
         }?
-        .to_string();
+        .clone();
 
         let is_force_exit_corrupted_assets = tokens_out.iter().all(|coin| {
             let total_liquidity = pool
@@ -296,56 +1077,65 @@ impl Transmuter<'_> {
                 pool.weights()?.unwrap_or_default(),
             )?;
         } else {
-            let prev_weights = pool.weights_map()?;
+            let prev_weights = self.weights_for_limiters(deps.storage, &pool)?;
 
             pool.exit_pool(&tokens_out)?;
 
-            // check and update limiters only if pool assets are not zero
-            if let Some(updated_weights) = pool.weights()? {
-                self.limiters.check_limits_and_update(
-                    deps.storage,
-                    pair_weights_by_denom(prev_weights, updated_weights),
-                    env.block.time,
-                )?;
-            }
+            self.check_limits_and_update(deps.storage, prev_weights, &pool, env.block.time)?;
         }
 
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
-        self.pool.save(deps.storage, &pool)?;
+        let circuit_breaker_event =
+            self.save_pool_with_circuit_breaker(deps.storage, &env, &pool)?;
 
         let bank_send_msg = BankMsg::Send {
-            to_address: sender.to_string(),
+            to_address: recipient.to_string(),
             amount: tokens_out,
         };
 
-        let alloyed_asset_to_burn = Coin::new(
-            in_amount.u128(),
-            self.alloyed_asset.get_alloyed_denom(deps.storage)?,
-        )
-        .into();
-
         // burn alloyed assets
-        let burn_msg = MsgBurn {
-            sender: env.contract.address.to_string(),
-            amount: Some(alloyed_asset_to_burn),
-            burn_from_address,
+        let burn_msg = self.alloyed_asset.burn(
+            deps.storage,
+            &env.contract.address,
+            in_amount,
+            &burn_from_address,
+        )?;
+
+        let burner = match &burn_target {
+            BurnTarget::OwnerAccount { owner } => owner,
+            BurnTarget::SenderAccount | BurnTarget::SentFunds => &sender,
         };
 
-        Ok(response.add_message(burn_msg).add_message(bank_send_msg))
+        let response = response
+            .add_messages(burn_msg)
+            .add_message(bank_send_msg)
+            .add_events(operator_allowance_event)
+            .add_events(circuit_breaker_event);
+
+        self.after_alloyed_supply_change(
+            deps,
+            response,
+            AlloyedSupplyChange::Burn { from: burner },
+            in_amount,
+        )
     }
 
+    /// Swap exact amount of `token_in` for pool asset `token_out_denom`.
+    /// `fee_joining_pool` joins the pool along with `token_in`.
+    #[allow(clippy::too_many_arguments)]
     pub fn swap_non_alloyed_exact_amount_in(
         &self,
         token_in: Coin,
         token_out_denom: &str,
         token_out_min_amount: Uint128,
-        sender: Addr,
+        fee_joining_pool: &[Coin],
+        recipient: Addr,
         deps: DepsMut,
         env: Env,
     ) -> Result<Response, ContractError> {
         let pool = self.pool.load(deps.storage)?;
-        let prev_weights = pool.weights_map()?;
+        let prev_weights = self.weights_for_limiters(deps.storage, &pool)?;
 
         let (mut pool, actual_token_out) =
             self.out_amt_given_in(deps.as_ref(), pool, token_in, token_out_denom)?;
@@ -359,45 +1149,47 @@ impl Transmuter<'_> {
             }
         );
 
-        // check and update limiters only if pool assets are not zero
-        if let Some(updated_weights) = pool.weights()? {
-            self.limiters.check_limits_and_update(
-                deps.storage,
-                pair_weights_by_denom(prev_weights, updated_weights),
-                env.block.time,
-            )?;
-        }
+        pool.join_pool(fee_joining_pool)?;
+
+        self.check_limits_and_update(deps.storage, prev_weights, &pool, env.block.time)?;
 
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
         // save pool
-        self.pool.save(deps.storage, &pool)?;
+        let circuit_breaker_event =
+            self.save_pool_with_circuit_breaker(deps.storage, &env, &pool)?;
 
-        let send_token_out_to_sender_msg = BankMsg::Send {
-            to_address: sender.to_string(),
+        let send_token_out_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
             amount: vec![actual_token_out.clone()],
         };
 
         let swap_result = SwapExactAmountInResponseData {
             token_out_amount: actual_token_out.amount,
+            swap_fee_amount: Uint128::zero(),
         };
 
         Ok(Response::new()
-            .add_message(send_token_out_to_sender_msg)
+            .add_message(send_token_out_msg)
+            .add_events(circuit_breaker_event)
             .set_data(to_json_binary(&swap_result)?))
     }
 
+    /// Swap as little pool asset `token_in_denom` as possible for exact amount of `token_out`.
+    /// `fee_joining_pool` joins the pool along with the required token in.
+    #[allow(clippy::too_many_arguments)]
     pub fn swap_non_alloyed_exact_amount_out(
         &self,
         token_in_denom: &str,
         token_in_max_amount: Uint128,
         token_out: Coin,
-        sender: Addr,
+        fee_joining_pool: &[Coin],
+        recipient: Addr,
         deps: DepsMut,
         env: Env,
     ) -> Result<Response, ContractError> {
         let pool = self.pool.load(deps.storage)?;
-        let prev_weights = pool.weights_map()?;
+        let prev_weights = self.weights_for_limiters(deps.storage, &pool)?;
 
         let (mut pool, actual_token_in) = self.in_amt_given_out(
             deps.as_ref(),
@@ -414,34 +1206,176 @@ impl Transmuter<'_> {
             }
         );
 
-        // check and update limiters only if pool assets are not zero
-        if let Some(updated_weights) = pool.weights()? {
-            self.limiters.check_limits_and_update(
-                deps.storage,
-                pair_weights_by_denom(prev_weights, updated_weights),
-                env.block.time,
-            )?;
-        }
+        pool.join_pool(fee_joining_pool)?;
+
+        self.check_limits_and_update(deps.storage, prev_weights, &pool, env.block.time)?;
 
         self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
 
         // save pool
-        self.pool.save(deps.storage, &pool)?;
+        let circuit_breaker_event =
+            self.save_pool_with_circuit_breaker(deps.storage, &env, &pool)?;
 
-        let send_token_out_to_sender_msg = BankMsg::Send {
-            to_address: sender.to_string(),
+        let send_token_out_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
             amount: vec![token_out],
         };
 
         let swap_result = SwapExactAmountOutResponseData {
             token_in_amount: actual_token_in.amount,
+            swap_fee_amount: Uint128::zero(),
         };
 
         Ok(Response::new()
-            .add_message(send_token_out_to_sender_msg)
+            .add_message(send_token_out_msg)
+            .add_events(circuit_breaker_event)
             .set_data(to_json_binary(&swap_result)?))
     }
 
+    /// Perform multiple transmutes between pool assets atomically.
+    /// Limiters are checked against the net change in pool composition after all
+    /// operations are applied, rather than after each operation.
+    pub fn batch_swap_exact_amount_in(
+        &self,
+        operations: &[SwapOperation],
+        funds: &[Coin],
+        sender: Addr,
+        recipient: Addr,
+        deps: DepsMut,
+        env: Env,
+    ) -> Result<Response, ContractError> {
+        self.trader_allow_list
+            .ensure_can_swap(deps.storage, &sender)?;
+
+        let mut pool = self.pool.load(deps.storage)?;
+        let prev_weights = self.weights_for_limiters(deps.storage, &pool)?;
+        let swap_fee_config = self.swap_fee.get_for(deps.storage, Some(&sender))?;
+
+        let mut required_funds: BTreeMap<String, Uint128> = BTreeMap::new();
+        let mut tokens_out: BTreeMap<String, Uint128> = BTreeMap::new();
+        let mut fees: BTreeMap<String, Uint128> = BTreeMap::new();
+
+        for operation in operations {
+            // ensure non-zero token_in amount
+            ensure!(
+                operation.token_in.amount > Uint128::zero(),
+                ContractError::ZeroValueOperation {}
+            );
+
+            // only transmutes between pool assets are allowed
+            let swap_variant = self.swap_variant(
+                &operation.token_in.denom,
+                &operation.token_out_denom,
+                deps.as_ref(),
+            )?;
+            ensure!(
+                swap_variant == SwapVariant::TokenToToken,
+                ContractError::AlloyedAssetNotAllowedInBatchSwap {}
+            );
+
+            let rate = swap_fee_rate(&swap_fee_config, &pool, |mut pool| {
+                pool.transmute(
+                    AmountConstraint::exact_in(operation.token_in.amount),
+                    &operation.token_in.denom,
+                    &operation.token_out_denom,
+                )?;
+                Ok(pool)
+            })?;
+            let fee_amount = fee_on_exact_in(rate, operation.token_in.amount)?;
+            let (token_in, token_out) = pool.transmute(
+                AmountConstraint::exact_in(operation.token_in.amount.checked_sub(fee_amount)?),
+                &operation.token_in.denom,
+                &operation.token_out_denom,
+            )?;
+
+            self.ensure_min_swap_amount(deps.storage, &operation.token_in)?;
+            self.ensure_min_swap_amount(deps.storage, &token_out)?;
+
+            ensure!(
+                token_out.amount >= operation.token_out_min_amount,
+                ContractError::InsufficientTokenOut {
+                    min_required: operation.token_out_min_amount,
+                    amount_out: token_out.amount
+                }
+            );
+
+            let required = required_funds.entry(token_in.denom.clone()).or_default();
+            *required = required
+                .checked_add(token_in.amount)?
+                .checked_add(fee_amount)?;
+
+            if !fee_amount.is_zero() {
+                let fee = fees.entry(token_in.denom.clone()).or_default();
+                *fee = fee.checked_add(fee_amount)?;
+
+                // fee is added to the pool before limiters are checked
+                if let FeeDestination::Pool = swap_fee_config.destination {
+                    pool.join_pool(&[Coin::new(fee_amount.u128(), token_in.denom)])?;
+                }
+            }
+
+            let out = tokens_out.entry(token_out.denom).or_default();
+            *out = out.checked_add(token_out.amount)?;
+        }
+
+        // ensure that sent funds exactly cover all operations' token in
+        let sent_funds = funds
+            .iter()
+            .map(|coin| (coin.denom.clone(), coin.amount))
+            .collect::<BTreeMap<_, _>>();
+        ensure!(
+            sent_funds == required_funds,
+            ContractError::FundsMismatch {
+                expected: to_coins(required_funds),
+                actual: funds.to_vec(),
+            }
+        );
+
+        // check and update limiters against the net change
+        self.check_limits_and_update(deps.storage, prev_weights, &pool, env.block.time)?;
+
+        self.clean_up_drained_corrupted_assets(deps.storage, &mut pool)?;
+
+        // save pool
+        let circuit_breaker_event =
+            self.save_pool_with_circuit_breaker(deps.storage, &env, &pool)?;
+
+        let tokens_in = to_coins(required_funds);
+        let tokens_out = to_coins(tokens_out);
+        let fees = to_coins(fees);
+
+        if let FeeDestination::Collector(_) = swap_fee_config.destination {
+            for fee in &fees {
+                self.swap_fee.accrue(deps.storage, fee)?;
+            }
+        }
+
+        let send_tokens_out_msg = BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: tokens_out.clone(),
+        };
+
+        let response = Response::new()
+            .add_message(send_tokens_out_msg)
+            .add_events(circuit_breaker_event)
+            .add_events(fee_accrual_events(&fees, &swap_fee_config.destination))
+            .set_data(to_json_binary(&BatchSwapResponseData {
+                tokens_out: tokens_out.clone(),
+            })?);
+
+        // the batch is reported as a single swap of all its tokens in for all its tokens out
+        self.after_swap(
+            deps.storage,
+            env.block.time,
+            response,
+            &sender,
+            ActivityKind::Swap,
+            &tokens_in,
+            &tokens_out,
+            &fees,
+        )
+    }
+
     pub fn in_amt_given_out(
         &self,
         deps: Deps,
@@ -564,14 +1498,273 @@ impl Transmuter<'_> {
         })
     }
 
-    pub fn ensure_valid_swap_fee(&self, swap_fee: Decimal) -> Result<(), ContractError> {
-        // ensure swap fee is the same as one from get_swap_fee which essentially is always 0
+    /// Standardized event for swaps, joining and exiting pool, emitted as `wasm-transmuter-swap`,
+    /// with the pool weights after the operation. Coins are comma separated, e.g. `100uaaa,200ubbb`.
+    pub fn swap_event(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+        tokens_in: &[Coin],
+        tokens_out: &[Coin],
+        fee: &[Coin],
+    ) -> Result<Event, ContractError> {
+        let weights = weights_to_string(self.pool.load(storage)?.weights()?.unwrap_or_default());
+
+        Ok(Event::new(SWAP_EVENT_TYPE)
+            .add_attribute("sender", sender)
+            .add_attribute("token_in", coins_to_string(tokens_in))
+            .add_attribute("token_out", coins_to_string(tokens_out))
+            .add_attribute("fee", coins_to_string(fee))
+            .add_attribute("weights", weights))
+    }
+
+    /// Record swap volume, add swap event to `response` and call the flow quota and swap hook
+    /// contracts, if registered. The flow quota contract is called with a plain message so that
+    /// it can reject the swap, while the hook is called via submessage that replies on error,
+    /// so that a failing hook contract can't block swaps.
+    #[allow(clippy::too_many_arguments)]
+    pub fn after_swap(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+        response: Response,
+        sender: &Addr,
+        activity_kind: ActivityKind,
+        tokens_in: &[Coin],
+        tokens_out: &[Coin],
+        fee: &[Coin],
+    ) -> Result<Response, ContractError> {
+        self.record_swap_volume(storage, block_time, tokens_in, tokens_out)?;
+
+        if self.account_activities.is_enabled(storage)? {
+            let value = self.alloyed_value(storage, tokens_in)?;
+            self.account_activities
+                .record(storage, sender, activity_kind, value, block_time)?;
+        }
+
+        self.metrics.increment(storage, activity_kind.into())?;
+
+        let response = response
+            .add_event(self.swap_event(storage, sender, tokens_in, tokens_out, fee)?)
+            .add_events(fee_events(fee));
+
+        let quota_msg = FlowQuotaMsg::RecordFlow {
+            sender: sender.to_string(),
+            inflow: tokens_in.to_vec(),
+            outflow: tokens_out.to_vec(),
+        };
+
+        let response = match self.flow_quota.call(storage, &quota_msg)? {
+            Some(msg) => response.add_message(msg),
+            None => response,
+        };
+
+        let hook_msg = SwapHookMsg::AfterSwap {
+            sender: sender.to_string(),
+            tokens_in: tokens_in.to_vec(),
+            tokens_out: tokens_out.to_vec(),
+            fee: fee.to_vec(),
+        };
+
+        Ok(match self.swap_hook.call(storage, &hook_msg)? {
+            Some(msg) => response.add_submessage(SubMsg::reply_on_error(msg, SWAP_HOOK_REPLY_ID)),
+            None => response,
+        })
+    }
+
+    /// Record volume of each denom pair of the swap, valued in alloyed asset amount so that
+    /// volumes of different pairs are comparable. When one side of the swap has multiple tokens,
+    /// as in joining or exiting the pool, each pair is valued by its token on that side,
+    /// otherwise by the token in. Tokens no longer in the pool, like a drained corrupted asset
+    /// that got removed, can't be valued and are not recorded.
+    fn record_swap_volume(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+        tokens_in: &[Coin],
+        tokens_out: &[Coin],
+    ) -> Result<(), ContractError> {
+        let pool = self.pool.load(storage)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(storage)?;
+        let alloyed_normalization_factor = self.alloyed_asset.get_normalization_factor(storage)?;
+
+        let normalization_factor = |denom: &str| {
+            if denom == alloyed_denom {
+                return Some(alloyed_normalization_factor);
+            }
+
+            pool.pool_assets
+                .iter()
+                .find(|asset| asset.denom() == denom)
+                .map(|asset| asset.normalization_factor())
+        };
+
+        for token_in in tokens_in {
+            for token_out in tokens_out {
+                let valued_by = if tokens_out.len() > 1 {
+                    token_out
+                } else {
+                    token_in
+                };
+
+                let Some(valued_by_normalization_factor) = normalization_factor(&valued_by.denom)
+                else {
+                    continue;
+                };
+
+                if valued_by.amount.is_zero() {
+                    continue;
+                }
+
+                let volume = convert_amount(
+                    valued_by.amount,
+                    valued_by_normalization_factor,
+                    alloyed_normalization_factor,
+                    &Rounding::Down,
+                )?;
+
+                self.swap_volumes.record(
+                    storage,
+                    &token_in.denom,
+                    &token_out.denom,
+                    volume,
+                    block_time,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Total value of `tokens` in alloyed asset amount. Tokens no longer in the pool can't be valued
+    /// and are not counted.
+    fn alloyed_value(
+        &self,
+        storage: &dyn Storage,
+        tokens: &[Coin],
+    ) -> Result<Uint128, ContractError> {
+        let pool = self.pool.load(storage)?;
+        let alloyed_denom = self.alloyed_asset.get_alloyed_denom(storage)?;
+        let alloyed_normalization_factor = self.alloyed_asset.get_normalization_factor(storage)?;
+
+        tokens.iter().try_fold(Uint128::zero(), |total, token| {
+            let normalization_factor = if token.denom == alloyed_denom {
+                alloyed_normalization_factor
+            } else {
+                match pool
+                    .pool_assets
+                    .iter()
+                    .find(|asset| asset.denom() == token.denom)
+                {
+                    Some(asset) => asset.normalization_factor(),
+                    None => return Ok(total),
+                }
+            };
+
+            let value = convert_amount(
+                token.amount,
+                normalization_factor,
+                alloyed_normalization_factor,
+                &Rounding::Down,
+            )?;
+
+            Ok(total.checked_add(value)?)
+        })
+    }
+
+    /// Record alloyed asset `amount` minted or burned by the messages in `response` for the
+    /// state diff event, then call the supply hook contract, if registered. Like the swap hook,
+    /// it is called via submessage that replies on error, so that a failing hook contract
+    /// can't block minting or burning.
+    pub fn after_alloyed_supply_change(
+        &self,
+        deps: DepsMut,
+        response: Response,
+        change: AlloyedSupplyChange,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        self.record_alloyed_supply_change(deps.storage, &change, amount)?;
+
+        if self.supply_hook.contract(deps.storage)?.is_none() {
+            return Ok(response);
+        }
+
+        // mint and burn in `response` are not executed yet
+        let supply = self.alloyed_asset.get_total_supply(deps.as_ref())?;
+        let hook_msg = match change {
+            AlloyedSupplyChange::Mint { to } => SupplyHookMsg::AlloyedSupplyChanged {
+                minter: Some(to.to_string()),
+                burner: None,
+                amount,
+                new_supply: supply.checked_add(amount)?,
+            },
+            AlloyedSupplyChange::Burn { from } => SupplyHookMsg::AlloyedSupplyChanged {
+                minter: None,
+                burner: Some(from.to_string()),
+                amount,
+                new_supply: supply.checked_sub(amount)?,
+            },
+        };
+
+        Ok(match self.supply_hook.call(deps.storage, &hook_msg)? {
+            Some(msg) => response.add_submessage(SubMsg::reply_on_error(msg, SUPPLY_HOOK_REPLY_ID)),
+            None => response,
+        })
+    }
+
+    /// Ensure `coin` swapped in or out is not below the minimum swap amount of its denom.
+    /// The minimum is at least 1, so that dust swaps that round to zero are always rejected.
+    pub fn ensure_min_swap_amount(
+        &self,
+        storage: &dyn Storage,
+        coin: &Coin,
+    ) -> Result<(), ContractError> {
+        let min_amount = self
+            .min_swap_amounts
+            .may_load(storage, &coin.denom)?
+            .unwrap_or_default()
+            .max(Uint128::one());
+
+        ensure!(
+            coin.amount >= min_amount,
+            ContractError::SwapAmountBelowMinimum {
+                denom: coin.denom.clone(),
+                min_amount,
+                amount: coin.amount,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Ensure that affiliate, if any, is valid and its share does not exceed the configured cap.
+    pub fn ensure_valid_affiliate(
+        &self,
+        deps: Deps,
+        affiliate: Option<&Affiliate>,
+    ) -> Result<(), ContractError> {
+        match affiliate {
+            Some(affiliate) => affiliate.validate(
+                deps.api,
+                self.swap_fee.get(deps.storage)?.max_affiliate_share_bps,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    pub fn ensure_valid_swap_fee(
+        &self,
+        storage: &dyn Storage,
+        swap_fee: Decimal,
+    ) -> Result<(), ContractError> {
+        // ensure swap fee is the same as one from get_swap_fee
         // in case where the swap fee mismatch, it can cause the pool to be imbalanced
+        let expected = self.swap_fee.get(storage)?.rate;
         ensure_eq!(
             swap_fee,
-            SWAP_FEE,
+            expected,
             ContractError::InvalidSwapFee {
-                expected: SWAP_FEE,
+                expected,
                 actual: swap_fee
             }
         );
@@ -597,6 +1790,78 @@ impl Transmuter<'_> {
     }
 }
 
+/// Ensure that the execution price, defined as amount of token in paid
+/// per unit of token out, does not exceed `max_execution_price`.
+pub fn ensure_execution_price_within_bound(
+    token_in_amount: Uint128,
+    token_out_amount: Uint128,
+    max_execution_price: Decimal,
+) -> Result<(), ContractError> {
+    let execution_price = Decimal::checked_from_ratio(token_in_amount, token_out_amount)?;
+
+    ensure!(
+        execution_price <= max_execution_price,
+        ContractError::ExecutionPriceBoundExceeded {
+            max_execution_price,
+            execution_price,
+        }
+    );
+
+    Ok(())
+}
+
+/// Ensure that the block time has not passed `deadline`, if any.
+pub fn ensure_deadline_not_exceeded(
+    deadline: Option<Timestamp>,
+    block_time: Timestamp,
+) -> Result<(), ContractError> {
+    if let Some(deadline) = deadline {
+        ensure!(
+            block_time <= deadline,
+            ContractError::DeadlineExceeded {
+                deadline,
+                block_time,
+            }
+        );
+    }
+
+    Ok(())
+}
+
+/// Address that receives tokens out, which is `to_address` if set, otherwise `sender`.
+pub fn recipient_or_sender(
+    api: &dyn Api,
+    to_address: Option<String>,
+    sender: &Addr,
+) -> StdResult<Addr> {
+    match to_address {
+        Some(to_address) => api.addr_validate(&to_address),
+        None => Ok(sender.clone()),
+    }
+}
+
+/// Recipient of token out, which is the contract itself if token out is forwarded over IBC,
+/// otherwise `to_address`, or the sender if not set.
+pub fn swap_recipient(
+    api: &dyn Api,
+    env: &Env,
+    to_address: Option<String>,
+    ibc_forward: Option<&IbcForward>,
+    sender: &Addr,
+) -> Result<Addr, ContractError> {
+    match ibc_forward {
+        Some(_) => {
+            ensure!(
+                to_address.is_none(),
+                ContractError::IbcForwardWithToAddress {}
+            );
+
+            Ok(env.contract.address.clone())
+        }
+        None => Ok(recipient_or_sender(api, to_address, sender)?),
+    }
+}
+
 fn pair_weights_by_denom(
     prev_weights: BTreeMap<String, Decimal>,
     updated_weights: Vec<(String, Decimal)>,
@@ -611,6 +1876,34 @@ fn pair_weights_by_denom(
     denom_weight_pairs
 }
 
+/// Comma separated `denom:weight`, e.g. `uaaa:0.6,ubbb:0.4`
+fn weights_to_string<D, W>(weights: impl IntoIterator<Item = (D, W)>) -> String
+where
+    D: Display,
+    W: Display,
+{
+    weights
+        .into_iter()
+        .map(|(denom, weight)| format!("{denom}:{weight}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub(crate) fn coins_to_string(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn to_coins(amounts: BTreeMap<String, Uint128>) -> Vec<Coin> {
+    amounts
+        .into_iter()
+        .map(|(denom, amount)| Coin { denom, amount })
+        .collect()
+}
+
 /// Possible variants of swap, depending on the input and output tokens
 #[derive(PartialEq, Debug)]
 pub enum SwapVariant {
@@ -629,6 +1922,24 @@ pub enum Entrypoint {
     Sudo,
 }
 
+/// Effective swap fee rate of a swap, `swap` is only simulated when dynamic fee is enabled
+/// since the base rate does not depend on the resulting pool.
+fn swap_fee_rate<F>(
+    swap_fee_config: &SwapFeeConfig,
+    pool: &TransmuterPool,
+    swap: F,
+) -> Result<Decimal, ContractError>
+where
+    F: FnOnce(TransmuterPool) -> Result<TransmuterPool, ContractError>,
+{
+    if swap_fee_config.dynamic.is_none() {
+        return Ok(swap_fee_config.rate);
+    }
+
+    let updated_pool = swap(pool.clone())?;
+    swap_fee_config.effective_rate(&pool.weights_map()?, &updated_pool.weights_map()?)
+}
+
 pub fn set_data_if_sudo<T>(
     response: Response,
     entrypoint: &Entrypoint,
@@ -647,12 +1958,65 @@ where
 /// Fixing token in amount makes token amount out varies
 pub struct SwapExactAmountInResponseData {
     pub token_out_amount: Uint128,
+    /// Swap fee charged by the contract, in token in denom, deducted from token in.
+    /// The chain level taker fee is deducted by poolmanager before the swap and is not included.
+    pub swap_fee_amount: Uint128,
 }
 
 #[cw_serde]
 /// Fixing token out amount makes token amount in varies
 pub struct SwapExactAmountOutResponseData {
     pub token_in_amount: Uint128,
+    /// Swap fee charged by the contract, in token in denom, included in token in amount.
+    /// The chain level taker fee is charged by poolmanager on top and is not included.
+    pub swap_fee_amount: Uint128,
+}
+
+impl IbcForward {
+    /// IBC transfer of `token_out`, which must be held by the contract
+    pub fn transfer_msg(self, token_out: Coin, env: &Env) -> Result<IbcMsg, ContractError> {
+        ensure!(
+            !self.channel.is_empty() && !self.receiver.is_empty() && self.timeout_seconds > 0,
+            ContractError::InvalidIbcForward {}
+        );
+
+        Ok(IbcMsg::Transfer {
+            channel_id: self.channel,
+            to_address: self.receiver,
+            amount: token_out,
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(self.timeout_seconds)),
+        })
+    }
+}
+
+/// Single transmute operation within a batch swap
+#[cw_serde]
+pub struct SwapOperation {
+    pub token_in: Coin,
+    pub token_out_denom: String,
+    pub token_out_min_amount: Uint128,
+}
+
+#[cw_serde]
+/// Detailed result of a simulated swap
+pub struct SwapSimulation {
+    /// Pool asset balances after the swap
+    pub pool_balances: Vec<Coin>,
+
+    /// Pool asset weights after the swap
+    pub weights: Vec<(String, Decimal)>,
+
+    /// Swap fee paid
+    pub swap_fee: Coin,
+
+    /// Whether each limiter of the changed assets would pass or fail
+    pub limiter_verdicts: Vec<LimiterVerdict>,
+}
+
+#[cw_serde]
+/// Aggregated tokens out of all operations in a batch swap
+pub struct BatchSwapResponseData {
+    pub tokens_out: Vec<Coin>,
 }
 
 #[derive(Debug)]
@@ -666,6 +2030,12 @@ pub enum SwapToAlloyedConstraint<'a> {
         token_in_max_amount: Uint128,
         token_out_amount: Uint128,
     },
+    /// Exact amount of tokens in for exact amount of alloyed asset out,
+    /// tokens in must be worth at least the alloyed asset out.
+    ExactInAndOut {
+        tokens_in: &'a [Coin],
+        token_out_amount: Uint128,
+    },
 }
 
 #[derive(Debug)]
@@ -681,6 +2051,20 @@ pub enum SwapFromAlloyedConstraint<'a> {
     },
 }
 
+/// Alloyed asset mint or burn that the supply hook contract gets notified of
+pub enum AlloyedSupplyChange<'a> {
+    Mint { to: &'a Addr },
+    Burn { from: &'a Addr },
+}
+
+/// Burn swapped alloyed asset from `owner`'s account if set, otherwise from the sent funds
+fn burn_target(owner: Option<Addr>) -> BurnTarget {
+    match owner {
+        Some(owner) => BurnTarget::OwnerAccount { owner },
+        None => BurnTarget::SentFunds,
+    }
+}
+
 /// Determines where to burn alloyed assets from.
 pub enum BurnTarget {
     /// Burn alloyed asset from the sender's account.
@@ -691,6 +2075,10 @@ pub enum BurnTarget {
     /// This is used when the sender wants to swap tokens for alloyed assets,
     /// since alloyed asset needs to be sent to the contract before swapping.
     SentFunds,
+    /// Burn alloyed asset from the owner's account.
+    /// This is used when the sender is an operator exiting pool or swapping on behalf of the owner,
+    /// deducting the burned amount from the owner's allowance for the sender.
+    OwnerAccount { owner: Addr },
 }
 
 #[cfg(test)]
@@ -703,6 +2091,7 @@ mod tests {
         testing::{mock_dependencies, mock_env, MOCK_CONTRACT_ADDR},
     };
     use itertools::Itertools;
+    use osmosis_std::types::osmosis::tokenfactory::v1beta1::{MsgBurn, MsgMint};
     use rstest::rstest;
 
     #[rstest]
@@ -830,6 +2219,7 @@ mod tests {
         let res = transmuter.swap_tokens_to_alloyed_asset(
             entrypoint,
             constraint,
+            &[],
             mint_to_address,
             deps.as_mut(),
             mock_env(),
@@ -930,9 +2320,10 @@ mod tests {
         #[case] sender: Addr,
         #[case] expected_res: Result<Response, ContractError>,
     ) {
-        let alloyed_holder = match burn_target {
+        let alloyed_holder = match &burn_target {
             BurnTarget::SenderAccount => sender.to_string(),
             BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
+            BurnTarget::OwnerAccount { owner } => owner.to_string(),
         };
 
         let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
@@ -968,6 +2359,7 @@ mod tests {
             entrypoint,
             constraint,
             burn_target,
+            sender.clone(),
             sender,
             deps.as_mut(),
             mock_env(),
@@ -1030,6 +2422,7 @@ mod tests {
             })
             .set_data(to_json_binary(&SwapExactAmountInResponseData {
                 token_out_amount: 1000000000000u128.into(),
+                swap_fee_amount: Uint128::zero(),
             }).unwrap()))
     )]
     #[case(
@@ -1097,6 +2490,7 @@ mod tests {
             })
             .set_data(to_json_binary(&SwapExactAmountOutResponseData {
                 token_in_amount: Uint128::from(110000000000000u128),
+                swap_fee_amount: Uint128::zero(),
             }).unwrap()))
     )]
     #[case(
@@ -1121,6 +2515,7 @@ mod tests {
             })
             .set_data(to_json_binary(&SwapExactAmountOutResponseData {
                 token_in_amount: Uint128::from(105000000000000u128),
+                swap_fee_amount: Uint128::zero(),
             }).unwrap()))
     )]
     fn test_swap_alloyed_asset_to_tokens_with_corrupted_assets(
@@ -1132,9 +2527,10 @@ mod tests {
         #[case] sender: Addr,
         #[case] expected_res: Result<Response, ContractError>,
     ) {
-        let alloyed_holder = match burn_target {
+        let alloyed_holder = match &burn_target {
             BurnTarget::SenderAccount => sender.to_string(),
             BurnTarget::SentFunds => MOCK_CONTRACT_ADDR.to_string(),
+            BurnTarget::OwnerAccount { owner } => owner.to_string(),
         };
 
         let mut deps = cosmwasm_std::testing::mock_dependencies_with_balances(&[(
@@ -1197,6 +2593,7 @@ mod tests {
             entrypoint,
             constraint,
             burn_target,
+            sender.clone(),
             sender,
             deps.as_mut(),
             mock_env(),
@@ -1298,6 +2695,7 @@ mod tests {
                 coin(1000000000000, "denom3"),
                 "denom1",
                 1000000000000u128.into(),
+                &[],
                 deps.api.addr_make("sender"),
                 deps.as_mut(),
                 mock_env(),
@@ -1379,6 +2777,7 @@ mod tests {
                 "denom3",
                 1000000000000u128.into(),
                 coin(1000000000000, "denom1"),
+                &[],
                 deps.api.addr_make("sender"),
                 deps.as_mut(),
                 mock_env(),
@@ -1497,6 +2896,7 @@ mod tests {
             token_in.clone(),
             token_out_denom,
             token_out_min_amount.into(),
+            &[],
             sender,
             deps.as_mut(),
             mock_env(),
@@ -1594,6 +2994,7 @@ mod tests {
             token_in_denom,
             token_in_max_amount.into(),
             token_out,
+            &[],
             sender,
             deps.as_mut(),
             mock_env(),
@@ -1601,4 +3002,61 @@ mod tests {
 
         assert_eq!(res, expected_res);
     }
+
+    #[rstest]
+    #[case(Uint128::new(100), Uint128::new(100), Decimal::one(), Ok(()))]
+    #[case(Uint128::new(99), Uint128::new(100), Decimal::percent(99), Ok(()))]
+    #[case(
+        Uint128::new(100),
+        Uint128::new(99),
+        Decimal::one(),
+        Err(ContractError::ExecutionPriceBoundExceeded {
+            max_execution_price: Decimal::one(),
+            execution_price: Decimal::from_ratio(100u128, 99u128),
+        })
+    )]
+    #[case(
+        Uint128::new(200),
+        Uint128::new(100),
+        Decimal::percent(150),
+        Err(ContractError::ExecutionPriceBoundExceeded {
+            max_execution_price: Decimal::percent(150),
+            execution_price: Decimal::percent(200),
+        })
+    )]
+    fn test_ensure_execution_price_within_bound(
+        #[case] token_in_amount: Uint128,
+        #[case] token_out_amount: Uint128,
+        #[case] max_execution_price: Decimal,
+        #[case] expected: Result<(), ContractError>,
+    ) {
+        assert_eq!(
+            ensure_execution_price_within_bound(
+                token_in_amount,
+                token_out_amount,
+                max_execution_price
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(None, Timestamp::from_seconds(100), Ok(()))]
+    #[case(Some(Timestamp::from_seconds(101)), Timestamp::from_seconds(100), Ok(()))]
+    #[case(Some(Timestamp::from_seconds(100)), Timestamp::from_seconds(100), Ok(()))]
+    #[case(
+        Some(Timestamp::from_seconds(99)),
+        Timestamp::from_seconds(100),
+        Err(ContractError::DeadlineExceeded {
+            deadline: Timestamp::from_seconds(99),
+            block_time: Timestamp::from_seconds(100),
+        })
+    )]
+    fn test_ensure_deadline_not_exceeded(
+        #[case] deadline: Option<Timestamp>,
+        #[case] block_time: Timestamp,
+        #[case] expected: Result<(), ContractError>,
+    ) {
+        assert_eq!(ensure_deadline_not_exceeded(deadline, block_time), expected);
+    }
 }
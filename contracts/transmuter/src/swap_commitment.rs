@@ -0,0 +1,167 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, to_json_vec, Addr, Binary, Coin, StdResult, Storage, Uint128};
+use cw_storage_plus::Map;
+use sha2::{Digest, Sha256};
+
+use crate::ContractError;
+
+/// Swap exact amount in that is committed by its hash and revealed in a later block
+#[cw_serde]
+pub struct SwapIntent {
+    pub sender: String,
+    pub token_in: Coin,
+    pub token_out_denom: String,
+    pub token_out_min_amount: Uint128,
+    /// Random value to prevent guessing the intent from its commitment
+    pub salt: String,
+}
+
+impl SwapIntent {
+    /// sha256 of the intent's JSON encoding
+    pub fn commitment(&self) -> StdResult<Binary> {
+        Ok(Binary::from(Sha256::digest(to_json_vec(self)?).to_vec()))
+    }
+}
+
+/// Block height at which each sender committed each swap intent.
+pub struct SwapCommitments<'a> {
+    commitments: Map<'a, (&'a Addr, &'a [u8]), u64>,
+}
+
+impl<'a> SwapCommitments<'a> {
+    pub const fn new(commitments_namespace: &'a str) -> Self {
+        Self {
+            commitments: Map::new(commitments_namespace),
+        }
+    }
+
+    /// Commit `commitment` of `sender` at `height`, must be a sha256 hash
+    pub fn commit(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        commitment: &Binary,
+        height: u64,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            commitment.len() == 32,
+            ContractError::InvalidSwapCommitment {}
+        );
+
+        self.commitments
+            .save(storage, (sender, commitment.as_slice()), &height)?;
+
+        Ok(())
+    }
+
+    /// Consume commitment of `intent`, which must be committed by its sender before `height`
+    pub fn reveal(
+        &self,
+        storage: &mut dyn Storage,
+        sender: &Addr,
+        intent: &SwapIntent,
+        height: u64,
+    ) -> Result<(), ContractError> {
+        let commitment = intent.commitment()?;
+        let key = (sender, commitment.as_slice());
+
+        let committed_height = self
+            .commitments
+            .may_load(storage, key)?
+            .ok_or(ContractError::SwapCommitmentNotFound {})?;
+
+        ensure!(
+            height > committed_height,
+            ContractError::SwapRevealedTooEarly { committed_height }
+        );
+
+        self.commitments.remove(storage, key);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_commit_and_reveal() {
+        let mut deps = mock_dependencies();
+        let commitments = SwapCommitments::new("swap_commitments");
+
+        let sender = Addr::unchecked("sender");
+        let intent = SwapIntent {
+            sender: sender.to_string(),
+            token_in: Coin::new(1000, "denoma"),
+            token_out_denom: "denomb".to_string(),
+            token_out_min_amount: Uint128::new(1000),
+            salt: "salt".to_string(),
+        };
+
+        // commitment must be a sha256 hash
+        assert_eq!(
+            commitments
+                .commit(&mut deps.storage, &sender, &Binary::from(b"intent"), 1)
+                .unwrap_err(),
+            ContractError::InvalidSwapCommitment {}
+        );
+
+        // not committed
+        assert_eq!(
+            commitments
+                .reveal(&mut deps.storage, &sender, &intent, 2)
+                .unwrap_err(),
+            ContractError::SwapCommitmentNotFound {}
+        );
+
+        commitments
+            .commit(&mut deps.storage, &sender, &intent.commitment().unwrap(), 1)
+            .unwrap();
+
+        // cannot reveal in the same block
+        assert_eq!(
+            commitments
+                .reveal(&mut deps.storage, &sender, &intent, 1)
+                .unwrap_err(),
+            ContractError::SwapRevealedTooEarly {
+                committed_height: 1
+            }
+        );
+
+        // commitment is bound to the sender and the exact intent
+        assert_eq!(
+            commitments
+                .reveal(&mut deps.storage, &Addr::unchecked("other"), &intent, 2)
+                .unwrap_err(),
+            ContractError::SwapCommitmentNotFound {}
+        );
+        assert_eq!(
+            commitments
+                .reveal(
+                    &mut deps.storage,
+                    &sender,
+                    &SwapIntent {
+                        token_out_min_amount: Uint128::new(999),
+                        ..intent.clone()
+                    },
+                    2
+                )
+                .unwrap_err(),
+            ContractError::SwapCommitmentNotFound {}
+        );
+
+        commitments
+            .reveal(&mut deps.storage, &sender, &intent, 2)
+            .unwrap();
+
+        // commitment can only be revealed once
+        assert_eq!(
+            commitments
+                .reveal(&mut deps.storage, &sender, &intent, 3)
+                .unwrap_err(),
+            ContractError::SwapCommitmentNotFound {}
+        );
+    }
+}
@@ -0,0 +1,631 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Api, Coin, Decimal, Empty, Order, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map};
+
+use crate::{
+    asset::{convert_amount, Rounding},
+    ContractError,
+};
+
+pub use crate::msg::Affiliate;
+
+/// Where the collected swap fee goes to
+#[cw_serde]
+pub enum FeeDestination {
+    /// Swap fee is kept in the pool, which accrues value to alloyed asset holders
+    Pool,
+
+    /// Swap fee accrues to the collector address, which withdraws it
+    Collector(Addr),
+}
+
+/// Denominator of basis points
+const BPS_DENOMINATOR: u128 = 10_000;
+
+#[cw_serde]
+pub struct SwapFeeConfig {
+    /// Fraction of token in that is charged as swap fee, must be less than 1
+    pub rate: Decimal,
+
+    /// Destination of the collected swap fee
+    pub destination: FeeDestination,
+
+    /// Imbalance-dependent fee on top of `rate`, disabled if not set
+    pub dynamic: Option<DynamicFee>,
+
+    /// Maximum share of swap fee, in basis points, that can be given to an affiliate
+    #[serde(default)]
+    pub max_affiliate_share_bps: u16,
+}
+
+impl Default for SwapFeeConfig {
+    fn default() -> Self {
+        Self {
+            rate: Decimal::zero(),
+            destination: FeeDestination::Pool,
+            dynamic: None,
+            max_affiliate_share_bps: 0,
+        }
+    }
+}
+
+impl Affiliate {
+    /// Ensure that affiliate address is valid and its share does not exceed the cap
+    pub fn validate(&self, api: &dyn Api, max_share_bps: u16) -> Result<(), ContractError> {
+        api.addr_validate(&self.address)?;
+
+        ensure!(
+            self.share_bps <= max_share_bps,
+            ContractError::AffiliateShareExceeded {
+                max_share_bps,
+                share_bps: self.share_bps,
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Affiliate's share of `swap_fee`, rounded down
+    pub fn fee_share(&self, swap_fee: &Coin) -> Coin {
+        Coin {
+            denom: swap_fee.denom.clone(),
+            amount: swap_fee
+                .amount
+                .multiply_ratio(self.share_bps, BPS_DENOMINATOR),
+        }
+    }
+}
+
+impl SwapFeeConfig {
+    /// Effective swap fee rate for a swap that moves pool weights from `prev_weights`
+    /// to `updated_weights`. Without dynamic fee, this is always `rate`.
+    pub fn effective_rate(
+        &self,
+        prev_weights: &BTreeMap<String, Decimal>,
+        updated_weights: &BTreeMap<String, Decimal>,
+    ) -> Result<Decimal, ContractError> {
+        match &self.dynamic {
+            Some(dynamic) => dynamic.effective_rate(self.rate, prev_weights, updated_weights),
+            None => Ok(self.rate),
+        }
+    }
+}
+
+/// Fee that varies with how much a swap worsens or improves the pool's
+/// deviation from target weights, where target weights are equal weights of all pool assets.
+///
+/// Deviation is measured as the sum of absolute differences between each weight and its target.
+/// Swaps that worsen deviation pay `rate + slope * deviation increase`, capped at `cap`.
+/// Swaps that improve deviation pay `rate - slope * deviation decrease`, floored at zero.
+#[cw_serde]
+pub struct DynamicFee {
+    /// Fee rate change per unit of deviation change
+    pub slope: Decimal,
+
+    /// Maximum effective fee rate, must be at least the base rate and less than 1
+    pub cap: Decimal,
+}
+
+impl DynamicFee {
+    fn effective_rate(
+        &self,
+        base_rate: Decimal,
+        prev_weights: &BTreeMap<String, Decimal>,
+        updated_weights: &BTreeMap<String, Decimal>,
+    ) -> Result<Decimal, ContractError> {
+        let prev_deviation = deviation(prev_weights)?;
+        let updated_deviation = deviation(updated_weights)?;
+
+        if updated_deviation > prev_deviation {
+            let penalty = self
+                .slope
+                .checked_mul(updated_deviation.checked_sub(prev_deviation)?)?;
+            Ok(base_rate.checked_add(penalty)?.min(self.cap))
+        } else {
+            let discount = self
+                .slope
+                .checked_mul(prev_deviation.checked_sub(updated_deviation)?)?;
+            Ok(base_rate.saturating_sub(discount))
+        }
+    }
+}
+
+/// Sum of absolute differences between each weight and the equal target weight.
+/// Empty weights (pool with no liquidity) has zero deviation.
+fn deviation(weights: &BTreeMap<String, Decimal>) -> Result<Decimal, ContractError> {
+    if weights.is_empty() {
+        return Ok(Decimal::zero());
+    }
+
+    let target = Decimal::checked_from_ratio(1u128, weights.len() as u128)?;
+
+    weights
+        .values()
+        .map(|weight| weight.abs_diff(target))
+        .try_fold(Decimal::zero(), |acc, diff| acc.checked_add(diff))
+        .map_err(Into::into)
+}
+
+/// Swap fee charged on exact amount of token in.
+/// Rounds up so that the pool never under-charges.
+pub fn fee_on_exact_in(rate: Decimal, token_in_amount: Uint128) -> Result<Uint128, ContractError> {
+    convert_amount(
+        token_in_amount,
+        Decimal::one().atomics(),
+        rate.atomics(),
+        &Rounding::Up,
+    )
+}
+
+/// Token in amount required, including swap fee, so that
+/// `token_in_amount` remains after the fee is deducted.
+/// Rounds up so that the pool never under-charges.
+pub fn token_in_amount_with_fee(
+    rate: Decimal,
+    token_in_amount: Uint128,
+) -> Result<Uint128, ContractError> {
+    let remaining_rate = Decimal::one().checked_sub(rate)?;
+
+    convert_amount(
+        token_in_amount,
+        remaining_rate.atomics(),
+        Decimal::one().atomics(),
+        &Rounding::Up,
+    )
+}
+
+pub struct SwapFee<'a> {
+    config: Item<'a, SwapFeeConfig>,
+    exempted: Map<'a, &'a Addr, Empty>,
+    /// Map of denom -> swap fee accrued to the collector, not yet withdrawn
+    accrued: Map<'a, &'a str, Uint128>,
+}
+
+impl<'a> SwapFee<'a> {
+    pub const fn new(
+        config_namespace: &'a str,
+        exempted_namespace: &'a str,
+        accrued_namespace: &'a str,
+    ) -> Self {
+        Self {
+            config: Item::new(config_namespace),
+            exempted: Map::new(exempted_namespace),
+            accrued: Map::new(accrued_namespace),
+        }
+    }
+
+    /// Get swap fee config, defaults to zero fee if it has never been set
+    pub fn get(&self, storage: &dyn Storage) -> StdResult<SwapFeeConfig> {
+        self.config.may_load(storage).map(Option::unwrap_or_default)
+    }
+
+    /// Get swap fee config that applies to `sender`.
+    /// Exempted sender is charged no fee, unknown sender is charged as usual.
+    pub fn get_for(
+        &self,
+        storage: &dyn Storage,
+        sender: Option<&Addr>,
+    ) -> StdResult<SwapFeeConfig> {
+        let config = self.get(storage)?;
+
+        match sender {
+            Some(sender) if self.is_exempted(storage, sender) => Ok(SwapFeeConfig {
+                rate: Decimal::zero(),
+                dynamic: None,
+                ..config
+            }),
+            _ => Ok(config),
+        }
+    }
+
+    pub fn is_exempted(&self, storage: &dyn Storage, address: &Addr) -> bool {
+        self.exempted.has(storage, address)
+    }
+
+    pub fn set_exemption(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        exempted: bool,
+    ) -> StdResult<()> {
+        if exempted {
+            self.exempted.save(storage, address, &Empty {})
+        } else {
+            self.exempted.remove(storage, address);
+            Ok(())
+        }
+    }
+
+    pub fn exempted_addresses(&self, storage: &dyn Storage) -> StdResult<Vec<Addr>> {
+        self.exempted
+            .keys(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Accrue `fee` to the collector, until it is withdrawn
+    pub fn accrue(&self, storage: &mut dyn Storage, fee: &Coin) -> Result<(), ContractError> {
+        if fee.amount.is_zero() {
+            return Ok(());
+        }
+
+        self.accrued
+            .update(storage, &fee.denom, |accrued| -> StdResult<_> {
+                Ok(accrued.unwrap_or_default().checked_add(fee.amount)?)
+            })?;
+
+        Ok(())
+    }
+
+    /// Swap fee accrued to the collector and not yet withdrawn, ordered by denom
+    pub fn accrued_fees(&self, storage: &dyn Storage) -> StdResult<Vec<Coin>> {
+        self.accrued
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin::new(amount.u128(), denom)))
+            .collect()
+    }
+
+    /// Rescale swap fee accrued in `denom` by `numerator / denominator`, rounding down
+    pub fn rescale_accrued(
+        &self,
+        storage: &mut dyn Storage,
+        denom: &str,
+        numerator: Uint128,
+        denominator: Uint128,
+    ) -> Result<(), ContractError> {
+        if let Some(accrued) = self.accrued.may_load(storage, denom)? {
+            let accrued = accrued.checked_multiply_ratio(numerator, denominator)?;
+            if accrued.is_zero() {
+                self.accrued.remove(storage, denom);
+            } else {
+                self.accrued.save(storage, denom, &accrued)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take all accrued swap fee out for withdrawal, ordered by denom
+    pub fn take_accrued_fees(&self, storage: &mut dyn Storage) -> StdResult<Vec<Coin>> {
+        let accrued_fees = self.accrued_fees(storage)?;
+        for fee in &accrued_fees {
+            self.accrued.remove(storage, &fee.denom);
+        }
+
+        Ok(accrued_fees)
+    }
+
+    pub fn set(
+        &self,
+        storage: &mut dyn Storage,
+        config: SwapFeeConfig,
+    ) -> Result<(), ContractError> {
+        ensure!(
+            config.rate < Decimal::one(),
+            ContractError::InvalidSwapFeeRate { rate: config.rate }
+        );
+
+        ensure!(
+            u128::from(config.max_affiliate_share_bps) <= BPS_DENOMINATOR,
+            ContractError::InvalidAffiliateShare {
+                share_bps: config.max_affiliate_share_bps
+            }
+        );
+
+        if let Some(dynamic) = &config.dynamic {
+            ensure!(
+                dynamic.cap >= config.rate && dynamic.cap < Decimal::one(),
+                ContractError::InvalidSwapFeeRate { rate: dynamic.cap }
+            );
+        }
+
+        self.config.save(storage, &config).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+    use rstest::rstest;
+
+    #[test]
+    fn test_get_and_set_swap_fee() {
+        let mut deps = mock_dependencies();
+        let swap_fee = SwapFee::new("swap_fee", "swap_fee_exempted", "swap_fee_accrued");
+
+        // default to zero fee
+        assert_eq!(
+            swap_fee.get(&deps.storage).unwrap(),
+            SwapFeeConfig {
+                rate: Decimal::zero(),
+                destination: FeeDestination::Pool,
+                dynamic: None,
+                max_affiliate_share_bps: 0,
+            }
+        );
+
+        let config = SwapFeeConfig {
+            rate: Decimal::percent(1),
+            destination: FeeDestination::Collector(Addr::unchecked("collector")),
+            dynamic: None,
+            max_affiliate_share_bps: 0,
+        };
+        swap_fee.set(&mut deps.storage, config.clone()).unwrap();
+        assert_eq!(swap_fee.get(&deps.storage).unwrap(), config);
+
+        // rate must be less than 1
+        let err = swap_fee
+            .set(
+                &mut deps.storage,
+                SwapFeeConfig {
+                    rate: Decimal::one(),
+                    destination: FeeDestination::Pool,
+                    dynamic: None,
+                    max_affiliate_share_bps: 0,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSwapFeeRate {
+                rate: Decimal::one()
+            }
+        );
+        assert_eq!(swap_fee.get(&deps.storage).unwrap(), config);
+
+        // dynamic fee cap must not be less than base rate
+        let err = swap_fee
+            .set(
+                &mut deps.storage,
+                SwapFeeConfig {
+                    rate: Decimal::percent(1),
+                    destination: FeeDestination::Pool,
+                    dynamic: Some(DynamicFee {
+                        slope: Decimal::one(),
+                        cap: Decimal::permille(5),
+                    }),
+                    max_affiliate_share_bps: 0,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSwapFeeRate {
+                rate: Decimal::permille(5)
+            }
+        );
+
+        // dynamic fee cap must be less than 1
+        let err = swap_fee
+            .set(
+                &mut deps.storage,
+                SwapFeeConfig {
+                    rate: Decimal::percent(1),
+                    destination: FeeDestination::Pool,
+                    dynamic: Some(DynamicFee {
+                        slope: Decimal::one(),
+                        cap: Decimal::one(),
+                    }),
+                    max_affiliate_share_bps: 0,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ContractError::InvalidSwapFeeRate {
+                rate: Decimal::one()
+            }
+        );
+        assert_eq!(swap_fee.get(&deps.storage).unwrap(), config);
+    }
+
+    #[test]
+    fn test_swap_fee_exemption() {
+        let mut deps = mock_dependencies();
+        let swap_fee = SwapFee::new("swap_fee", "swap_fee_exempted", "swap_fee_accrued");
+
+        let config = SwapFeeConfig {
+            rate: Decimal::percent(1),
+            destination: FeeDestination::Pool,
+            dynamic: Some(DynamicFee {
+                slope: Decimal::one(),
+                cap: Decimal::percent(5),
+            }),
+            max_affiliate_share_bps: 0,
+        };
+        swap_fee.set(&mut deps.storage, config.clone()).unwrap();
+
+        let rebalancer = Addr::unchecked("rebalancer");
+        let user = Addr::unchecked("user");
+
+        assert!(!swap_fee.is_exempted(&deps.storage, &rebalancer));
+        assert_eq!(
+            swap_fee.get_for(&deps.storage, Some(&rebalancer)).unwrap(),
+            config
+        );
+
+        swap_fee
+            .set_exemption(&mut deps.storage, &rebalancer, true)
+            .unwrap();
+
+        assert!(swap_fee.is_exempted(&deps.storage, &rebalancer));
+        assert_eq!(
+            swap_fee.exempted_addresses(&deps.storage).unwrap(),
+            vec![rebalancer.clone()]
+        );
+        assert_eq!(
+            swap_fee.get_for(&deps.storage, Some(&rebalancer)).unwrap(),
+            SwapFeeConfig {
+                rate: Decimal::zero(),
+                destination: FeeDestination::Pool,
+                dynamic: None,
+                max_affiliate_share_bps: 0,
+            }
+        );
+        assert_eq!(
+            swap_fee.get_for(&deps.storage, Some(&user)).unwrap(),
+            config
+        );
+        assert_eq!(swap_fee.get_for(&deps.storage, None).unwrap(), config);
+
+        swap_fee
+            .set_exemption(&mut deps.storage, &rebalancer, false)
+            .unwrap();
+
+        assert!(!swap_fee.is_exempted(&deps.storage, &rebalancer));
+        assert_eq!(
+            swap_fee.exempted_addresses(&deps.storage).unwrap(),
+            Vec::<Addr>::new()
+        );
+        assert_eq!(
+            swap_fee.get_for(&deps.storage, Some(&rebalancer)).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn test_accrued_fees() {
+        let mut deps = mock_dependencies();
+        let swap_fee = SwapFee::new("swap_fee", "swap_fee_exempted", "swap_fee_accrued");
+
+        assert_eq!(swap_fee.accrued_fees(&deps.storage).unwrap(), vec![]);
+
+        swap_fee
+            .accrue(&mut deps.storage, &Coin::new(100, "uosmo"))
+            .unwrap();
+        swap_fee
+            .accrue(&mut deps.storage, &Coin::new(0, "uatom"))
+            .unwrap();
+        swap_fee
+            .accrue(&mut deps.storage, &Coin::new(30, "uion"))
+            .unwrap();
+        swap_fee
+            .accrue(&mut deps.storage, &Coin::new(50, "uosmo"))
+            .unwrap();
+
+        assert_eq!(
+            swap_fee.accrued_fees(&deps.storage).unwrap(),
+            vec![Coin::new(30, "uion"), Coin::new(150, "uosmo")]
+        );
+
+        assert_eq!(
+            swap_fee.take_accrued_fees(&mut deps.storage).unwrap(),
+            vec![Coin::new(30, "uion"), Coin::new(150, "uosmo")]
+        );
+        assert_eq!(swap_fee.accrued_fees(&deps.storage).unwrap(), vec![]);
+        assert_eq!(
+            swap_fee.take_accrued_fees(&mut deps.storage).unwrap(),
+            vec![]
+        );
+    }
+
+    #[rstest]
+    #[case(Decimal::zero(), 1000u128, 0u128, 1000u128)]
+    #[case(Decimal::percent(1), 1000u128, 10u128, 1011u128)]
+    #[case(Decimal::permille(3), 1000u128, 3u128, 1004u128)]
+    #[case(Decimal::permille(3), 1u128, 1u128, 2u128)]
+    #[case(Decimal::percent(50), 1000u128, 500u128, 2000u128)]
+    fn test_fee_calculation(
+        #[case] rate: Decimal,
+        #[case] amount: u128,
+        #[case] expected_fee_on_exact_in: u128,
+        #[case] expected_token_in_amount_with_fee: u128,
+    ) {
+        assert_eq!(
+            fee_on_exact_in(rate, Uint128::from(amount)).unwrap(),
+            Uint128::from(expected_fee_on_exact_in)
+        );
+        assert_eq!(
+            token_in_amount_with_fee(rate, Uint128::from(amount)).unwrap(),
+            Uint128::from(expected_token_in_amount_with_fee)
+        );
+    }
+
+    #[rstest]
+    #[case::no_dynamic_fee(
+        None,
+        vec![("a", Decimal::percent(50)), ("b", Decimal::percent(50))],
+        vec![("a", Decimal::percent(60)), ("b", Decimal::percent(40))],
+        Decimal::permille(3)
+    )]
+    #[case::worsen_deviation(
+        Some(DynamicFee { slope: Decimal::percent(5), cap: Decimal::percent(5) }),
+        vec![("a", Decimal::percent(50)), ("b", Decimal::percent(50))],
+        vec![("a", Decimal::percent(60)), ("b", Decimal::percent(40))],
+        Decimal::permille(13)
+    )]
+    #[case::worsen_deviation_capped(
+        Some(DynamicFee { slope: Decimal::one(), cap: Decimal::percent(5) }),
+        vec![("a", Decimal::percent(50)), ("b", Decimal::percent(50))],
+        vec![("a", Decimal::percent(60)), ("b", Decimal::percent(40))],
+        Decimal::percent(5)
+    )]
+    #[case::improve_deviation(
+        Some(DynamicFee { slope: Decimal::percent(1), cap: Decimal::percent(5) }),
+        vec![("a", Decimal::percent(60)), ("b", Decimal::percent(40))],
+        vec![("a", Decimal::percent(55)), ("b", Decimal::percent(45))],
+        Decimal::permille(2)
+    )]
+    #[case::improve_deviation_floored(
+        Some(DynamicFee { slope: Decimal::one(), cap: Decimal::percent(5) }),
+        vec![("a", Decimal::percent(60)), ("b", Decimal::percent(40))],
+        vec![("a", Decimal::percent(50)), ("b", Decimal::percent(50))],
+        Decimal::zero()
+    )]
+    #[case::empty_pool(
+        Some(DynamicFee { slope: Decimal::percent(5), cap: Decimal::percent(10) }),
+        vec![],
+        vec![("a", Decimal::one()), ("b", Decimal::zero())],
+        Decimal::permille(53)
+    )]
+    fn test_effective_rate(
+        #[case] dynamic: Option<DynamicFee>,
+        #[case] prev_weights: Vec<(&str, Decimal)>,
+        #[case] updated_weights: Vec<(&str, Decimal)>,
+        #[case] expected: Decimal,
+    ) {
+        let config = SwapFeeConfig {
+            rate: Decimal::permille(3),
+            destination: FeeDestination::Pool,
+            dynamic,
+            max_affiliate_share_bps: 0,
+        };
+
+        let to_map = |weights: Vec<(&str, Decimal)>| {
+            weights
+                .into_iter()
+                .map(|(denom, weight)| (denom.to_string(), weight))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert_eq!(
+            config
+                .effective_rate(&to_map(prev_weights), &to_map(updated_weights))
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(0, 1000u128, 0u128)]
+    #[case(5000, 1000u128, 500u128)]
+    #[case(10000, 1000u128, 1000u128)]
+    #[case(3333, 10u128, 3u128)]
+    fn test_affiliate_fee_share(
+        #[case] share_bps: u16,
+        #[case] swap_fee_amount: u128,
+        #[case] expected: u128,
+    ) {
+        let affiliate = Affiliate {
+            address: "affiliate".to_string(),
+            share_bps,
+        };
+
+        assert_eq!(
+            affiliate.fee_share(&Coin::new(swap_fee_amount, "uosmo")),
+            Coin::new(expected, "uosmo")
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Addr, Coin, StdResult, Storage, WasmMsg};
+use cw_storage_plus::Item;
+
+/// Message the registered swap hook contract receives after each swap, join and exit pool
+#[cw_serde]
+pub enum SwapHookMsg {
+    AfterSwap {
+        sender: String,
+        tokens_in: Vec<Coin>,
+        tokens_out: Vec<Coin>,
+        fee: Vec<Coin>,
+    },
+}
+
+/// Optional contract that gets notified after each swap, so that external incentive programs
+/// or accounting systems can react on-chain.
+pub struct SwapHook<'a> {
+    contract: Item<'a, Addr>,
+}
+
+impl<'a> SwapHook<'a> {
+    pub const fn new(contract_namespace: &'a str) -> Self {
+        Self {
+            contract: Item::new(contract_namespace),
+        }
+    }
+
+    /// Registered hook contract, `None` if there is none
+    pub fn contract(&self, storage: &dyn Storage) -> StdResult<Option<Addr>> {
+        self.contract.may_load(storage)
+    }
+
+    /// Register hook contract, `None` to unregister
+    pub fn set_contract(&self, storage: &mut dyn Storage, contract: Option<Addr>) -> StdResult<()> {
+        match contract {
+            Some(contract) => self.contract.save(storage, &contract),
+            None => {
+                self.contract.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Message to call the hook contract with `msg`, `None` if there is no hook contract
+    pub fn call(&self, storage: &dyn Storage, msg: &SwapHookMsg) -> StdResult<Option<WasmMsg>> {
+        self.contract(storage)?
+            .map(|contract| {
+                Ok(WasmMsg::Execute {
+                    contract_addr: contract.into_string(),
+                    msg: to_json_binary(msg)?,
+                    funds: vec![],
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_call() {
+        let mut deps = mock_dependencies();
+        let swap_hook = SwapHook::new("swap_hook");
+
+        let msg = SwapHookMsg::AfterSwap {
+            sender: "sender".to_string(),
+            tokens_in: vec![Coin::new(1000, "denoma")],
+            tokens_out: vec![Coin::new(1000, "denomb")],
+            fee: vec![],
+        };
+
+        // no hook registered
+        assert_eq!(swap_hook.call(&deps.storage, &msg).unwrap(), None);
+
+        swap_hook
+            .set_contract(&mut deps.storage, Some(Addr::unchecked("hook")))
+            .unwrap();
+        assert_eq!(
+            swap_hook.call(&deps.storage, &msg).unwrap(),
+            Some(WasmMsg::Execute {
+                contract_addr: "hook".to_string(),
+                msg: to_json_binary(&msg).unwrap(),
+                funds: vec![],
+            })
+        );
+
+        swap_hook.set_contract(&mut deps.storage, None).unwrap();
+        assert_eq!(swap_hook.contract(&deps.storage).unwrap(), None);
+        assert_eq!(swap_hook.call(&deps.storage, &msg).unwrap(), None);
+    }
+}
@@ -0,0 +1,367 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Order, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw_storage_plus::Map;
+
+use crate::{limiter::WindowConfig, ContractError};
+
+/// 24h window with hourly divisions
+pub const DAILY_VOLUME_WINDOW: WindowConfig = WindowConfig {
+    window_size: Uint64::new(24 * 60 * 60 * 1_000_000_000),
+    division_count: Uint64::new(24),
+};
+
+/// 7d window with daily divisions
+pub const WEEKLY_VOLUME_WINDOW: WindowConfig = WindowConfig {
+    window_size: Uint64::new(7 * 24 * 60 * 60 * 1_000_000_000),
+    division_count: Uint64::new(7),
+};
+
+/// Volume swapped within a division of the window
+#[cw_serde]
+pub struct VolumeDivision {
+    pub started_at: Timestamp,
+    pub volume: Uint128,
+}
+
+/// Divisions of both windows for a denom pair
+#[cw_serde]
+#[derive(Default)]
+pub struct VolumeDivisions {
+    pub daily: Vec<VolumeDivision>,
+    pub weekly: Vec<VolumeDivision>,
+}
+
+/// Rolling swap volume of `denom_in` swapped for `denom_out`
+#[cw_serde]
+pub struct SwapVolume {
+    pub denom_in: String,
+    pub denom_out: String,
+    pub volume_24h: Uint128,
+    pub volume_7d: Uint128,
+}
+
+/// Rolling swap volume per denom pair. Like the limiters, each window is split into divisions
+/// so that only a bounded number of data points is kept. A division is counted as a whole
+/// as long as it overlaps the window, so volume can include up to one division before the window.
+pub struct SwapVolumes<'a> {
+    /// Map of (denom in, denom out) -> volume divisions
+    volumes: Map<'a, (&'a str, &'a str), VolumeDivisions>,
+}
+
+impl<'a> SwapVolumes<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            volumes: Map::new(namespace),
+        }
+    }
+
+    /// Add `volume` of `denom_in` swapped for `denom_out` at `block_time`
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        denom_in: &str,
+        denom_out: &str,
+        volume: Uint128,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let mut divisions = self
+            .volumes
+            .may_load(storage, (denom_in, denom_out))?
+            .unwrap_or_default();
+
+        add_volume(
+            &mut divisions.daily,
+            &DAILY_VOLUME_WINDOW,
+            volume,
+            block_time,
+        )?;
+        add_volume(
+            &mut divisions.weekly,
+            &WEEKLY_VOLUME_WINDOW,
+            volume,
+            block_time,
+        )?;
+
+        self.volumes
+            .save(storage, (denom_in, denom_out), &divisions)?;
+
+        Ok(())
+    }
+
+    /// Prune divisions that are out of both windows at `block_time`, which otherwise only get
+    /// pruned when the pair is swapped, dropping pairs with no volume left
+    pub fn prune(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let volumes = self
+            .volumes
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        for ((denom_in, denom_out), divisions) in volumes {
+            let pruned = VolumeDivisions {
+                daily: without_outdated(&divisions.daily, &DAILY_VOLUME_WINDOW, block_time)?,
+                weekly: without_outdated(&divisions.weekly, &WEEKLY_VOLUME_WINDOW, block_time)?,
+            };
+
+            if pruned.daily.is_empty() && pruned.weekly.is_empty() {
+                self.volumes.remove(storage, (&denom_in, &denom_out));
+            } else if pruned != divisions {
+                self.volumes
+                    .save(storage, (&denom_in, &denom_out), &pruned)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rolling volume of every denom pair that has been swapped, as of `block_time`
+    pub fn all(
+        &self,
+        storage: &dyn Storage,
+        block_time: Timestamp,
+    ) -> Result<Vec<SwapVolume>, ContractError> {
+        self.volumes
+            .range(storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .map(|((denom_in, denom_out), divisions)| {
+                Ok(SwapVolume {
+                    denom_in,
+                    denom_out,
+                    volume_24h: sum_volume(&divisions.daily, &DAILY_VOLUME_WINDOW, block_time)?,
+                    volume_7d: sum_volume(&divisions.weekly, &WEEKLY_VOLUME_WINDOW, block_time)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Whether the division ended before the window that ends at `block_time` started
+fn is_outdated(
+    division: &VolumeDivision,
+    window: &WindowConfig,
+    division_size: u64,
+    block_time: Timestamp,
+) -> bool {
+    let ended_at = division.started_at.nanos().saturating_add(division_size);
+    let window_started_at = block_time.nanos().saturating_sub(window.window_size.u64());
+
+    ended_at <= window_started_at
+}
+
+fn without_outdated(
+    divisions: &[VolumeDivision],
+    window: &WindowConfig,
+    block_time: Timestamp,
+) -> Result<Vec<VolumeDivision>, ContractError> {
+    let division_size = window.division_size()?.u64();
+
+    Ok(divisions
+        .iter()
+        .filter(|division| !is_outdated(division, window, division_size, block_time))
+        .cloned()
+        .collect())
+}
+
+fn add_volume(
+    divisions: &mut Vec<VolumeDivision>,
+    window: &WindowConfig,
+    volume: Uint128,
+    block_time: Timestamp,
+) -> Result<(), ContractError> {
+    let division_size = window.division_size()?.u64();
+    divisions.retain(|division| !is_outdated(division, window, division_size, block_time));
+
+    // divisions are aligned to multiples of the division size
+    let started_at = Timestamp::from_nanos(block_time.nanos() - block_time.nanos() % division_size);
+
+    match divisions.last_mut() {
+        Some(latest) if latest.started_at == started_at => {
+            latest.volume = latest.volume.checked_add(volume)?;
+        }
+        _ => divisions.push(VolumeDivision { started_at, volume }),
+    }
+
+    Ok(())
+}
+
+fn sum_volume(
+    divisions: &[VolumeDivision],
+    window: &WindowConfig,
+    block_time: Timestamp,
+) -> Result<Uint128, ContractError> {
+    let division_size = window.division_size()?.u64();
+
+    divisions
+        .iter()
+        .filter(|division| !is_outdated(division, window, division_size, block_time))
+        .try_fold(Uint128::zero(), |sum, division| {
+            sum.checked_add(division.volume)
+        })
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    const HOUR: u64 = 60 * 60;
+    const DAY: u64 = 24 * HOUR;
+
+    #[test]
+    fn test_record_and_all() {
+        let mut deps = mock_dependencies();
+        let volumes = SwapVolumes::new("swap_volumes");
+        let start = Timestamp::from_seconds(DAY * 1000);
+
+        assert_eq!(volumes.all(&deps.storage, start), Ok(vec![]));
+
+        volumes
+            .record(&mut deps.storage, "denoma", "denomb", 100u128.into(), start)
+            .unwrap();
+        volumes
+            .record(
+                &mut deps.storage,
+                "denoma",
+                "denomb",
+                50u128.into(),
+                start.plus_seconds(HOUR + 10),
+            )
+            .unwrap();
+        volumes
+            .record(
+                &mut deps.storage,
+                "denomb",
+                "denoma",
+                7u128.into(),
+                start.plus_seconds(2 * HOUR),
+            )
+            .unwrap();
+
+        let volume =
+            |denom_in: &str, denom_out: &str, volume_24h: u128, volume_7d: u128| SwapVolume {
+                denom_in: denom_in.to_string(),
+                denom_out: denom_out.to_string(),
+                volume_24h: volume_24h.into(),
+                volume_7d: volume_7d.into(),
+            };
+
+        // pairs are directional
+        assert_eq!(
+            volumes.all(&deps.storage, start.plus_seconds(2 * HOUR)),
+            Ok(vec![
+                volume("denoma", "denomb", 150, 150),
+                volume("denomb", "denoma", 7, 7)
+            ])
+        );
+
+        // the first hourly division is out of the 24h window
+        assert_eq!(
+            volumes.all(&deps.storage, start.plus_seconds(DAY + HOUR)),
+            Ok(vec![
+                volume("denoma", "denomb", 50, 150),
+                volume("denomb", "denoma", 7, 7)
+            ])
+        );
+
+        // the first daily division is out of the 7d window
+        assert_eq!(
+            volumes.all(&deps.storage, start.plus_seconds(8 * DAY)),
+            Ok(vec![
+                volume("denoma", "denomb", 0, 0),
+                volume("denomb", "denoma", 0, 0)
+            ])
+        );
+
+        // outdated divisions are pruned on record
+        volumes
+            .record(
+                &mut deps.storage,
+                "denoma",
+                "denomb",
+                1u128.into(),
+                start.plus_seconds(8 * DAY),
+            )
+            .unwrap();
+        let divisions = volumes
+            .volumes
+            .load(&deps.storage, ("denoma", "denomb"))
+            .unwrap();
+        assert_eq!(
+            divisions,
+            VolumeDivisions {
+                daily: vec![VolumeDivision {
+                    started_at: start.plus_seconds(8 * DAY),
+                    volume: 1u128.into(),
+                }],
+                weekly: vec![VolumeDivision {
+                    started_at: start.plus_seconds(8 * DAY),
+                    volume: 1u128.into(),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut deps = mock_dependencies();
+        let volumes = SwapVolumes::new("swap_volumes");
+        let start = Timestamp::from_seconds(DAY * 1000);
+
+        volumes
+            .record(&mut deps.storage, "denoma", "denomb", 100u128.into(), start)
+            .unwrap();
+        volumes
+            .record(
+                &mut deps.storage,
+                "denomb",
+                "denoma",
+                7u128.into(),
+                start.plus_seconds(2 * DAY),
+            )
+            .unwrap();
+
+        // only hourly divisions out of the 24h window are pruned
+        volumes
+            .prune(&mut deps.storage, start.plus_seconds(2 * DAY))
+            .unwrap();
+        assert_eq!(
+            volumes
+                .volumes
+                .load(&deps.storage, ("denoma", "denomb"))
+                .unwrap(),
+            VolumeDivisions {
+                daily: vec![],
+                weekly: vec![VolumeDivision {
+                    started_at: start,
+                    volume: 100u128.into(),
+                }],
+            }
+        );
+
+        // pair with no division left is dropped
+        volumes
+            .prune(&mut deps.storage, start.plus_seconds(8 * DAY))
+            .unwrap();
+        assert_eq!(
+            volumes
+                .volumes
+                .may_load(&deps.storage, ("denoma", "denomb"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            volumes.all(&deps.storage, start.plus_seconds(8 * DAY)),
+            Ok(vec![SwapVolume {
+                denom_in: "denomb".to_string(),
+                denom_out: "denoma".to_string(),
+                volume_24h: Uint128::zero(),
+                volume_7d: 7u128.into(),
+            }])
+        );
+    }
+}
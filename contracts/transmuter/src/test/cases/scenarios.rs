@@ -59,13 +59,22 @@ fn test_join_pool() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
     // failed to join pool with 0 denom
     let err = t
         .contract
-        .execute(&ExecMsg::JoinPool {}, &[], &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &[],
+            &t.accounts["provider_1"],
+        )
         .unwrap_err();
 
     assert_contract_err(ContractError::AtLeastSingleTokenExpected {}, err);
@@ -74,7 +83,11 @@ fn test_join_pool() {
     let tokens_in = vec![Coin::new(1_000, "urandom")];
     let err = t
         .contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap_err();
 
     assert_contract_err(
@@ -89,7 +102,11 @@ fn test_join_pool() {
     let tokens_in = vec![Coin::new(1_000, COSMOS_USDC)];
 
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap();
 
     // check contract balances
@@ -127,7 +144,11 @@ fn test_join_pool() {
     // join pool with multiple correct pool's denom should added to the contract's balance and update state
     let tokens_in = vec![Coin::new(1_000, AXL_USDC), Coin::new(1_000, COSMOS_USDC)];
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_1"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &tokens_in,
+            &t.accounts["provider_1"],
+        )
         .unwrap();
 
     // check contract balances
@@ -165,7 +186,11 @@ fn test_join_pool() {
     // join pool with another provider with multiple correct pool's denom should added to the contract's balance and update state
     let tokens_in = vec![Coin::new(2_000, AXL_USDC), Coin::new(2_000, COSMOS_USDC)];
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider_2"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &tokens_in,
+            &t.accounts["provider_2"],
+        )
         .unwrap();
 
     // check contract balances
@@ -227,6 +252,11 @@ fn test_swap() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -269,7 +299,11 @@ fn test_swap() {
 
     // join pool properly
     t.contract
-        .execute(&ExecMsg::JoinPool {}, &tokens_in, &t.accounts["provider"])
+        .execute(
+            &ExecMsg::JoinPool { deadline: None },
+            &tokens_in,
+            &t.accounts["provider"],
+        )
         .unwrap();
 
     // transmute with incorrect funds should still fail
@@ -489,13 +523,18 @@ fn test_exit_pool() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
     // join pool
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider_1"],
         )
@@ -503,7 +542,7 @@ fn test_exit_pool() {
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider_2"],
         )
@@ -532,6 +571,9 @@ fn test_exit_pool() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(1_500, AXL_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["user"],
@@ -551,6 +593,9 @@ fn test_exit_pool() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(500, AXL_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider_1"],
@@ -599,6 +644,9 @@ fn test_exit_pool() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(1_000, AXL_USDC), Coin::new(99_000, COSMOS_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider_2"],
@@ -645,6 +693,9 @@ fn test_exit_pool() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(1, AXL_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider_2"],
@@ -665,6 +716,9 @@ fn test_exit_pool() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(1, AXL_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider_1"],
@@ -699,6 +753,11 @@ fn test_3_pool_swap() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -709,7 +768,7 @@ fn test_3_pool_swap() {
     // join pool
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(100_000, COSMOS_USDC)],
             &t.accounts["provider"],
         )
@@ -863,6 +922,9 @@ fn test_3_pool_swap() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(1_000, AXL_DAI), Coin::new(99_000, COSMOS_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider"],
@@ -918,6 +980,11 @@ fn test_swap_alloyed_asset() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -975,6 +1042,11 @@ fn test_limiters() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.address()),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -1101,7 +1173,7 @@ fn test_limiters() {
     // join pool - weight = 50:50
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[
                 Coin::new(500_000, AXL_USDC),
                 Coin::new(500_000, COSMOS_USDC),
@@ -1185,6 +1257,9 @@ fn test_limiters() {
         .execute(
             &ExecMsg::ExitPool {
                 tokens_out: vec![Coin::new(200_000, COSMOS_USDC)],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["provider"],
@@ -1204,7 +1279,7 @@ fn test_limiters() {
     let err = t
         .contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(200_000, AXL_USDC)],
             &t.accounts["provider"],
         )
@@ -1317,6 +1392,11 @@ fn test_register_limiter_after_having_liquidity() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: Some(admin.address()),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -31,6 +31,11 @@ fn test_add_new_assets() {
             alloyed_asset_subdenom: "denomx".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -36,6 +36,11 @@ fn test_admin_set_denom_metadata() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .with_admin("admin")
         .build(&app);
@@ -30,6 +30,11 @@ fn test_create_pool() {
             alloyed_asset_subdenom: "denomx".to_string(),
             alloyed_asset_normalization_factor: Uint128::one(),
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
@@ -65,6 +70,6 @@ fn test_create_pool() {
     assert_eq!(total_shares, Uint128::zero());
 
     // get active status
-    let IsActiveResponse { is_active } = t.contract.query(&QueryMsg::IsActive {}).unwrap();
+    let IsActiveResponse { is_active, .. } = t.contract.query(&QueryMsg::IsActive {}).unwrap();
     assert!(is_active);
 }
@@ -98,11 +98,20 @@ fn test_join_pool_with_single_lp_should_update_shares_and_liquidity_properly() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                adopted_alloyed_denom: None,
+                gov_admin: None,
+                pool_registry: None,
+                alloyed_backend: None,
+                pool_creation_fee: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.funds, &t.accounts["provider"])
+            .execute(
+                &ExecMsg::JoinPool { deadline: None },
+                &case.funds,
+                &t.accounts["provider"],
+            )
             .unwrap();
 
         // check if shares are updated
@@ -241,13 +250,22 @@ fn test_join_pool_should_update_shares_and_liquidity_properly() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                adopted_alloyed_denom: None,
+                gov_admin: None,
+                pool_registry: None,
+                alloyed_backend: None,
+                pool_creation_fee: None,
             })
             .build(&app);
 
         for (addr, funds) in case.joins.clone() {
             // join pool
             t.contract
-                .execute(&ExecMsg::JoinPool {}, &funds, &t.accounts[addr])
+                .execute(
+                    &ExecMsg::JoinPool { deadline: None },
+                    &funds,
+                    &t.accounts[addr],
+                )
                 .unwrap();
         }
 
@@ -364,11 +382,20 @@ fn test_exit_pool_less_than_their_shares_should_update_shares_and_liquidity_prop
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                adopted_alloyed_denom: None,
+                gov_admin: None,
+                pool_registry: None,
+                alloyed_backend: None,
+                pool_creation_fee: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.join, &t.accounts["addr1"])
+            .execute(
+                &ExecMsg::JoinPool { deadline: None },
+                &case.join,
+                &t.accounts["addr1"],
+            )
             .unwrap();
 
         // check if shares are updated
@@ -427,6 +454,9 @@ fn test_exit_pool_less_than_their_shares_should_update_shares_and_liquidity_prop
             .execute(
                 &ExecMsg::ExitPool {
                     tokens_out: case.exit.clone(),
+                    deadline: None,
+                    to_address: None,
+                    owner: None,
                 },
                 &[],
                 &t.accounts["addr1"],
@@ -564,11 +594,20 @@ fn test_exit_pool_greater_than_their_shares_should_fail() {
                 alloyed_asset_normalization_factor: Uint128::one(),
                 admin: None,
                 moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+                adopted_alloyed_denom: None,
+                gov_admin: None,
+                pool_registry: None,
+                alloyed_backend: None,
+                pool_creation_fee: None,
             })
             .build(&app);
 
         t.contract
-            .execute(&ExecMsg::JoinPool {}, &case.join, &t.accounts["addr"])
+            .execute(
+                &ExecMsg::JoinPool { deadline: None },
+                &case.join,
+                &t.accounts["addr"],
+            )
             .unwrap();
 
         let err = t
@@ -576,6 +615,9 @@ fn test_exit_pool_greater_than_their_shares_should_fail() {
             .execute(
                 &ExecMsg::ExitPool {
                     tokens_out: case.exit.clone(),
+                    deadline: None,
+                    to_address: None,
+                    owner: None,
                 },
                 &[],
                 &t.accounts["addr"],
@@ -616,12 +658,17 @@ fn test_exit_pool_within_shares_but_over_joined_denom_amount() {
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(&app);
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(100_000_000, "denoma")],
             &t.accounts["instantiator"],
         )
@@ -629,7 +676,7 @@ fn test_exit_pool_within_shares_but_over_joined_denom_amount() {
 
     t.contract
         .execute(
-            &ExecMsg::JoinPool {},
+            &ExecMsg::JoinPool { deadline: None },
             &[Coin::new(200_000_000, "denomb")],
             &t.accounts["addr1"],
         )
@@ -642,6 +689,9 @@ fn test_exit_pool_within_shares_but_over_joined_denom_amount() {
                     Coin::new(100_000_000, "denoma"),
                     Coin::new(100_000_000, "denomb"),
                 ],
+                deadline: None,
+                to_address: None,
+                owner: None,
             },
             &[],
             &t.accounts["addr1"],
@@ -6,7 +6,7 @@ use crate::{
         sv::{InstantiateMsg, QueryMsg},
         GetModeratorResponse, ListAssetConfigsResponse,
     },
-    migrations::v3_2_0::MigrateMsg,
+    migrations::MigrateMsg,
     test::{modules::cosmwasm_pool::CosmwasmPool, test_env::TransmuterContract},
 };
 use cosmwasm_schema::cw_serde;
@@ -191,6 +191,11 @@ fn test_migrate_v3(#[case] from_version: &str) {
         alloyed_asset_normalization_factor: Uint128::new(10),
         admin: Some(signer.address()),
         moderator: signer.address(),
+        adopted_alloyed_denom: None,
+        gov_admin: None,
+        pool_registry: None,
+        alloyed_backend: None,
+        pool_creation_fee: None,
     };
 
     let code_id = 1;
@@ -217,7 +222,10 @@ fn test_migrate_v3(#[case] from_version: &str) {
     let t = TransmuterContract::new(&app, code_id, pool_id, contract_address.clone());
 
     // --- migrate pool ---
-    let migrate_msg = MigrateMsg {};
+    let migrate_msg = MigrateMsg::V3_3_0 {
+        dry_run: false,
+        force: false,
+    };
 
     gov.propose_and_execute(
         MigratePoolContractsProposal::TYPE_URL.to_string(),
@@ -268,7 +276,7 @@ fn test_migrate_v3(#[case] from_version: &str) {
         version,
         cw2::ContractVersion {
             contract: "crates.io:transmuter".to_string(),
-            version: "3.2.0".to_string()
+            version: "3.3.0".to_string()
         }
     );
 }
@@ -71,6 +71,7 @@ fn test_spot_price(liquidity: &[Coin]) {
                 },
                 "denom0".to_string(),
                 "denom0".to_string(),
+                None,
             )
             .unwrap_err(),
         ContractError::SpotPriceQueryFailed {
@@ -87,6 +88,7 @@ fn test_spot_price(liquidity: &[Coin]) {
                 },
                 "random_denom".to_string(),
                 "denom0".to_string(),
+                None,
             )
             .unwrap_err(),
         ContractError::SpotPriceQueryFailed {
@@ -103,6 +105,7 @@ fn test_spot_price(liquidity: &[Coin]) {
                 },
                 "denom1".to_string(),
                 "random_denom".to_string(),
+                None,
             )
             .unwrap_err(),
         ContractError::SpotPriceQueryFailed {
@@ -119,6 +122,7 @@ fn test_spot_price(liquidity: &[Coin]) {
                 },
                 "denom0".to_string(),
                 "denom1".to_string(),
+                None,
             )
             .unwrap()
             .spot_price,
@@ -512,13 +512,18 @@ fn pool_with_single_lp(
             alloyed_asset_normalization_factor: Uint128::one(),
             admin: None,
             moderator: "osmo1cyyzpxplxdzkeea7kwsydadg87357qnahakaks".to_string(),
+            adopted_alloyed_denom: None,
+            gov_admin: None,
+            pool_registry: None,
+            alloyed_backend: None,
+            pool_creation_fee: None,
         })
         .build(app);
 
     if !non_zero_pool_assets.is_empty() {
         t.contract
             .execute(
-                &ExecMsg::JoinPool {},
+                &ExecMsg::JoinPool { deadline: None },
                 &non_zero_pool_assets,
                 &t.accounts["provider"],
             )
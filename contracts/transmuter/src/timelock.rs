@@ -0,0 +1,269 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Decimal, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Item, Map};
+
+use crate::{asset::AssetConfig, param::Param, swap_fee::DynamicFee, ContractError};
+
+/// Sensitive parameter change that has to be queued while the timelock is enabled
+#[cw_serde]
+pub enum TimelockedChange {
+    AddNewAssets {
+        asset_configs: Vec<AssetConfig>,
+    },
+    DeregisterLimiter {
+        denom: String,
+        label: String,
+    },
+    /// Add pool asset whose supply is queried on another chain
+    AddRemoteAsset {
+        asset_config: AssetConfig,
+        connection_id: String,
+        origin_denom: String,
+    },
+    SetSwapFee {
+        swap_fee: Decimal,
+        fee_collector: Option<String>,
+    },
+    SetSwapFeeExemption {
+        address: String,
+        exempted: bool,
+    },
+    SetMaxAffiliateShare {
+        max_affiliate_share_bps: u16,
+    },
+    SetDynamicSwapFee {
+        dynamic_fee: Option<DynamicFee>,
+    },
+    /// Set timelocked parameter in the parameter registry
+    SetParam {
+        param: Param,
+    },
+    /// Roll limiter and fee settings back to a config snapshot
+    RollbackConfig {
+        snapshot_id: u64,
+    },
+    /// Shorten or disable the timelock delay, in seconds
+    SetTimelockDelay {
+        delay: Option<u64>,
+    },
+}
+
+impl TimelockedChange {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TimelockedChange::AddNewAssets { .. } => "add_new_assets",
+            TimelockedChange::DeregisterLimiter { .. } => "deregister_limiter",
+            TimelockedChange::AddRemoteAsset { .. } => "add_remote_asset",
+            TimelockedChange::SetSwapFee { .. } => "set_swap_fee",
+            TimelockedChange::SetSwapFeeExemption { .. } => "set_swap_fee_exemption",
+            TimelockedChange::SetMaxAffiliateShare { .. } => "set_max_affiliate_share",
+            TimelockedChange::SetDynamicSwapFee { .. } => "set_dynamic_swap_fee",
+            TimelockedChange::SetParam { .. } => "set_param",
+            TimelockedChange::RollbackConfig { .. } => "rollback_config",
+            TimelockedChange::SetTimelockDelay { .. } => "set_timelock_delay",
+        }
+    }
+}
+
+#[cw_serde]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: TimelockedChange,
+    /// Change can be executed once block time reaches this
+    pub effective_at: Timestamp,
+}
+
+/// Optional delay between queueing a sensitive parameter change and the change taking effect,
+/// so that pool participants can react, or admin can cancel it, before it does.
+pub struct Timelock<'a> {
+    /// Delay in seconds, timelock is disabled if not set
+    delay: Item<'a, u64>,
+    pending: Map<'a, u64, PendingChange>,
+    next_id: Item<'a, u64>,
+}
+
+impl<'a> Timelock<'a> {
+    pub const fn new(
+        delay_namespace: &'a str,
+        pending_namespace: &'a str,
+        next_id_namespace: &'a str,
+    ) -> Self {
+        Self {
+            delay: Item::new(delay_namespace),
+            pending: Map::new(pending_namespace),
+            next_id: Item::new(next_id_namespace),
+        }
+    }
+
+    /// Timelock delay in seconds, `None` if timelock is disabled
+    pub fn delay(&self, storage: &dyn Storage) -> StdResult<Option<u64>> {
+        self.delay.may_load(storage)
+    }
+
+    /// Set timelock delay in seconds, `None` to disable timelock
+    pub fn set_delay(&self, storage: &mut dyn Storage, delay: Option<u64>) -> StdResult<()> {
+        match delay {
+            Some(delay) => self.delay.save(storage, &delay),
+            None => {
+                self.delay.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    /// Ensure the change can be applied right away, i.e. timelock is disabled
+    pub fn ensure_not_timelocked(&self, storage: &dyn Storage) -> Result<(), ContractError> {
+        ensure!(
+            self.delay(storage)?.is_none(),
+            ContractError::TimelockedChangeMustBeQueued {}
+        );
+
+        Ok(())
+    }
+
+    /// Queue `change` to be effective after the timelock delay from `now`
+    pub fn queue(
+        &self,
+        storage: &mut dyn Storage,
+        change: TimelockedChange,
+        now: Timestamp,
+    ) -> Result<PendingChange, ContractError> {
+        let delay = self
+            .delay(storage)?
+            .ok_or(ContractError::TimelockNotEnabled {})?;
+
+        let id = self.next_id.may_load(storage)?.unwrap_or_default();
+        self.next_id.save(storage, &(id + 1))?;
+
+        let pending_change = PendingChange {
+            id,
+            change,
+            effective_at: now.plus_seconds(delay),
+        };
+        self.pending.save(storage, id, &pending_change)?;
+
+        Ok(pending_change)
+    }
+
+    /// Remove pending change `id` so that it can be applied, it must be effective at `now`
+    pub fn take_effective(
+        &self,
+        storage: &mut dyn Storage,
+        id: u64,
+        now: Timestamp,
+    ) -> Result<PendingChange, ContractError> {
+        let pending_change = self.get(storage, id)?;
+        ensure!(
+            pending_change.effective_at <= now,
+            ContractError::PendingChangeNotEffective {
+                id,
+                effective_at: pending_change.effective_at
+            }
+        );
+
+        self.pending.remove(storage, id);
+
+        Ok(pending_change)
+    }
+
+    pub fn cancel(
+        &self,
+        storage: &mut dyn Storage,
+        id: u64,
+    ) -> Result<PendingChange, ContractError> {
+        let pending_change = self.get(storage, id)?;
+        self.pending.remove(storage, id);
+
+        Ok(pending_change)
+    }
+
+    /// Cancel every pending change
+    pub fn cancel_all(&self, storage: &mut dyn Storage) {
+        self.pending.clear(storage);
+    }
+
+    pub fn get(&self, storage: &dyn Storage, id: u64) -> Result<PendingChange, ContractError> {
+        self.pending
+            .may_load(storage, id)?
+            .ok_or(ContractError::PendingChangeNotFound { id })
+    }
+
+    pub fn list(&self, storage: &dyn Storage) -> StdResult<Vec<PendingChange>> {
+        self.pending
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(_, pending_change)| pending_change))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_queue_and_take_effective() {
+        let mut deps = mock_dependencies();
+        let timelock = Timelock::new("delay", "pending", "next_id");
+        let now = Timestamp::from_seconds(1000);
+
+        let change = TimelockedChange::DeregisterLimiter {
+            denom: "denoma".to_string(),
+            label: "1h".to_string(),
+        };
+
+        // disabled by default
+        timelock.ensure_not_timelocked(&deps.storage).unwrap();
+        assert_eq!(
+            timelock
+                .queue(&mut deps.storage, change.clone(), now)
+                .unwrap_err(),
+            ContractError::TimelockNotEnabled {}
+        );
+
+        timelock.set_delay(&mut deps.storage, Some(3600)).unwrap();
+        assert_eq!(
+            timelock.ensure_not_timelocked(&deps.storage).unwrap_err(),
+            ContractError::TimelockedChangeMustBeQueued {}
+        );
+
+        let pending_change = timelock
+            .queue(&mut deps.storage, change.clone(), now)
+            .unwrap();
+        assert_eq!(
+            pending_change,
+            PendingChange {
+                id: 0,
+                change,
+                effective_at: Timestamp::from_seconds(4600),
+            }
+        );
+
+        // not effective before the delay has passed
+        assert_eq!(
+            timelock
+                .take_effective(&mut deps.storage, 0, Timestamp::from_seconds(4599))
+                .unwrap_err(),
+            ContractError::PendingChangeNotEffective {
+                id: 0,
+                effective_at: Timestamp::from_seconds(4600)
+            }
+        );
+
+        assert_eq!(
+            timelock
+                .take_effective(&mut deps.storage, 0, Timestamp::from_seconds(4600))
+                .unwrap(),
+            pending_change
+        );
+
+        // can only be taken once
+        assert_eq!(
+            timelock
+                .take_effective(&mut deps.storage, 0, Timestamp::from_seconds(4600))
+                .unwrap_err(),
+            ContractError::PendingChangeNotFound { id: 0 }
+        );
+        assert_eq!(timelock.list(&deps.storage).unwrap(), vec![]);
+    }
+}
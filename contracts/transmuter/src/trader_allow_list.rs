@@ -0,0 +1,201 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+/// Which operations are restricted to allow-listed traders.
+/// Everything is open to anyone by default.
+#[cw_serde]
+#[derive(Default)]
+pub struct PermissionedMode {
+    /// Only allow-listed traders can swap, including swaps routed through `poolmanager`
+    pub swap: bool,
+
+    /// Only allow-listed traders can join and exit pool
+    pub join_and_exit: bool,
+}
+
+pub struct TraderAllowList<'a> {
+    mode: Item<'a, PermissionedMode>,
+    allowed: Map<'a, &'a Addr, Empty>,
+}
+
+impl<'a> TraderAllowList<'a> {
+    pub const fn new(mode_namespace: &'a str, allowed_namespace: &'a str) -> Self {
+        Self {
+            mode: Item::new(mode_namespace),
+            allowed: Map::new(allowed_namespace),
+        }
+    }
+
+    /// Get permissioned mode, defaults to open for everyone if it has never been set
+    pub fn mode(&self, storage: &dyn Storage) -> StdResult<PermissionedMode> {
+        self.mode.may_load(storage).map(Option::unwrap_or_default)
+    }
+
+    pub fn set_mode(&self, storage: &mut dyn Storage, mode: &PermissionedMode) -> StdResult<()> {
+        self.mode.save(storage, mode)
+    }
+
+    pub fn is_allowed(&self, storage: &dyn Storage, address: &Addr) -> bool {
+        self.allowed.has(storage, address)
+    }
+
+    pub fn set_allowed(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        allowed: bool,
+    ) -> StdResult<()> {
+        if allowed {
+            self.allowed.save(storage, address, &Empty {})
+        } else {
+            self.allowed.remove(storage, address);
+            Ok(())
+        }
+    }
+
+    pub fn allowed_traders(&self, storage: &dyn Storage) -> StdResult<Vec<Addr>> {
+        self.allowed
+            .keys(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Ensure `sender` can swap under the current permissioned mode
+    pub fn ensure_can_swap(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if self.mode(storage)?.swap {
+            self.ensure_allowed(storage, sender)?;
+        }
+
+        Ok(())
+    }
+
+    /// Ensure `sender` can join or exit pool under the current permissioned mode
+    pub fn ensure_can_join_and_exit(
+        &self,
+        storage: &dyn Storage,
+        sender: &Addr,
+    ) -> Result<(), ContractError> {
+        if self.mode(storage)?.join_and_exit {
+            self.ensure_allowed(storage, sender)?;
+        }
+
+        Ok(())
+    }
+
+    fn ensure_allowed(&self, storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+        ensure!(
+            self.is_allowed(storage, sender),
+            ContractError::TraderNotAllowed {
+                address: sender.clone()
+            }
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_permissioned_mode() {
+        let mut deps = mock_dependencies();
+        let allow_list = TraderAllowList::new("permissioned_mode", "allowed_traders");
+
+        let trader = Addr::unchecked("trader");
+        let someone = Addr::unchecked("someone");
+
+        // open for everyone by default
+        assert_eq!(
+            allow_list.mode(&deps.storage).unwrap(),
+            PermissionedMode {
+                swap: false,
+                join_and_exit: false
+            }
+        );
+        allow_list.ensure_can_swap(&deps.storage, &someone).unwrap();
+        allow_list
+            .ensure_can_join_and_exit(&deps.storage, &someone)
+            .unwrap();
+
+        allow_list
+            .set_allowed(&mut deps.storage, &trader, true)
+            .unwrap();
+        assert_eq!(
+            allow_list.allowed_traders(&deps.storage).unwrap(),
+            vec![trader.clone()]
+        );
+
+        // only swap is permissioned
+        allow_list
+            .set_mode(
+                &mut deps.storage,
+                &PermissionedMode {
+                    swap: true,
+                    join_and_exit: false,
+                },
+            )
+            .unwrap();
+
+        allow_list.ensure_can_swap(&deps.storage, &trader).unwrap();
+        assert_eq!(
+            allow_list
+                .ensure_can_swap(&deps.storage, &someone)
+                .unwrap_err(),
+            ContractError::TraderNotAllowed {
+                address: someone.clone()
+            }
+        );
+        allow_list
+            .ensure_can_join_and_exit(&deps.storage, &someone)
+            .unwrap();
+
+        // join and exit is also permissioned
+        allow_list
+            .set_mode(
+                &mut deps.storage,
+                &PermissionedMode {
+                    swap: true,
+                    join_and_exit: true,
+                },
+            )
+            .unwrap();
+
+        allow_list
+            .ensure_can_join_and_exit(&deps.storage, &trader)
+            .unwrap();
+        assert_eq!(
+            allow_list
+                .ensure_can_join_and_exit(&deps.storage, &someone)
+                .unwrap_err(),
+            ContractError::TraderNotAllowed {
+                address: someone.clone()
+            }
+        );
+
+        // removed trader is no longer allowed
+        allow_list
+            .set_allowed(&mut deps.storage, &trader, false)
+            .unwrap();
+        assert_eq!(
+            allow_list
+                .ensure_can_swap(&deps.storage, &trader)
+                .unwrap_err(),
+            ContractError::TraderNotAllowed {
+                address: trader.clone()
+            }
+        );
+        assert_eq!(
+            allow_list.allowed_traders(&deps.storage).unwrap(),
+            Vec::<Addr>::new()
+        );
+    }
+}
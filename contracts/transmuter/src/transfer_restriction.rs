@@ -0,0 +1,190 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{ensure, Addr, Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+/// How listed addresses restrict alloyed asset transfers,
+/// enforced via tokenfactory's before-send hook.
+#[cw_serde]
+#[derive(Default)]
+pub enum TransferRestrictionMode {
+    /// Alloyed asset can be transferred freely
+    #[default]
+    Unrestricted,
+
+    /// Listed addresses can neither send nor receive alloyed asset
+    Blocklist,
+
+    /// Only listed addresses can receive alloyed asset
+    Allowlist,
+}
+
+impl TransferRestrictionMode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            TransferRestrictionMode::Unrestricted => "unrestricted",
+            TransferRestrictionMode::Blocklist => "blocklist",
+            TransferRestrictionMode::Allowlist => "allowlist",
+        }
+    }
+}
+
+pub struct TransferRestriction<'a> {
+    mode: Item<'a, TransferRestrictionMode>,
+    listed: Map<'a, &'a Addr, Empty>,
+}
+
+impl<'a> TransferRestriction<'a> {
+    pub const fn new(mode_namespace: &'a str, listed_namespace: &'a str) -> Self {
+        Self {
+            mode: Item::new(mode_namespace),
+            listed: Map::new(listed_namespace),
+        }
+    }
+
+    /// Get restriction mode, defaults to unrestricted if it has never been set
+    pub fn mode(&self, storage: &dyn Storage) -> StdResult<TransferRestrictionMode> {
+        self.mode.may_load(storage).map(Option::unwrap_or_default)
+    }
+
+    pub fn set_mode(
+        &self,
+        storage: &mut dyn Storage,
+        mode: &TransferRestrictionMode,
+    ) -> StdResult<()> {
+        self.mode.save(storage, mode)
+    }
+
+    pub fn is_listed(&self, storage: &dyn Storage, address: &Addr) -> bool {
+        self.listed.has(storage, address)
+    }
+
+    pub fn set_listed(
+        &self,
+        storage: &mut dyn Storage,
+        address: &Addr,
+        listed: bool,
+    ) -> StdResult<()> {
+        if listed {
+            self.listed.save(storage, address, &Empty {})
+        } else {
+            self.listed.remove(storage, address);
+            Ok(())
+        }
+    }
+
+    pub fn listed_addresses(&self, storage: &dyn Storage) -> StdResult<Vec<Addr>> {
+        self.listed
+            .keys(storage, None, None, Order::Ascending)
+            .collect()
+    }
+
+    /// Ensure alloyed asset can be transferred from `from` to `to` under the current mode.
+    /// Transfers to `contract` itself are always allowed so that swapping alloyed asset
+    /// for underlying assets keeps working.
+    pub fn ensure_transfer_allowed(
+        &self,
+        storage: &dyn Storage,
+        from: &Addr,
+        to: &Addr,
+        contract: &Addr,
+    ) -> Result<(), ContractError> {
+        match self.mode(storage)? {
+            TransferRestrictionMode::Unrestricted => {}
+            TransferRestrictionMode::Blocklist => {
+                for address in [from, to] {
+                    ensure!(
+                        !self.is_listed(storage, address),
+                        ContractError::AlloyedTransferRestricted {
+                            address: address.clone()
+                        }
+                    );
+                }
+            }
+            TransferRestrictionMode::Allowlist => {
+                ensure!(
+                    to == contract || self.is_listed(storage, to),
+                    ContractError::AlloyedTransferRestricted {
+                        address: to.clone()
+                    }
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_dependencies;
+
+    #[test]
+    fn test_ensure_transfer_allowed() {
+        let mut deps = mock_dependencies();
+        let restriction = TransferRestriction::new("mode", "listed");
+
+        let contract = Addr::unchecked("contract");
+        let alice = Addr::unchecked("alice");
+        let bob = Addr::unchecked("bob");
+
+        // unrestricted by default
+        assert_eq!(
+            restriction.mode(&deps.storage).unwrap(),
+            TransferRestrictionMode::Unrestricted
+        );
+        restriction
+            .set_listed(&mut deps.storage, &alice, true)
+            .unwrap();
+        restriction
+            .ensure_transfer_allowed(&deps.storage, &alice, &bob, &contract)
+            .unwrap();
+
+        // blocklisted address can neither send nor receive
+        restriction
+            .set_mode(&mut deps.storage, &TransferRestrictionMode::Blocklist)
+            .unwrap();
+        for (from, to) in [(&alice, &bob), (&bob, &alice), (&alice, &contract)] {
+            assert_eq!(
+                restriction
+                    .ensure_transfer_allowed(&deps.storage, from, to, &contract)
+                    .unwrap_err(),
+                ContractError::AlloyedTransferRestricted {
+                    address: alice.clone()
+                }
+            );
+        }
+        restriction
+            .ensure_transfer_allowed(&deps.storage, &bob, &contract, &contract)
+            .unwrap();
+
+        // only allowlisted address and the contract can receive
+        restriction
+            .set_mode(&mut deps.storage, &TransferRestrictionMode::Allowlist)
+            .unwrap();
+        restriction
+            .ensure_transfer_allowed(&deps.storage, &bob, &alice, &contract)
+            .unwrap();
+        restriction
+            .ensure_transfer_allowed(&deps.storage, &bob, &contract, &contract)
+            .unwrap();
+        assert_eq!(
+            restriction
+                .ensure_transfer_allowed(&deps.storage, &alice, &bob, &contract)
+                .unwrap_err(),
+            ContractError::AlloyedTransferRestricted {
+                address: bob.clone()
+            }
+        );
+
+        restriction
+            .set_listed(&mut deps.storage, &alice, false)
+            .unwrap();
+        assert_eq!(
+            restriction.listed_addresses(&deps.storage).unwrap(),
+            Vec::<Addr>::new()
+        );
+    }
+}
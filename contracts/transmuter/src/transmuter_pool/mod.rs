@@ -38,6 +38,34 @@ impl TransmuterPool {
         Ok(pool)
     }
 
+    /// Every violation of pool asset count range and denom uniqueness by `denoms`,
+    /// instead of only the first one as `new` does
+    pub fn denom_violations(denoms: &[&str]) -> Vec<ContractError> {
+        let mut violations = vec![];
+
+        let count = Uint64::new(denoms.len() as u64);
+        if count < MIN_POOL_ASSET_DENOMS || count > MAX_POOL_ASSET_DENOMS {
+            violations.push(ContractError::PoolAssetDenomCountOutOfRange {
+                min: MIN_POOL_ASSET_DENOMS,
+                max: MAX_POOL_ASSET_DENOMS,
+                actual: count,
+            });
+        }
+
+        // report each duplicated denom once
+        let mut seen = HashSet::new();
+        let mut duplicated = HashSet::new();
+        for denom in denoms {
+            if !seen.insert(denom) && duplicated.insert(denom) {
+                violations.push(ContractError::DuplicatedPoolAssetDenom {
+                    denom: denom.to_string(),
+                });
+            }
+        }
+
+        violations
+    }
+
     fn ensure_pool_asset_count_within_range(&self) -> Result<(), ContractError> {
         let count = Uint64::new(self.pool_assets.len() as u64);
         ensure!(
@@ -174,6 +202,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_denom_violations() {
+        assert_eq!(TransmuterPool::denom_violations(&["a", "b"]), vec![]);
+        assert_eq!(
+            TransmuterPool::denom_violations(&[]),
+            vec![ContractError::PoolAssetDenomCountOutOfRange {
+                min: MIN_POOL_ASSET_DENOMS,
+                max: MAX_POOL_ASSET_DENOMS,
+                actual: Uint64::new(0),
+            }]
+        );
+
+        // each duplicated denom is reported once
+        assert_eq!(
+            TransmuterPool::denom_violations(&["a", "b", "a", "b", "a", "c"]),
+            vec![
+                ContractError::DuplicatedPoolAssetDenom {
+                    denom: "a".to_string(),
+                },
+                ContractError::DuplicatedPoolAssetDenom {
+                    denom: "b".to_string(),
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_duplicated_denom() {
         let assets = Asset::unchecked_equal_assets(&["a", "a"]);
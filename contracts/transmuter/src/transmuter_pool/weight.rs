@@ -1,6 +1,6 @@
 use std::collections::BTreeMap;
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{ensure, Decimal, Uint128};
 
 use crate::{
     asset::{convert_amount, Rounding},
@@ -55,6 +55,62 @@ impl TransmuterPool {
         Ok(self.weights()?.unwrap_or_default().into_iter().collect())
     }
 
+    /// Amount of `denom_in` that can be swapped in before its weight reaches `target_weight`,
+    /// swapping for `denom_out` if it is a pool asset, or joining the pool if `None`.
+    /// Swapping for another pool asset keeps the total normalized pool value while joining grows it,
+    /// so solving `(value_in + d) / total = w` or `(value_in + d) / (total + d) = w` respectively.
+    /// Swapping is also bounded by the balance of `denom_out`. Swap fee is not accounted for.
+    pub fn amount_in_until_weight(
+        &self,
+        denom_in: &str,
+        denom_out: Option<&str>,
+        target_weight: Decimal,
+    ) -> Result<Uint128, ContractError> {
+        ensure!(
+            target_weight < Decimal::one()
+                || (denom_out.is_some() && target_weight == Decimal::one()),
+            ContractError::UnreachableTargetWeight { target_weight }
+        );
+
+        let std_norm_factor = lcm_from_iter(
+            self.pool_assets
+                .iter()
+                .map(|pool_asset| pool_asset.normalization_factor()),
+        )?;
+        let normalized_asset_values: BTreeMap<String, Uint128> = self
+            .normalized_asset_values(std_norm_factor)?
+            .into_iter()
+            .collect();
+        let total_normalized_pool_value = normalized_asset_values
+            .values()
+            .try_fold(Uint128::zero(), |acc, value| acc.checked_add(*value))?;
+
+        let asset_in = self.get_pool_asset_by_denom(denom_in)?;
+        let value_in = normalized_asset_values[denom_in];
+        let target_value = total_normalized_pool_value
+            .checked_multiply_ratio(target_weight.atomics(), Decimal::one().atomics())?;
+        let value_to_target = target_value.saturating_sub(value_in);
+
+        let normalized_amount_in = match denom_out {
+            Some(denom_out) => {
+                self.get_pool_asset_by_denom(denom_out)?;
+                value_to_target.min(normalized_asset_values[denom_out])
+            }
+            None => value_to_target.checked_multiply_ratio(
+                Decimal::one().atomics(),
+                (Decimal::one() - target_weight).atomics(),
+            )?,
+        };
+
+        // round down so that the weight does not exceed the target
+        convert_amount(
+            normalized_amount_in,
+            std_norm_factor,
+            asset_in.normalization_factor(),
+            &Rounding::Down,
+        )
+    }
+
     fn normalized_asset_values(
         &self,
         std_norm_factor: Uint128,
@@ -189,4 +245,41 @@ mod tests {
         let ratios = pool.weights().unwrap();
         assert_eq!(ratios, None);
     }
+
+    #[rstest]
+    // swapping keeps the total value
+    #[case(Some("whusdc"), Decimal::percent(60), Ok(Uint128::new(2000)))]
+    #[case(Some("whusdc"), Decimal::percent(40), Ok(Uint128::zero()))]
+    // bounded by balance of token out
+    #[case(Some("whusdc"), Decimal::one(), Ok(Uint128::new(6000)))]
+    // joining grows the total value, (4000 + d) / (10000 + d) = 0.6
+    #[case(None, Decimal::percent(60), Ok(Uint128::new(5000)))]
+    #[case(
+        None,
+        Decimal::one(),
+        Err(ContractError::UnreachableTargetWeight { target_weight: Decimal::one() })
+    )]
+    #[case(
+        Some("whusdc"),
+        Decimal::percent(101),
+        Err(ContractError::UnreachableTargetWeight { target_weight: Decimal::percent(101) })
+    )]
+    fn test_amount_in_until_weight(
+        #[case] denom_out: Option<&str>,
+        #[case] target_weight: Decimal,
+        #[case] expected: Result<Uint128, ContractError>,
+    ) {
+        // normalization factors differ, 4000 axlusdc is worth 4_000_000 whusdc
+        let pool = TransmuterPool {
+            pool_assets: vec![
+                Asset::new(4000u128, "axlusdc", 1u128).unwrap(),
+                Asset::new(6_000_000u128, "whusdc", 1000u128).unwrap(),
+            ],
+        };
+
+        assert_eq!(
+            pool.amount_in_until_weight("axlusdc", denom_out, target_weight),
+            expected
+        );
+    }
 }
@@ -0,0 +1,74 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal};
+
+use crate::contract::{
+    CalcInAmtGivenOutResponse, CalcOutAmtGivenInResponse, DashboardResponse,
+    GetAlloyedDenomResponse, GetSwapFeeConfigResponse, GetTotalPoolLiquidityResponse,
+    ListAssetConfigsResponse, SpotPriceResponse,
+};
+
+/// Major queries that can be answered in a versioned response envelope.
+/// Arguments are the same as the unversioned query of the same name.
+#[cw_serde]
+pub enum VersionedQuery {
+    ListAssetConfigs {},
+    GetTotalPoolLiquidity {},
+    GetSwapFeeConfig {},
+    GetAlloyedDenom {},
+    SpotPrice {
+        base_asset_denom: String,
+        quote_asset_denom: String,
+    },
+    CalcOutAmtGivenIn {
+        token_in: Coin,
+        token_out_denom: String,
+        swap_fee: Decimal,
+        sender: Option<String>,
+        detailed: Option<bool>,
+    },
+    CalcInAmtGivenOut {
+        token_out: Coin,
+        token_in_denom: String,
+        swap_fee: Decimal,
+        sender: Option<String>,
+        detailed: Option<bool>,
+    },
+    Dashboard {},
+}
+
+impl VersionedQuery {
+    /// Schema version of the response data, bumped whenever its shape changes
+    pub fn version(&self) -> u32 {
+        match self {
+            VersionedQuery::ListAssetConfigs {} => 1,
+            VersionedQuery::GetTotalPoolLiquidity {} => 1,
+            VersionedQuery::GetSwapFeeConfig {} => 1,
+            VersionedQuery::GetAlloyedDenom {} => 1,
+            VersionedQuery::SpotPrice { .. } => 1,
+            VersionedQuery::CalcOutAmtGivenIn { .. } => 1,
+            VersionedQuery::CalcInAmtGivenOut { .. } => 1,
+            VersionedQuery::Dashboard {} => 1,
+        }
+    }
+}
+
+/// Response data of a [VersionedQuery], tagged by the query it answers
+#[cw_serde]
+pub enum VersionedData {
+    ListAssetConfigs(ListAssetConfigsResponse),
+    GetTotalPoolLiquidity(GetTotalPoolLiquidityResponse),
+    GetSwapFeeConfig(GetSwapFeeConfigResponse),
+    GetAlloyedDenom(GetAlloyedDenomResponse),
+    SpotPrice(SpotPriceResponse),
+    CalcOutAmtGivenIn(CalcOutAmtGivenInResponse),
+    CalcInAmtGivenOut(CalcInAmtGivenOutResponse),
+    Dashboard(DashboardResponse),
+}
+
+/// Response envelope with the schema `version` of `data`, so that integrators can detect
+/// schema changes instead of silently misreading the response
+#[cw_serde]
+pub struct VersionedResponse {
+    pub version: u32,
+    pub data: VersionedData,
+}
@@ -0,0 +1,246 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Order, StdResult, Storage, Timestamp};
+use cw_storage_plus::{Bound, Map};
+
+use crate::{transmuter_pool::TransmuterPool, ContractError};
+
+/// Length of the period each checkpoint covers, in seconds
+pub const WEIGHT_CHECKPOINT_INTERVAL: u64 = 3600;
+
+/// Number of most recent periods kept, older checkpoints are pruned
+pub const MAX_WEIGHT_CHECKPOINTS: u64 = 168;
+
+/// Weights of pool assets at the start of a period
+#[cw_serde]
+pub struct WeightCheckpoint {
+    pub time: Timestamp,
+    pub weights: Vec<(String, Decimal)>,
+}
+
+/// Pruned history of pool asset weights, one checkpoint per period in which the pool changed.
+/// Weights stay the same between a checkpoint and the next one, since the pool did not change.
+pub struct WeightCheckpoints<'a> {
+    /// Map of period -> weights at the start of the period
+    checkpoints: Map<'a, u64, Vec<(String, Decimal)>>,
+}
+
+impl<'a> WeightCheckpoints<'a> {
+    pub const fn new(namespace: &'a str) -> Self {
+        Self {
+            checkpoints: Map::new(namespace),
+        }
+    }
+
+    /// Record weights of `prev_pool`, the pool before it gets changed at `block_time`,
+    /// if it is the first change in the period. Empty pool has no weights to record.
+    pub fn record(
+        &self,
+        storage: &mut dyn Storage,
+        prev_pool: &TransmuterPool,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let period = block_time.seconds() / WEIGHT_CHECKPOINT_INTERVAL;
+
+        if self.checkpoints.has(storage, period) {
+            return Ok(());
+        }
+
+        let Some(weights) = prev_pool.weights()? else {
+            return Ok(());
+        };
+
+        self.checkpoints.save(storage, period, &weights)?;
+
+        self.prune(storage, block_time)
+    }
+
+    /// Prune checkpoints of periods that are no longer kept at `block_time`. Also called on record,
+    /// but checkpoints of a pool that stops changing are only pruned by calling this.
+    pub fn prune(
+        &self,
+        storage: &mut dyn Storage,
+        block_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let period = block_time.seconds() / WEIGHT_CHECKPOINT_INTERVAL;
+
+        if let Some(pruned_period) = period.checked_sub(MAX_WEIGHT_CHECKPOINTS) {
+            let pruned_periods = self
+                .checkpoints
+                .keys(
+                    storage,
+                    None,
+                    Some(Bound::inclusive(pruned_period)),
+                    Order::Ascending,
+                )
+                .collect::<StdResult<Vec<_>>>()?;
+
+            for pruned_period in pruned_periods {
+                self.checkpoints.remove(storage, pruned_period);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checkpoints within `from` and `to` inclusive, oldest first
+    pub fn range(
+        &self,
+        storage: &dyn Storage,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
+    ) -> StdResult<Vec<WeightCheckpoint>> {
+        // checkpoints are at the start of their periods, so periods are rounded up from `from`
+        let min =
+            from.map(|from| Bound::inclusive(from.seconds().div_ceil(WEIGHT_CHECKPOINT_INTERVAL)));
+        let max = to.map(|to| Bound::inclusive(to.seconds() / WEIGHT_CHECKPOINT_INTERVAL));
+
+        self.checkpoints
+            .range(storage, min, max, Order::Ascending)
+            .map(|item| {
+                item.map(|(period, weights)| WeightCheckpoint {
+                    time: Timestamp::from_seconds(period * WEIGHT_CHECKPOINT_INTERVAL),
+                    weights,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use cosmwasm_std::{testing::mock_dependencies, Coin};
+
+    fn pool(amount_a: u128, amount_b: u128) -> TransmuterPool {
+        TransmuterPool {
+            pool_assets: Asset::unchecked_equal_assets_from_coins(&[
+                Coin::new(amount_a, "denoma"),
+                Coin::new(amount_b, "denomb"),
+            ]),
+        }
+    }
+
+    #[test]
+    fn test_record_and_range() {
+        let mut deps = mock_dependencies();
+        let checkpoints = WeightCheckpoints::new("weight_checkpoints");
+        let start = Timestamp::from_seconds(WEIGHT_CHECKPOINT_INTERVAL * 1000);
+
+        // empty pool has no weights
+        checkpoints
+            .record(&mut deps.storage, &pool(0, 0), start)
+            .unwrap();
+        assert_eq!(checkpoints.range(&deps.storage, None, None), Ok(vec![]));
+
+        // only the first change in the period is recorded
+        checkpoints
+            .record(&mut deps.storage, &pool(1, 1), start.plus_seconds(10))
+            .unwrap();
+        checkpoints
+            .record(&mut deps.storage, &pool(3, 1), start.plus_seconds(20))
+            .unwrap();
+        checkpoints
+            .record(
+                &mut deps.storage,
+                &pool(3, 1),
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * 2 + 30),
+            )
+            .unwrap();
+
+        let half = vec![
+            ("denoma".to_string(), Decimal::percent(50)),
+            ("denomb".to_string(), Decimal::percent(50)),
+        ];
+        let three_quarters = vec![
+            ("denoma".to_string(), Decimal::percent(75)),
+            ("denomb".to_string(), Decimal::percent(25)),
+        ];
+        assert_eq!(
+            checkpoints.range(&deps.storage, None, None),
+            Ok(vec![
+                WeightCheckpoint {
+                    time: start,
+                    weights: half.clone(),
+                },
+                WeightCheckpoint {
+                    time: start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * 2),
+                    weights: three_quarters.clone(),
+                },
+            ])
+        );
+
+        // range is inclusive of checkpoint times
+        assert_eq!(
+            checkpoints.range(&deps.storage, Some(start.plus_seconds(1)), None),
+            Ok(vec![WeightCheckpoint {
+                time: start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * 2),
+                weights: three_quarters,
+            }])
+        );
+        assert_eq!(
+            checkpoints.range(
+                &deps.storage,
+                Some(start),
+                Some(start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * 2 - 1))
+            ),
+            Ok(vec![WeightCheckpoint {
+                time: start,
+                weights: half,
+            }])
+        );
+
+        // checkpoints older than the kept periods are pruned
+        checkpoints
+            .record(
+                &mut deps.storage,
+                &pool(1, 1),
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * (MAX_WEIGHT_CHECKPOINTS + 1)),
+            )
+            .unwrap();
+        let times = checkpoints
+            .range(&deps.storage, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|checkpoint| checkpoint.time)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            vec![
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * 2),
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * (MAX_WEIGHT_CHECKPOINTS + 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut deps = mock_dependencies();
+        let checkpoints = WeightCheckpoints::new("weight_checkpoints");
+        let start = Timestamp::from_seconds(WEIGHT_CHECKPOINT_INTERVAL * 1000);
+
+        checkpoints
+            .record(&mut deps.storage, &pool(1, 1), start)
+            .unwrap();
+
+        // kept while the period is within the kept periods, even if the pool doesn't change
+        checkpoints
+            .prune(
+                &mut deps.storage,
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * (MAX_WEIGHT_CHECKPOINTS - 1)),
+            )
+            .unwrap();
+        assert_eq!(
+            checkpoints.range(&deps.storage, None, None).unwrap().len(),
+            1
+        );
+
+        checkpoints
+            .prune(
+                &mut deps.storage,
+                start.plus_seconds(WEIGHT_CHECKPOINT_INTERVAL * MAX_WEIGHT_CHECKPOINTS),
+            )
+            .unwrap();
+        assert_eq!(checkpoints.range(&deps.storage, None, None), Ok(vec![]));
+    }
+}